@@ -0,0 +1,39 @@
+//! Cost of parsing the largest responses, from the bytes of the body as the client does, and through a copy
+//! of the body into a `String` as it did before.
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use serde::de::DeserializeOwned;
+
+use binance::rest_model::{ExchangeInformation, Prices};
+
+static EXCHANGE_INFO: &str = include_str!("../test_data/exchangeInfo.json");
+
+/// Body of `/api/v3/ticker/price` for as many symbols as the spot market lists
+fn all_prices() -> Vec<u8> {
+    let prices: Vec<String> = (0..2500)
+        .map(|i| format!(r#"{{"symbol":"SYM{}USDT","price":"{}.12345678"}}"#, i, i))
+        .collect();
+    format!("[{}]", prices.join(",")).into_bytes()
+}
+
+fn parse<T: DeserializeOwned>(c: &mut Criterion, name: &str, body: &[u8]) {
+    let mut group = c.benchmark_group(name);
+    group.throughput(Throughput::Bytes(body.len() as u64));
+    group.bench_with_input(BenchmarkId::new("from_slice", body.len()), body, |b, body| {
+        b.iter(|| serde_json::from_slice::<T>(black_box(body)).unwrap())
+    });
+    group.bench_with_input(BenchmarkId::new("string_then_from_str", body.len()), body, |b, body| {
+        b.iter(|| {
+            let text = String::from_utf8(black_box(body).to_vec()).unwrap();
+            serde_json::from_str::<T>(&text).unwrap()
+        })
+    });
+    group.finish();
+}
+
+fn large_responses(c: &mut Criterion) {
+    parse::<ExchangeInformation>(c, "exchange_info", EXCHANGE_INFO.as_bytes());
+    parse::<Prices>(c, "all_prices", &all_prices());
+}
+
+criterion_group!(benches, large_responses);
+criterion_main!(benches);
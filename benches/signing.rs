@@ -0,0 +1,37 @@
+//! Cost of building a query, and of building and signing the same query, which is what a public endpoint would
+//! pay if it went through the signed request path.
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+
+use binance::rest_model::PairQuery;
+use binance::signing::{Ed25519Key, HmacKey, SecretKey};
+use binance::util::{build_request_p, build_signed_request_p};
+
+static SECRET: &str = "a secret key of the usual length, sixty four characters long...";
+
+fn query() -> PairQuery {
+    PairQuery {
+        symbol: "BTCUSDT".to_string(),
+    }
+}
+
+fn build_requests(c: &mut Criterion) {
+    c.bench_function("build_request_p", |b| b.iter(|| build_request_p(black_box(query())).unwrap()));
+    c.bench_function("build_signed_request_p", |b| {
+        b.iter(|| build_signed_request_p(black_box(query()), 5000).unwrap())
+    });
+}
+
+fn sign_requests(c: &mut Criterion) {
+    let request = build_signed_request_p(query(), 5000).unwrap();
+    let hmac = SecretKey::parse(SECRET).unwrap();
+    c.bench_function("hmac sign", |b| b.iter(|| hmac.sign(black_box(&request))));
+    // what every request paid before the key was derived once per client
+    c.bench_function("hmac derive and sign", |b| {
+        b.iter(|| HmacKey::new(black_box(SECRET)).sign(black_box(&request)))
+    });
+    let ed25519 = SecretKey::from(Ed25519Key::from_seed(&[7; 32]).unwrap());
+    c.bench_function("ed25519 sign", |b| b.iter(|| ed25519.sign(black_box(&request))));
+}
+
+criterion_group!(benches, build_requests, sign_requests);
+criterion_main!(benches);
@@ -0,0 +1,69 @@
+#[cfg(feature = "coin_futures_api")]
+#[macro_use]
+extern crate tracing;
+
+use env_logger::Builder;
+
+#[tokio::main]
+async fn main() {
+    Builder::new().parse_default_env().init();
+    #[cfg(feature = "coin_futures_api")]
+    general().await;
+    #[cfg(feature = "coin_futures_api")]
+    market_data().await;
+}
+
+#[cfg(feature = "coin_futures_api")]
+async fn general() {
+    use binance::api::*;
+    use binance::coin_futures::general::*;
+
+    let general: CoinFuturesGeneral = Binance::new(None, None);
+
+    match general.ping().await {
+        Ok(answer) => info!("Ping : {:?}", answer),
+        Err(e) => error!("Error: {:?}", e),
+    }
+
+    match general.get_symbol_info("btcusd_perp").await {
+        Ok(answer) => info!("Symbol information: {:?}", answer),
+        Err(e) => error!("Error: {:?}", e),
+    }
+}
+
+#[cfg(feature = "coin_futures_api")]
+async fn market_data() {
+    use binance::api::*;
+    use binance::coin_futures::market::*;
+    use binance::futures::rest_model::ContractType;
+
+    let market: CoinFuturesMarket = Binance::new(None, None);
+
+    match market.get_custom_depth("BTCUSD_PERP", 10).await {
+        Ok(answer) => info!("Depth update ID: {:?}", answer.last_update_id),
+        Err(e) => error!("Error: {:?}", e),
+    }
+
+    match market.get_klines("BTCUSD_PERP", "5m", 10, None, None).await {
+        Ok(klines) => info!("{:?}", klines.first()),
+        Err(e) => error!("Error: {:?}", e),
+    }
+
+    match market.get_mark_prices(Some("BTCUSD".to_string())).await {
+        Ok(answer) => info!("Mark prices: {:?}", answer),
+        Err(e) => error!("Error: {:?}", e),
+    }
+
+    match market.open_interest("BTCUSD_PERP").await {
+        Ok(answer) => info!("Open interest: {:?}", answer),
+        Err(e) => error!("Error: {:?}", e),
+    }
+
+    match market
+        .get_open_interest_history("BTCUSD", ContractType::Perpetual, "1h", None, None, 10)
+        .await
+    {
+        Ok(answer) => info!("Open interest history: {:?}", answer),
+        Err(e) => error!("Error: {:?}", e),
+    }
+}
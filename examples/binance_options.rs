@@ -0,0 +1,54 @@
+#[cfg(feature = "options_api")]
+#[macro_use]
+extern crate tracing;
+
+use env_logger::Builder;
+
+#[tokio::main]
+async fn main() {
+    Builder::new().parse_default_env().init();
+    #[cfg(feature = "options_api")]
+    market_data().await;
+}
+
+#[cfg(feature = "options_api")]
+async fn market_data() {
+    use binance::api::*;
+    use binance::options::general::*;
+    use binance::options::market::*;
+
+    let general: OptionsGeneral = Binance::new(None, None);
+    let market: OptionsMarket = Binance::new(None, None);
+
+    let symbol = match general.exchange_info().await {
+        Ok(info) => info.option_symbols.into_iter().next().map(|s| s.symbol),
+        Err(e) => {
+            error!("Error: {:?}", e);
+            None
+        }
+    };
+
+    match market.get_mark_prices(symbol.clone()).await {
+        Ok(marks) => {
+            for mark in marks {
+                info!(
+                    "{} mark {} iv {} delta {} gamma {} theta {} vega {}",
+                    mark.symbol, mark.mark_price, mark.mark_iv, mark.delta, mark.gamma, mark.theta, mark.vega
+                );
+            }
+        }
+        Err(e) => error!("Error: {:?}", e),
+    }
+
+    if let Some(symbol) = symbol {
+        match market.get_custom_depth(symbol, 10).await {
+            Ok(answer) => info!("Depth update ID: {:?}", answer.update_id),
+            Err(e) => error!("Error: {:?}", e),
+        }
+    }
+
+    match market.get_index_price("BTCUSDT").await {
+        Ok(answer) => info!("Index price: {:?}", answer),
+        Err(e) => error!("Error: {:?}", e),
+    }
+}
@@ -0,0 +1,45 @@
+#[cfg(feature = "portfolio_margin_api")]
+#[macro_use]
+extern crate tracing;
+
+use env_logger::Builder;
+
+#[tokio::main]
+async fn main() {
+    Builder::new().parse_default_env().init();
+    #[cfg(feature = "portfolio_margin_api")]
+    account().await;
+}
+
+#[cfg(feature = "portfolio_margin_api")]
+async fn account() {
+    use binance::api::*;
+    use binance::config::Config;
+    use binance::portfolio_margin::account::*;
+
+    let account: PortfolioMarginAccount = Binance::new_with_env(&Config::default());
+
+    match account.account_info().await {
+        Ok(info) => info!(
+            "uniMMR {} equity {} maintenance margin {} status {:?}",
+            info.uni_mmr, info.account_equity, info.account_maint_margin, info.account_status
+        ),
+        Err(e) => error!("Error: {:?}", e),
+    }
+
+    match account.balances().await {
+        Ok(balances) => {
+            for balance in balances {
+                info!(
+                    "{} wallet {} margin free {} um {} cm {}",
+                    balance.asset,
+                    balance.total_wallet_balance,
+                    balance.cross_margin_free,
+                    balance.um_wallet_balance,
+                    balance.cm_wallet_balance
+                );
+            }
+        }
+        Err(e) => error!("Error: {:?}", e),
+    }
+}
@@ -1,3 +1,5 @@
+#![allow(clippy::result_large_err)]
+
 use csv::Writer;
 use std::error::Error;
 use std::fs::File;
@@ -1,13 +1,17 @@
+#![allow(clippy::result_large_err)]
+
 #[macro_use]
 extern crate tokio;
 
 use binance::api::*;
+use binance::rest_model::KlineInterval;
 use binance::userstream::*;
 use binance::websockets::*;
-use binance::ws_model::{CombinedStreamEvent, WebsocketEvent, WebsocketEventUntag};
+use binance::ws_model::{CombinedStreamEvent, ConnectionEvent, WebsocketEvent, WebsocketEventUntag};
 use futures::future::BoxFuture;
 use futures::stream::StreamExt;
 use serde_json::from_str;
+use std::collections::HashMap;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::RwLock;
 use tokio::sync::mpsc::UnboundedSender;
@@ -34,8 +38,10 @@ async fn main() {
         Box::pin(kline_websocket(logger_tx.clone())),
         Box::pin(all_trades_websocket(logger_tx.clone())),
         Box::pin(last_price(logger_tx.clone())),
+        Box::pin(mini_ticker_cache()),
         Box::pin(book_ticker(logger_tx.clone())),
         Box::pin(combined_orderbook(logger_tx.clone())),
+        Box::pin(reconnecting_websocket(logger_tx.clone())),
         Box::pin(custom_event_loop(logger_tx)),
     ];
 
@@ -166,10 +172,37 @@ async fn all_trades_websocket(logger_tx: UnboundedSender<WebsocketEvent>) {
     println!("disconnected");
 }
 
+#[allow(dead_code)]
+async fn reconnecting_websocket(logger_tx: UnboundedSender<WebsocketEvent>) {
+    let keep_running = AtomicBool::new(true);
+    let trade: String = trade_stream("btcusdt");
+    let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new(|event: WebsocketEvent| {
+        match event {
+            WebsocketEvent::Connection(ConnectionEvent::Connected) => {
+                println!("Reconnected, trades sent while disconnected are lost");
+            }
+            WebsocketEvent::Connection(ConnectionEvent::Closed(reason)) if !reason.is_normal() => {
+                println!("Closed by the server: {}", reason);
+            }
+            WebsocketEvent::Connection(ConnectionEvent::Disconnected(reason)) => {
+                println!("Disconnected: {}", reason);
+            }
+            event => logger_tx.send(event).unwrap(),
+        }
+        Ok(())
+    });
+
+    web_socket.connect(&trade).await.unwrap(); // check error
+    let policy = ReconnectPolicy::default().set_max_retries(Some(10));
+    if let Err(e) = web_socket.event_loop_with_reconnect(&keep_running, &policy).await {
+        println!("Error: {}", e);
+    }
+}
+
 #[allow(dead_code)]
 async fn kline_websocket(logger_tx: UnboundedSender<WebsocketEvent>) {
     let keep_running = AtomicBool::new(true);
-    let kline = kline_stream("ethbtc", "1m");
+    let kline = kline_stream("ethbtc", KlineInterval::OneMinute);
     let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new(|event: WebsocketEvent| {
         logger_tx.send(event.clone()).unwrap();
         if let WebsocketEvent::Kline(kline_event) = event {
@@ -225,6 +258,30 @@ async fn last_price(logger_tx: UnboundedSender<WebsocketEvent>) {
     println!("disconnected");
 }
 
+#[allow(dead_code)]
+async fn mini_ticker_cache() {
+    let keep_running = AtomicBool::new(true);
+    let mut prices: HashMap<String, f64> = HashMap::new();
+
+    // The whole array of a message is handed at once, the cache is never half updated
+    let mut web_socket: WebSockets<'_, Vec<WebsocketEvent>> = WebSockets::new(|events: Vec<WebsocketEvent>| {
+        for event in events {
+            if let WebsocketEvent::DayMiniTicker(ticker) = event {
+                prices.insert(ticker.symbol, ticker.current_close.parse().unwrap_or_default());
+            }
+        }
+        println!("{} prices, BTCUSDT: {:?}", prices.len(), prices.get("BTCUSDT"));
+        Ok(())
+    });
+
+    web_socket.connect(all_mini_ticker_stream()).await.unwrap(); // check error
+    if let Err(e) = web_socket.event_loop(&keep_running).await {
+        println!("Error: {}", e);
+    }
+    web_socket.disconnect().await.unwrap();
+    println!("disconnected");
+}
+
 #[allow(dead_code)]
 async fn book_ticker(logger_tx: UnboundedSender<WebsocketEvent>) {
     let keep_running = AtomicBool::new(true);
@@ -253,7 +310,7 @@ async fn combined_orderbook(logger_tx: UnboundedSender<WebsocketEvent>) {
     let keep_running = AtomicBool::new(true);
     let streams: Vec<String> = vec!["btcusdt", "ethusdt"]
         .into_iter()
-        .map(|symbol| partial_book_depth_stream(symbol, 5, 1000))
+        .map(|symbol| partial_book_depth_stream(symbol, DepthLevels::Five, UpdateSpeed::Ms1000))
         .collect();
     let mut web_socket: WebSockets<'_, CombinedStreamEvent<_>> =
         WebSockets::new(|event: CombinedStreamEvent<WebsocketEventUntag>| {
@@ -261,7 +318,7 @@ async fn combined_orderbook(logger_tx: UnboundedSender<WebsocketEvent>) {
                 logger_tx.send(we.clone()).unwrap();
             }
             let data = event.data;
-            if let WebsocketEventUntag::Orderbook(orderbook) = data {
+            if let WebsocketEventUntag::PartialDepthOrderBook(orderbook) = data {
                 println!("{:?}", orderbook)
             }
             Ok(())
@@ -279,7 +336,7 @@ async fn combined_orderbook(logger_tx: UnboundedSender<WebsocketEvent>) {
 async fn custom_event_loop(logger_tx: UnboundedSender<WebsocketEvent>) {
     let streams: Vec<String> = vec!["btcusdt", "ethusdt"]
         .into_iter()
-        .map(|symbol| partial_book_depth_stream(symbol, 5, 1000))
+        .map(|symbol| partial_book_depth_stream(symbol, DepthLevels::Five, UpdateSpeed::Ms1000))
         .collect();
     let mut web_socket: WebSockets<'_, CombinedStreamEvent<_>> =
         WebSockets::new(|event: CombinedStreamEvent<WebsocketEventUntag>| {
@@ -287,7 +344,7 @@ async fn custom_event_loop(logger_tx: UnboundedSender<WebsocketEvent>) {
                 logger_tx.send(we.clone()).unwrap();
             }
             let data = event.data;
-            if let WebsocketEventUntag::Orderbook(orderbook) = data {
+            if let WebsocketEventUntag::PartialDepthOrderBook(orderbook) = data {
                 println!("{:?}", orderbook)
             }
             Ok(())
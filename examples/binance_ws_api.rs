@@ -0,0 +1,83 @@
+#![allow(clippy::result_large_err)]
+
+//! Places and cancels a testnet order over the WebSocket API only, with the credentials of
+//! `BINANCE_API_KEY` and `BINANCE_API_SECRET_KEY`. The session is logged on with the Ed25519 key of the PEM
+//! file `BINANCE_ED25519_KEY_FILE` if set, and requests are not signed.
+#[macro_use]
+extern crate tracing;
+
+use binance::account::{OrderCancellation, OrderRequest};
+use binance::config::Config;
+use binance::rest_model::{OrderSide, OrderType, TimeInForce};
+use binance::signing::Ed25519Key;
+use binance::ws_api::WebsocketApiClient;
+use env_logger::Builder;
+
+#[tokio::main]
+async fn main() {
+    Builder::new().parse_default_env().init();
+    let api_key = std::env::var("BINANCE_API_KEY").ok();
+    let secret_key = std::env::var("BINANCE_API_SECRET_KEY").ok();
+    let client = match WebsocketApiClient::connect(api_key, secret_key, &Config::testnet()).await {
+        Ok(client) => client,
+        Err(e) => {
+            error!("Error: {:?}", e);
+            return;
+        }
+    };
+
+    if let Ok(key_file) = std::env::var("BINANCE_ED25519_KEY_FILE") {
+        let key = match std::fs::read_to_string(key_file)
+            .map_err(Into::into)
+            .and_then(|pem| Ed25519Key::from_pem(&pem))
+        {
+            Ok(key) => key,
+            Err(e) => {
+                error!("Error: {:?}", e);
+                return;
+            }
+        };
+        match client.session_logon(key).await {
+            Ok(status) => info!("Logged on {:?}", status.result),
+            Err(e) => {
+                error!("Error: {:?}", e);
+                return;
+            }
+        }
+    }
+
+    let order = OrderRequest {
+        symbol: "BTCUSDT".to_string(),
+        side: OrderSide::Buy,
+        order_type: OrderType::Limit,
+        time_in_force: Some(TimeInForce::GTC),
+        quantity: Some(0.001),
+        price: Some(10000.0),
+        ..OrderRequest::default()
+    };
+    let order_id = match client.place_order(order).await {
+        Ok(placed) => {
+            info!("Placed order {}, rate limits {:?}", placed.result.order_id(), placed.rate_limits);
+            placed.result.order_id()
+        }
+        Err(e) => {
+            error!("Error: {:?}", e);
+            return;
+        }
+    };
+
+    match client.get_open_orders("BTCUSDT").await {
+        Ok(open_orders) => info!("{} open orders", open_orders.result.len()),
+        Err(e) => error!("Error: {:?}", e),
+    }
+
+    let cancellation = OrderCancellation {
+        symbol: "BTCUSDT".to_string(),
+        order_id: Some(order_id),
+        ..OrderCancellation::default()
+    };
+    match client.cancel_order(cancellation).await {
+        Ok(canceled) => info!("Canceled {:?}", canceled.result),
+        Err(e) => error!("Error: {:?}", e),
+    }
+}
@@ -1,15 +1,50 @@
 use crate::client::*;
+use crate::client_order_id::ClientOrderIdGenerator;
 use crate::errors::*;
+use crate::order_governor::OrderGovernor;
 use crate::rest_model::*;
 use crate::util::*;
-use serde_json::from_str;
-use std::collections::BTreeMap;
+use futures::future::try_join_all;
+use futures::stream::{self, Stream, StreamExt, TryStreamExt};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
 
 static API_V3_ACCOUNT: &str = "/api/v3/account";
 static API_V3_OPEN_ORDERS: &str = "/api/v3/openOrders";
 static API_V3_ALL_ORDERS: &str = "/api/v3/allOrders";
 static API_V3_MYTRADES: &str = "/api/v3/myTrades";
 static API_V3_ORDER: &str = "/api/v3/order";
+static API_V3_ORDER_AMEND: &str = "/api/v3/order/amend/keepPriority";
+static API_V3_ACCOUNT_COMMISSION: &str = "/api/v3/account/commission";
+static API_V3_EXCHANGE_INFO: &str = "/api/v3/exchangeInfo";
+static API_V3_RATE_LIMIT_ORDER: &str = "/api/v3/rateLimit/order";
+static API_V3_MY_PREVENTED_MATCHES: &str = "/api/v3/myPreventedMatches";
+static SAPI_V1_ASSET_DIVIDEND: &str = "/sapi/v1/asset/assetDividend";
+static SAPI_V1_API_TRADING_STATUS: &str = "/sapi/v1/account/apiTradingStatus";
+static SAPI_V1_API_RESTRICTIONS: &str = "/sapi/v1/account/apiRestrictions";
+static SAPI_V1_MANAGED_SUB_ACCOUNT_ASSET: &str = "/sapi/v1/managed-subaccount/asset";
+static SAPI_V1_MANAGED_SUB_ACCOUNT_SNAPSHOT: &str = "/sapi/v1/managed-subaccount/accountSnapshot";
+static SAPI_V3_USER_ASSET: &str = "/sapi/v3/asset/getUserAsset";
+/// Maximum number of trades returned by a single myTrades call.
+const MY_TRADES_MAX_LIMIT: usize = 1000;
+/// Longest time range accepted by a single myTrades call, 24 hours in ms.
+const MY_TRADES_MAX_RANGE: u64 = 24 * 3600 * 1000;
+/// Request weight of openOrders for a single symbol.
+const OPEN_ORDERS_SYMBOL_WEIGHT: usize = 6;
+/// Request weight of openOrders without a symbol, which returns the open orders of every symbol.
+const OPEN_ORDERS_ALL_WEIGHT: usize = 80;
+/// Maximum number of cancel requests in flight for `cancel_symbols`.
+const CANCEL_SYMBOLS_CONCURRENCY: usize = 5;
+/// Characters of the signature kept by `OrderRequest::to_signed_query`.
+const REDACTED_SIGNATURE_PREFIX: usize = 8;
+/// Maximum number of records returned by a single assetDividend call.
+const ASSET_DIVIDEND_MAX_LIMIT: usize = 500;
+/// Longest time range accepted by a single assetDividend call, 180 days in ms.
+const ASSET_DIVIDEND_MAX_RANGE: u64 = 180 * 24 * 3600 * 1000;
+/// Quantities below this threshold are considered fully matched.
+const PNL_QTY_EPSILON: f64 = 1e-12;
 /// Endpoint for test orders.
 /// Orders issued to this endpoint are validated, but not sent into the matching engine.
 static API_V3_ORDER_TEST: &str = "/api/v3/order/test";
@@ -19,6 +54,19 @@ static API_V3_ORDER_TEST: &str = "/api/v3/order/test";
 pub struct Account {
     pub client: Client,
     pub recv_window: u64,
+    /// Client order ids of the orders placed without one, Binance generates them when unset
+    pub(crate) client_order_id_generator: Option<Arc<dyn ClientOrderIdGenerator>>,
+    /// Holds orders back once the order count limits are reached, orders are not counted when unset
+    pub(crate) order_governor: Option<OrderGovernor>,
+}
+
+/// Next request of the trade history paging
+#[derive(Clone, Copy)]
+enum TradesPage {
+    /// The trades of the window starting at this time
+    Window(u64),
+    /// The trades from this id on, up to the end of the window if any
+    FromId(u64, Option<u64>),
 }
 
 /// Order Request
@@ -47,16 +95,140 @@ pub struct OrderRequest {
 }
 
 impl OrderRequest {
-    fn valid(&self) -> Result<()> {
+    /// An iceberg limit order of `total_qty` showing `visible_qty` at a time, always GTC.
+    ///
+    /// Fails if the symbol does not allow icebergs or if the order would be split in more parts than its
+    /// `ICEBERG_PARTS` filter allows.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*, general::*, rest_model::*};
+    /// let general: General = Binance::new(None, None);
+    /// let info = tokio_test::block_on(general.exchange_info()).unwrap();
+    /// let symbol = info.symbols.iter().find(|s| s.symbol == "BTCUSDT").unwrap();
+    /// let order = OrderRequest::iceberg(symbol, OrderSide::Buy, 20000.0, 1.0, 0.1).unwrap();
+    /// assert_eq!(order.iceberg_qty, Some(0.1));
+    /// ```
+    pub fn iceberg(symbol: &Symbol, side: OrderSide, price: f64, total_qty: f64, visible_qty: f64) -> Result<Self> {
+        if !symbol.iceberg_allowed {
+            return Err(Error::InvalidOrderError {
+                msg: format!("Iceberg orders are not allowed on {}", symbol.symbol),
+            });
+        }
+        if visible_qty <= 0.0 || visible_qty >= total_qty {
+            return Err(Error::InvalidOrderError {
+                msg: "The visible quantity of an iceberg has to be positive and less than its total".to_string(),
+            });
+        }
+        // Rounded first so that e.g. 0.3 / 0.1 counts 3 parts
+        let parts = ((total_qty / visible_qty * 1e8).round() / 1e8).ceil() as u64;
+        let limit = symbol.filters.iter().find_map(|filter| match filter {
+            Filters::IcebergParts { limit } => Some(*limit as u64),
+            _ => None,
+        });
+        if let Some(limit) = limit {
+            if parts > limit {
+                return Err(Error::InvalidOrderError {
+                    msg: format!("The iceberg would have {} parts, {} allows at most {}", parts, symbol.symbol, limit),
+                });
+            }
+        }
+        Ok(OrderRequest {
+            symbol: symbol.symbol.clone(),
+            side,
+            order_type: OrderType::Limit,
+            time_in_force: Some(TimeInForce::GTC),
+            quantity: Some(total_qty),
+            price: Some(price),
+            iceberg_qty: Some(visible_qty),
+            ..OrderRequest::default()
+        })
+    }
+
+    /// Ask for the ACK response, the order id only
+    pub fn with_ack(mut self) -> Self {
+        self.new_order_resp_type = Some(OrderResponse::Ack);
+        self
+    }
+
+    /// Ask for the FULL response, the order and its fills
+    pub fn with_full(mut self) -> Self {
+        self.new_order_resp_type = Some(OrderResponse::Full);
+        self
+    }
+
+    /// Response type Binance answers with, when none is set MARKET and LIMIT orders default to FULL and
+    /// other orders to ACK
+    pub fn response_type(&self) -> OrderResponse {
+        match (self.new_order_resp_type, &self.order_type) {
+            (Some(response_type), _) => response_type,
+            (None, OrderType::Market | OrderType::Limit) => OrderResponse::Full,
+            (None, _) => OrderResponse::Ack,
+        }
+    }
+
+    /// Query string [`Account::submit_order`] would send for this order, to compare with what Binance expects
+    /// while diagnosing -1022 signature errors. Only the first 8 characters of the signature are kept, enough
+    /// to compare signatures but not to replay the request, so that the result can be logged. See
+    /// [`Self::to_signed_query_unredacted`] for the whole signature.
+    ///
+    /// `recv_window` applies unless the order sets its own, and `signer` is the client of the account.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*, rest_model::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// let order = OrderRequest {
+    ///     symbol: "BTCUSDT".to_string(),
+    ///     side: OrderSide::Buy,
+    ///     order_type: OrderType::Market,
+    ///     quantity: Some(0.001),
+    ///     ..OrderRequest::default()
+    /// };
+    /// println!("{}", order.to_signed_query(account.recv_window, &account.client).unwrap());
+    /// ```
+    pub fn to_signed_query(&self, recv_window: u64, signer: &Client) -> Result<String> {
+        self.signed_query(recv_window, signer, true)
+    }
+
+    /// Same as [`Self::to_signed_query`] with the whole signature: the request would be accepted by Binance
+    /// until the receive window elapses
+    pub fn to_signed_query_unredacted(&self, recv_window: u64, signer: &Client) -> Result<String> {
+        self.signed_query(recv_window, signer, false)
+    }
+
+    fn signed_query(&self, recv_window: u64, signer: &Client, redacted: bool) -> Result<String> {
+        let request = build_signed_request_p(self, self.recv_window.unwrap_or(recv_window))?;
+        let mut signature = signer.signature(&request);
+        if redacted {
+            signature.replace_range(REDACTED_SIGNATURE_PREFIX.., "<redacted>");
+        }
+        Ok(format!("{}&signature={}", request, signature))
+    }
+
+    pub(crate) fn valid(&self) -> Result<()> {
         if self.iceberg_qty.is_some() && self.time_in_force != Some(TimeInForce::GTC) {
             return Err(Error::InvalidOrderError {
                 msg: "Time in force has to be GTC for iceberg orders".to_string(),
             });
         }
-        Ok(())
+        validate_client_order_ids([self.new_client_order_id.as_deref()])
     }
 }
 
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TestOrderRequest {
+    #[serde(flatten)]
+    order: OrderRequest,
+    compute_commission_rates: Option<bool>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UserAssetRequest {
+    asset: Option<String>,
+    need_btc_valuation: bool,
+}
+
 /// Order Cancellation Request
 /// perform an order cancellation for the account
 /// only works if the parameters match an active order
@@ -73,6 +245,46 @@ pub struct OrderCancellation {
     pub recv_window: Option<u64>,
 }
 
+impl OrderCancellation {
+    pub(crate) fn valid(&self) -> Result<()> {
+        validate_client_order_ids([
+            self.orig_client_order_id.as_deref(),
+            self.new_client_order_id.as_deref(),
+        ])
+    }
+}
+
+/// Order Amendment
+/// reduce the quantity of an open order, which keeps its priority in the order book
+/// either order_id (binance side id) or orig_client_order_id (id originally given by the client) must be set
+#[derive(Default, Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderAmendment {
+    pub symbol: String,
+    pub order_id: Option<u64>,
+    pub orig_client_order_id: Option<String>,
+    /// New client order id of the order, it keeps its id when not set
+    pub new_client_order_id: Option<String>,
+    /// Must be greater than 0 and less than the current quantity of the order
+    pub new_qty: f64,
+    /// Cannot be greater than 60000
+    pub recv_window: Option<u64>,
+}
+
+impl OrderAmendment {
+    pub(crate) fn valid(&self) -> Result<()> {
+        if self.order_id.is_none() && self.orig_client_order_id.is_none() {
+            return Err(Error::InvalidOrderError {
+                msg: "either order_id or orig_client_order_id must be set".to_string(),
+            });
+        }
+        validate_client_order_ids([
+            self.orig_client_order_id.as_deref(),
+            self.new_client_order_id.as_deref(),
+        ])
+    }
+}
+
 /// Order Status Request
 /// perform an order status request for the account
 #[derive(Default, Debug, Serialize, Deserialize, Clone)]
@@ -100,7 +312,228 @@ pub struct OrdersQuery {
     pub recv_window: Option<u64>,
 }
 
+/// Query on the orders expired by self trade prevention, by prevented match id or by order id
+#[derive(Default, Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PreventedMatchesQuery {
+    pub symbol: String,
+    pub prevented_match_id: Option<u64>,
+    pub order_id: Option<u64>,
+    /// Only with `order_id`, matches with an id greater than or equal to this one
+    pub from_prevented_match_id: Option<u64>,
+    /// Default 500 max 1000
+    pub limit: Option<u32>,
+}
+
+/// Lot matching strategy used to pair closing trades with opening trades when computing realized PnL
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PnlMatching {
+    /// First in, first out: the oldest open lot is closed first
+    #[default]
+    Fifo,
+    /// Last in, first out: the most recent open lot is closed first
+    Lifo,
+    /// All open quantity is carried at its weighted average entry price
+    Average,
+}
+
+/// Realized profit and loss over a set of trades, amounts are expressed in the quote asset
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct PnlReport {
+    pub symbol: String,
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub matching: PnlMatching,
+    /// Realized PnL before fees
+    pub gross_pnl: f64,
+    /// Fees paid in the quote asset, and fees paid in the base asset converted at the fill price
+    pub fees: f64,
+    /// Gross PnL minus fees
+    pub net_pnl: f64,
+    /// Fees paid in any other asset (e.g. BNB), these are not included in `fees`
+    pub other_fees: HashMap<String, f64>,
+    /// Quantity that was closed against an opposite trade
+    pub matched_qty: f64,
+    /// Unmatched quantity left at the end, positive when long, negative when short
+    pub open_qty: f64,
+    /// Average entry price of the unmatched quantity
+    pub open_avg_price: f64,
+    pub trade_count: usize,
+}
+
+/// An open lot, `qty` is positive when long and negative when short
+#[derive(Debug, Clone, Copy)]
+struct PnlLot {
+    qty: f64,
+    price: f64,
+}
+
+impl PnlReport {
+    /// Compute the realized PnL of `trades` using the given matching strategy.
+    ///
+    /// Trades are processed in chronological order. A sell that is not covered by previously bought
+    /// quantity opens a short lot, so trades made before the range should be included when the opening
+    /// side of a position is needed.
+    pub fn from_trades(
+        symbol: &str,
+        base_asset: &str,
+        quote_asset: &str,
+        trades: &[TradeHistory],
+        matching: PnlMatching,
+    ) -> Result<PnlReport> {
+        let mut sorted: Vec<&TradeHistory> = trades.iter().collect();
+        sorted.sort_by_key(|t| (t.time, t.id));
+
+        let mut report = PnlReport {
+            symbol: symbol.to_string(),
+            base_asset: base_asset.to_string(),
+            quote_asset: quote_asset.to_string(),
+            matching,
+            trade_count: sorted.len(),
+            ..PnlReport::default()
+        };
+        let mut lots: VecDeque<PnlLot> = VecDeque::new();
+
+        for trade in sorted {
+            let commission: f64 = trade.commission.parse()?;
+            if trade.commission_asset == quote_asset {
+                report.fees += commission;
+            } else if trade.commission_asset == base_asset {
+                report.fees += commission * trade.price;
+            } else {
+                *report.other_fees.entry(trade.commission_asset.clone()).or_default() += commission;
+            }
+
+            let sign = if trade.is_buyer { 1.0 } else { -1.0 };
+            let mut remaining = trade.qty;
+            while remaining > PNL_QTY_EPSILON {
+                let lot = match matching {
+                    PnlMatching::Fifo | PnlMatching::Average => lots.front_mut(),
+                    PnlMatching::Lifo => lots.back_mut(),
+                };
+                let lot = match lot {
+                    Some(lot) if lot.qty * sign < 0.0 => lot,
+                    _ => break,
+                };
+                let closed = remaining.min(lot.qty.abs());
+                report.gross_pnl += closed * (trade.price - lot.price) * lot.qty.signum();
+                report.matched_qty += closed;
+                lot.qty += closed * sign;
+                remaining -= closed;
+                if lot.qty.abs() <= PNL_QTY_EPSILON {
+                    match matching {
+                        PnlMatching::Fifo | PnlMatching::Average => lots.pop_front(),
+                        PnlMatching::Lifo => lots.pop_back(),
+                    };
+                }
+            }
+
+            if remaining > PNL_QTY_EPSILON {
+                match (matching, lots.front_mut()) {
+                    (PnlMatching::Average, Some(lot)) => {
+                        let qty = lot.qty.abs() + remaining;
+                        lot.price = (lot.qty.abs() * lot.price + remaining * trade.price) / qty;
+                        lot.qty = qty * sign;
+                    }
+                    _ => lots.push_back(PnlLot {
+                        qty: remaining * sign,
+                        price: trade.price,
+                    }),
+                }
+            }
+        }
+
+        report.open_qty = lots.iter().map(|l| l.qty).sum();
+        if report.open_qty.abs() > PNL_QTY_EPSILON {
+            report.open_avg_price = lots.iter().map(|l| l.qty * l.price).sum::<f64>() / report.open_qty;
+        }
+        report.net_pnl = report.gross_pnl - report.fees;
+
+        Ok(report)
+    }
+}
+
+/// Health snapshot of the account and its API key, each part is fetched independently and may fail
+/// on its own
+#[derive(Debug)]
+pub struct AccountDiagnostics {
+    pub trading_status: Result<ApiTradingStatus>,
+    pub order_rate_limits: Result<Vec<OrderRateLimitUsage>>,
+    pub api_key_permissions: Result<ApiKeyPermissions>,
+}
+
+impl AccountDiagnostics {
+    /// Every part was fetched, trading is not locked and no order rate limit is exhausted
+    pub fn is_healthy(&self) -> bool {
+        let unlocked = matches!(&self.trading_status, Ok(status) if !status.is_locked);
+        let under_limits = matches!(&self.order_rate_limits, Ok(limits) if limits.iter().all(|l| l.count < l.limit));
+        unlocked && under_limits && self.api_key_permissions.is_ok()
+    }
+}
+
 impl Account {
+    /// Give an id from `generator` to every order placed without `new_client_order_id`
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, client_order_id::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// let account = account.set_client_order_id_generator(PrefixedIdGenerator::new("mybot-").unwrap());
+    /// ```
+    pub fn set_client_order_id_generator<G: ClientOrderIdGenerator + 'static>(mut self, generator: G) -> Self {
+        self.client_order_id_generator = Some(Arc::new(generator));
+        self
+    }
+
+    /// Count the orders placed against the order rate limits, and refuse them with
+    /// [`Error::OrderRateLimited`] before Binance does once a limit is reached.
+    ///
+    /// The governor lets everything through until it knows the limits, which [`Self::order_count_usage`]
+    /// tells it. Its counts are then corrected with the ones Binance sends back with every order.
+    pub fn set_order_governor(mut self, governor: OrderGovernor) -> Self {
+        self.order_governor = Some(governor);
+        self
+    }
+
+    /// A clone of this account whose requests time out after `timeout`, e.g. for tighter deadlines on
+    /// cancellations. A timed out order is reported as [`Error::Timeout`], its state is then unknown.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// use std::time::Duration;
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// let urgent = account.with_timeout(Duration::from_millis(500));
+    /// let canceled = tokio_test::block_on(urgent.cancel_all_open_orders("BTCUSDT"));
+    /// ```
+    pub fn with_timeout(&self, timeout: Duration) -> Self {
+        Account {
+            client: self.client.with_timeout(timeout),
+            ..self.clone()
+        }
+    }
+
+    fn acquire_order(&self) -> Result<()> {
+        match &self.order_governor {
+            Some(governor) => governor.acquire(),
+            None => Ok(()),
+        }
+    }
+
+    /// Correct the governor with the order counts of the response to an order, rejected orders count too
+    fn sync_order_counts(&self, limits: &RateLimits) {
+        if let Some(governor) = &self.order_governor {
+            governor.update_counts(&limits.order_count);
+        }
+    }
+
+    /// Set a generated client order id if the order has none
+    fn assign_client_order_id(&self, order: &mut OrderRequest) {
+        if order.new_client_order_id.is_none() {
+            order.new_client_order_id = self.client_order_id_generator.as_ref().map(|g| g.next_id());
+        }
+    }
+
     /// General account information
     /// # Examples
     /// ```rust,no_run
@@ -113,13 +546,30 @@ impl Account {
         let parameters: BTreeMap<String, String> = BTreeMap::new();
 
         let request = build_signed_request(parameters, self.recv_window)?;
-        let data = self.client.get_signed(API_V3_ACCOUNT, &request).await?;
-        let account_info: AccountInformation = from_str(data.as_str())?;
+        self.client.get_signed_d(API_V3_ACCOUNT, &request).await
+    }
+
+    /// Commission rates of the account for a symbol
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// let commission = tokio_test::block_on(account.get_commission("BTCUSDT"));
+    /// assert!(commission.is_ok(), "{:?}", commission);
+    /// ```
+    pub async fn get_commission<S>(&self, symbol: S) -> Result<AccountCommission>
+    where
+        S: Into<String>,
+    {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("symbol".into(), symbol.into());
 
-        Ok(account_info)
+        let request = build_signed_request(parameters, self.recv_window)?;
+        self.client.get_signed_d(API_V3_ACCOUNT_COMMISSION, &request).await
     }
 
-    /// Account balance for a single asset
+    /// Account balance for a single asset, the whole account is fetched: use [`Self::get_balances`] for
+    /// several assets
     /// # Examples
     /// ```rust,no_run
     /// use binance::{api::*, account::*, config::*};
@@ -131,18 +581,24 @@ impl Account {
     where
         S: Into<String>,
     {
-        match self.get_account().await {
-            Ok(account) => {
-                let cmp_asset = asset.into();
-                for balance in account.balances {
-                    if balance.asset == cmp_asset {
-                        return Ok(balance);
-                    }
-                }
-                Err(Error::Msg("Asset not found".to_string()))
-            }
-            Err(e) => Err(e),
-        }
+        let account = self.get_account().await?;
+        account
+            .balance(&asset.into())
+            .cloned()
+            .ok_or_else(|| Error::Msg("Asset not found".to_string()))
+    }
+
+    /// Account balances of several assets by asset, from a single account request (weight 20) instead of
+    /// one per asset with [`Self::get_balance`]. Assets missing from the account have zero balances.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// let balances = tokio_test::block_on(account.get_balances(&["BTC", "USDT"]));
+    /// assert!(balances.is_ok(), "{:?}", balances);
+    /// ```
+    pub async fn get_balances(&self, assets: &[&str]) -> Result<HashMap<String, Balance>> {
+        Ok(self.get_account().await?.balances_of(assets))
     }
 
     /// All currently open orders for a single symbol
@@ -161,10 +617,7 @@ impl Account {
         parameters.insert("symbol".into(), symbol.into());
 
         let request = build_signed_request(parameters, self.recv_window)?;
-        let data = self.client.get_signed(API_V3_OPEN_ORDERS, &request).await?;
-        let order: Vec<Order> = from_str(data.as_str())?;
-
-        Ok(order)
+        self.client.get_signed_d(API_V3_OPEN_ORDERS, &request).await
     }
 
     /// All orders for the account
@@ -186,10 +639,7 @@ impl Account {
     pub async fn get_all_orders(&self, query: OrdersQuery) -> Result<Vec<Order>> {
         let recv_window = query.recv_window.unwrap_or(self.recv_window);
         let request = build_signed_request_p(query, recv_window)?;
-        let data = self.client.get_signed(API_V3_ALL_ORDERS, &request).await?;
-        let order: Vec<Order> = from_str(data.as_str())?;
-
-        Ok(order)
+        self.client.get_signed_d(API_V3_ALL_ORDERS, &request).await
     }
 
     /// All currently open orders for the account
@@ -202,10 +652,31 @@ impl Account {
     /// ```
     pub async fn get_all_open_orders(&self) -> Result<Vec<Order>> {
         let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
-        let data = self.client.get_signed(API_V3_OPEN_ORDERS, &request).await?;
-        let order: Vec<Order> = from_str(data.as_str())?;
+        self.client.get_signed_d(API_V3_OPEN_ORDERS, &request).await
+    }
 
-        Ok(order)
+    /// Currently open orders for a set of symbols, using the cheapest strategy in request weight.
+    ///
+    /// Querying a single symbol costs 6 while querying every symbol at once costs 80, so up to 13 symbols
+    /// are queried one by one (concurrently), beyond that all open orders are fetched in a single call and
+    /// filtered client side.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// let orders = tokio_test::block_on(account.get_open_orders_for(&["BTCUSDT", "ETHUSDT"]));
+    /// assert!(orders.is_ok(), "{:?}", orders);
+    /// ```
+    pub async fn get_open_orders_for(&self, symbols: &[&str]) -> Result<Vec<Order>> {
+        let symbols: BTreeSet<&str> = symbols.iter().copied().collect();
+        if symbols.len() * OPEN_ORDERS_SYMBOL_WEIGHT < OPEN_ORDERS_ALL_WEIGHT {
+            let orders = try_join_all(symbols.into_iter().map(|symbol| self.get_open_orders(symbol))).await?;
+            Ok(orders.into_iter().flatten().collect())
+        } else {
+            let mut orders = self.get_all_open_orders().await?;
+            orders.retain(|o| symbols.contains(o.symbol.as_str()));
+            Ok(orders)
+        }
     }
 
     /// Cancels all currently open orders of specified symbol for the account
@@ -213,7 +684,7 @@ impl Account {
     /// ```rust,no_run
     /// use binance::{api::*, account::*, config::*};
     /// let account: Account = Binance::new_with_env(&Config::testnet());
-    /// let canceled_orders = tokio_test::block_on(account.cancel_all_open_orders());
+    /// let canceled_orders = tokio_test::block_on(account.cancel_all_open_orders("BTCUSDT"));
     /// assert!(canceled_orders.is_ok(), "{:?}", canceled_orders);
     /// ```
     pub async fn cancel_all_open_orders<S>(&self, symbol: S) -> Result<Vec<Order>>
@@ -223,9 +694,46 @@ impl Account {
         let mut params: BTreeMap<String, String> = BTreeMap::new();
         params.insert("symbol".into(), symbol.into());
         let request = build_signed_request(params, self.recv_window)?;
-        let data = self.client.delete_signed(API_V3_OPEN_ORDERS, &request).await?;
-        let order: Vec<Order> = from_str(data.as_str())?;
-        Ok(order)
+        self.client.delete_signed_d(API_V3_OPEN_ORDERS, &request).await
+    }
+
+    /// Cancels all open orders of each of `symbols`, a few symbols at a time
+    ///
+    /// Each symbol gets its own result, a symbol without open orders is a success with no order. Once a
+    /// request hits the rate limit the symbols not yet sent are given up, their result is an error.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// let canceled = tokio_test::block_on(account.cancel_symbols(&["BTCUSDT", "ETHUSDT"]));
+    /// for (symbol, result) in canceled {
+    ///     println!("{}: {:?}", symbol, result);
+    /// }
+    /// ```
+    pub async fn cancel_symbols(&self, symbols: &[&str]) -> HashMap<String, Result<Vec<Order>>> {
+        let symbols: BTreeSet<&str> = symbols.iter().copied().collect();
+        let rate_limited = AtomicBool::new(false);
+        let rate_limited = &rate_limited;
+        stream::iter(symbols)
+            .map(|symbol| async move {
+                if rate_limited.load(Ordering::Relaxed) {
+                    let error = Error::Msg(format!("orders of {} not canceled, the rate limit was hit", symbol));
+                    return (symbol.to_string(), Err(error));
+                }
+                let result = match self.cancel_all_open_orders(symbol).await {
+                    // Unknown order sent, nothing to cancel
+                    Err(Error::BinanceError { response }) if response.code == -2011 => Ok(vec![]),
+                    Err(e) if e.is_rate_limited() => {
+                        rate_limited.store(true, Ordering::Relaxed);
+                        Err(e)
+                    }
+                    result => result,
+                };
+                (symbol.to_string(), result)
+            })
+            .buffer_unordered(CANCEL_SYMBOLS_CONCURRENCY)
+            .collect()
+            .await
     }
 
     /// Check an order's status
@@ -245,10 +753,7 @@ impl Account {
     pub async fn order_status(&self, osr: OrderStatusRequest) -> Result<Order> {
         let recv_window = osr.recv_window.unwrap_or(self.recv_window);
         let request = build_signed_request_p(osr, recv_window)?;
-        let data = self.client.get_signed(API_V3_ORDER, &request).await?;
-        let order: Order = from_str(data.as_str())?;
-
-        Ok(order)
+        self.client.get_signed_d(API_V3_ORDER, &request).await
     }
 
     /// Place a test status order
@@ -270,15 +775,15 @@ impl Account {
     pub async fn test_order_status(&self, osr: OrderStatusRequest) -> Result<TestResponse> {
         let recv_window = osr.recv_window.unwrap_or(self.recv_window);
         let request = build_signed_request_p(osr, recv_window)?;
-        let data = self.client.get_signed(API_V3_ORDER_TEST, &request).await?;
-        let tr: TestResponse = from_str(data.as_str())?;
-
-        Ok(tr)
+        self.client.get_signed_d(API_V3_ORDER_TEST, &request).await
     }
 
     /// Place an order
     /// Returns the Transaction if Ok
     /// This methods validates the order request before sending, making sure it complies with Binance rules
+    ///
+    /// An ACK answer cannot make a Transaction: orders which would be answered with ACK by default are sent
+    /// asking for RESULT, and orders explicitly asking for ACK are refused, see [`Self::submit_order`].
     /// # Examples
     /// ```rust,no_run
     /// use binance::{api::*, account::*, config::*, rest_model::*};
@@ -295,20 +800,60 @@ impl Account {
     /// let transaction = tokio_test::block_on(account.place_order(limit_buy));
     /// assert!(transaction.is_ok(), "{:?}", transaction);
     /// ```
-    pub async fn place_order(&self, order: OrderRequest) -> Result<Transaction> {
-        let _ = order.valid()?;
+    pub async fn place_order(&self, mut order: OrderRequest) -> Result<Transaction> {
+        match (order.new_order_resp_type, order.response_type()) {
+            (Some(OrderResponse::Ack), _) => {
+                return Err(Error::InvalidOrderError {
+                    msg: "ACK responses have no transaction, use submit_order".to_string(),
+                });
+            }
+            (None, OrderResponse::Ack) => order.new_order_resp_type = Some(OrderResponse::Result),
+            _ => {}
+        }
+        self.assign_client_order_id(&mut order);
+        order.valid()?;
+        self.acquire_order()?;
+        let recv_window = order.recv_window.unwrap_or(self.recv_window);
+        let request = build_signed_request_p(order, recv_window)?;
+        let (transaction, limits) = self.client.post_signed_with_limits(API_V3_ORDER, &request).await;
+        self.sync_order_counts(&limits);
+
+        transaction
+    }
+
+    /// Place an order, sent as is and answered with whichever response type it asks for or defaults to
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*, rest_model::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// let stop_loss = OrderRequest {
+    ///         symbol: "BTCUSDT".to_string(),
+    ///         quantity: Some(0.01),
+    ///         stop_price: Some(20000.0),
+    ///         order_type: OrderType::StopLoss,
+    ///         side: OrderSide::Sell,
+    ///         ..OrderRequest::default()
+    ///     };
+    /// let response = tokio_test::block_on(account.submit_order(stop_loss)).unwrap();
+    /// println!("order {} placed", response.order_id());
+    /// ```
+    pub async fn submit_order(&self, mut order: OrderRequest) -> Result<NewOrderResponse> {
+        self.assign_client_order_id(&mut order);
+        order.valid()?;
+        self.acquire_order()?;
         let recv_window = order.recv_window.unwrap_or(self.recv_window);
         let request = build_signed_request_p(order, recv_window)?;
-        let data = self.client.post_signed(API_V3_ORDER, &request).await?;
-        let transaction: Transaction = from_str(data.as_str())?;
+        let (response, limits) = self.client.post_signed_with_limits(API_V3_ORDER, &request).await;
+        self.sync_order_counts(&limits);
 
-        Ok(transaction)
+        response
     }
 
     /// Place a test order
     ///
     /// Despite being a test, this order is still validated before calls
     /// This order is sandboxed: it is validated, but not sent to the matching engine.
+    /// With `compute_commission_rates`, the response holds the commission rates the order would pay.
     /// # Examples
     /// ```rust,no_run
     /// use binance::{api::*, account::*, config::*, rest_model::*};
@@ -322,16 +867,23 @@ impl Account {
     ///         time_in_force: Some(TimeInForce::FOK),
     ///         ..OrderRequest::default()
     ///     };
-    /// let resp = tokio_test::block_on(account.place_test_order(limit_buy));
+    /// let resp = tokio_test::block_on(account.place_test_order(limit_buy, true));
     /// assert!(resp.is_ok(), "{:?}", resp);
     /// ```
-    pub async fn place_test_order(&self, order: OrderRequest) -> Result<TestResponse> {
-        let _ = order.valid()?;
+    pub async fn place_test_order(
+        &self,
+        mut order: OrderRequest,
+        compute_commission_rates: bool,
+    ) -> Result<TestResponse> {
+        self.assign_client_order_id(&mut order);
+        order.valid()?;
         let recv_window = order.recv_window.unwrap_or(self.recv_window);
-        let request = build_signed_request_p(order, recv_window)?;
-        let data = self.client.post_signed(API_V3_ORDER_TEST, &request).await?;
-        let tr: TestResponse = from_str(data.as_str())?;
-        Ok(tr)
+        let request = TestOrderRequest {
+            order,
+            compute_commission_rates: compute_commission_rates.then_some(true),
+        };
+        let request = build_signed_request_p(request, recv_window)?;
+        self.client.post_signed_d(API_V3_ORDER_TEST, &request).await
     }
 
     /// Place a cancellation order
@@ -350,12 +902,31 @@ impl Account {
     /// assert!(canceled.is_ok(), "{:?}", canceled);
     /// ```
     pub async fn cancel_order(&self, o: OrderCancellation) -> Result<OrderCanceled> {
+        o.valid()?;
         let recv_window = o.recv_window.unwrap_or(self.recv_window);
         let request = build_signed_request_p(o, recv_window)?;
-        let data = self.client.delete_signed(API_V3_ORDER, &request).await?;
-        let order_canceled: OrderCanceled = from_str(data.as_str())?;
+        self.client.delete_signed_d(API_V3_ORDER, &request).await
+    }
 
-        Ok(order_canceled)
+    /// Reduce the quantity of an open order without losing its priority
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// let amendment = OrderAmendment {
+    ///     symbol: "BTCUSDT".to_string(),
+    ///     order_id: Some(1),
+    ///     new_qty: 0.5,
+    ///     ..OrderAmendment::default()
+    /// };
+    /// let amended = tokio_test::block_on(account.amend_order(amendment));
+    /// assert!(amended.is_ok(), "{:?}", amended);
+    /// ```
+    pub async fn amend_order(&self, o: OrderAmendment) -> Result<OrderAmended> {
+        o.valid()?;
+        let recv_window = o.recv_window.unwrap_or(self.recv_window);
+        let request = build_signed_request_p(o, recv_window)?;
+        self.client.put_signed_d(API_V3_ORDER_AMEND, &request).await
     }
 
     /// Place a test cancel order
@@ -376,12 +947,10 @@ impl Account {
     /// assert!(response.is_ok(), "{:?}", response);
     /// ```
     pub async fn test_cancel_order(&self, o: OrderCancellation) -> Result<TestResponse> {
+        o.valid()?;
         let recv_window = o.recv_window.unwrap_or(self.recv_window);
         let request = build_signed_request_p(o, recv_window)?;
-        let data = self.client.delete_signed(API_V3_ORDER_TEST, &request).await?;
-        let tr: TestResponse = from_str(data.as_str())?;
-
-        Ok(tr)
+        self.client.delete_signed_d(API_V3_ORDER_TEST, &request).await
     }
 
     /// Trade history
@@ -400,10 +969,190 @@ impl Account {
         parameters.insert("symbol".into(), symbol.into());
 
         let request = build_signed_request(parameters, self.recv_window)?;
-        let data = self.client.get_signed(API_V3_MYTRADES, &request).await?;
-        let trade_history: Vec<TradeHistory> = from_str(data.as_str())?;
+        self.client.get_signed_d(API_V3_MYTRADES, &request).await
+    }
 
-        Ok(trade_history)
+    /// Trade history within a time range, all pages are fetched
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// let trades = tokio_test::block_on(account.trade_history_range("BTCUSDT", Some(1_640_995_200_000), None));
+    /// assert!(trades.is_ok(), "{:?}", trades);
+    /// ```
+    pub async fn trade_history_range<S>(
+        &self,
+        symbol: S,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> Result<Vec<TradeHistory>>
+    where
+        S: Into<String>,
+    {
+        self.trade_history_pages(symbol, start_time, end_time).try_concat().await
+    }
+
+    /// Trade history within a time range, one page at a time, the next page is only fetched once the
+    /// previous one has been consumed.
+    ///
+    /// With a start time the range is walked forwards in windows of at most 24 hours, each window one page
+    /// of 1000 trades at a time. Without one the whole history is paged from the first trade.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// use futures::TryStreamExt;
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// tokio_test::block_on(async {
+    ///     let mut pages = Box::pin(account.trade_history_pages("BTCUSDT", Some(1_640_995_200_000), None));
+    ///     while let Some(page) = pages.try_next().await.unwrap() {
+    ///         println!("{} trades", page.len());
+    ///     }
+    /// });
+    /// ```
+    pub fn trade_history_pages<S>(
+        &self,
+        symbol: S,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> impl Stream<Item = Result<Vec<TradeHistory>>> + '_
+    where
+        S: Into<String>,
+    {
+        let symbol = symbol.into();
+        let first = match start_time {
+            Some(start) => TradesPage::Window(start),
+            None => TradesPage::FromId(0, end_time),
+        };
+        // `None` once the last page was fetched
+        stream::try_unfold(Some((first, symbol)), move |state| async move {
+            let (page, symbol) = match state {
+                Some(state) => state,
+                None => return Ok(None),
+            };
+            let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+            parameters.insert("symbol".into(), symbol.clone());
+            parameters.insert("limit".into(), MY_TRADES_MAX_LIMIT.to_string());
+            let window_end = match page {
+                TradesPage::Window(start) => {
+                    let end = match end_time {
+                        Some(end) => end,
+                        None => get_timestamp()?,
+                    };
+                    if start > end {
+                        return Ok(None);
+                    }
+                    let window_end = end.min(start.saturating_add(MY_TRADES_MAX_RANGE - 1));
+                    parameters.insert("startTime".into(), start.to_string());
+                    parameters.insert("endTime".into(), window_end.to_string());
+                    Some(window_end)
+                }
+                TradesPage::FromId(id, window_end) => {
+                    parameters.insert("fromId".into(), id.to_string());
+                    window_end
+                }
+            };
+
+            let request = build_signed_request(parameters, self.recv_window)?;
+            let page: Vec<TradeHistory> = self.client.get_signed_d(API_V3_MYTRADES, &request).await?;
+
+            let full_page = page.len() >= MY_TRADES_MAX_LIMIT;
+            let next_id = page.last().map(|t| t.id + 1);
+            let page_len = page.len();
+            let trades: Vec<TradeHistory> = page
+                .into_iter()
+                .filter(|t| window_end.is_none_or(|end| t.time <= end))
+                .collect();
+            let past_end = trades.len() < page_len;
+            let next = match (next_id, window_end) {
+                (Some(id), _) if full_page && !past_end => TradesPage::FromId(id, window_end),
+                // The trades past the window are fetched again with the next one
+                (_, Some(window_end)) => TradesPage::Window(window_end + 1),
+                (_, None) => return Ok(Some((trades, None))),
+            };
+            Ok(Some((trades, Some((next, symbol)))))
+        })
+        .try_filter(|trades| futures::future::ready(!trades.is_empty()))
+    }
+
+    /// Write the trade history within a time range as CSV, one row per trade with a header row, without
+    /// holding more than a page of trades in memory. The writer is flushed after every page and `progress`
+    /// is called with the number of trades written so far. Returns the number of trades written.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// let file = std::fs::File::create("trades.csv").unwrap();
+    /// let export = account.export_trades_csv("BTCUSDT", None, None, file, |n| println!("{} trades", n));
+    /// let written = tokio_test::block_on(export);
+    /// ```
+    #[cfg(feature = "csv_export")]
+    pub async fn export_trades_csv<S, W, F>(
+        &self,
+        symbol: S,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        writer: W,
+        mut progress: F,
+    ) -> Result<usize>
+    where
+        S: Into<String>,
+        W: std::io::Write,
+        F: FnMut(usize),
+    {
+        let mut writer = csv::Writer::from_writer(writer);
+        let mut written = 0;
+        let mut pages = Box::pin(self.trade_history_pages(symbol, start_time, end_time));
+        while let Some(page) = pages.try_next().await? {
+            for trade in &page {
+                writer.serialize(trade)?;
+            }
+            writer.flush()?;
+            written += page.len();
+            progress(written);
+        }
+        Ok(written)
+    }
+
+    /// Realized profit and loss for a symbol over a time range, computed from the account's trade history.
+    ///
+    /// Fees paid in the quote or base asset are deducted from the net PnL, fees paid in any other asset
+    /// are reported separately in `other_fees`.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// let pnl = tokio_test::block_on(account.realized_pnl("BTCUSDT", Some(1_640_995_200_000), None, PnlMatching::Fifo));
+    /// assert!(pnl.is_ok(), "{:?}", pnl);
+    /// ```
+    pub async fn realized_pnl<S>(
+        &self,
+        symbol: S,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        matching: PnlMatching,
+    ) -> Result<PnlReport>
+    where
+        S: Into<String>,
+    {
+        let symbol = symbol.into();
+        let info: ExchangeInformation = self
+            .client
+            .get_p(API_V3_EXCHANGE_INFO, &format!("symbol={}", symbol))
+            .await?;
+        let symbol_info = info
+            .symbols
+            .into_iter()
+            .find(|s| s.symbol == symbol)
+            .ok_or(Error::UnknownSymbol(symbol.clone()))?;
+        let trades = self.trade_history_range(symbol.as_str(), start_time, end_time).await?;
+
+        PnlReport::from_trades(
+            &symbol,
+            &symbol_info.base_asset,
+            &symbol_info.quote_asset,
+            &trades,
+            matching,
+        )
     }
 
     pub async fn create_sub_account<S>(&self, label: S) -> Result<SubAccountCreationResp>
@@ -416,19 +1165,12 @@ impl Account {
             },
             self.recv_window,
         )?;
-        let data = self
-            .client
-            .post_signed("/sapi/v1/sub-account/virtualSubAccount", &request)
-            .await?;
-        let resp: SubAccountCreationResp = from_str(data.as_str())?;
-        Ok(resp)
+        self.client.post_signed_d("/sapi/v1/sub-account/virtualSubAccount", &request).await
     }
 
     pub async fn list_sub_account(&self) -> Result<ListSubAccountResp> {
         let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
-        let data = self.client.get_signed("/sapi/v1/sub-account/list", &request).await?;
-        let resp: ListSubAccountResp = from_str(data.as_str())?;
-        Ok(resp)
+        self.client.get_signed_d("/sapi/v1/sub-account/list", &request).await
     }
 
     pub async fn enable_futures_on_sub_account<S>(&self, account_email: S) -> Result<EnableFuturesOnSubAccountResponse>
@@ -441,11 +1183,541 @@ impl Account {
             },
             self.recv_window,
         )?;
-        let data = self
+        self.client.post_signed_d("/sapi/v1/sub-account/futures/enable", &request).await
+    }
+
+    /// Assets held by a managed sub account, for the investor account managing it
+    pub async fn managed_sub_account_assets<S>(&self, email: S) -> Result<Vec<ManagedSubAccountAsset>>
+    where
+        S: Into<String>,
+    {
+        let query = SubAccountSimpleRequest { email: email.into() };
+        self.client
+            .get_signed_p(SAPI_V1_MANAGED_SUB_ACCOUNT_ASSET, Some(query), self.recv_window)
+            .await
+    }
+
+    /// Daily snapshots of the spot, margin or futures account of a managed sub account
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*, rest_model::*};
+    /// let account: Account = Binance::new_with_env(&Config::default());
+    /// let query = ManagedSubAccountSnapshotQuery {
+    ///     email: "managed@example.com".to_string(),
+    ///     snapshot_type: AccountSnapshotType::Spot,
+    ///     start_time: None,
+    ///     end_time: None,
+    ///     limit: Some(30),
+    /// };
+    /// let snapshots = tokio_test::block_on(account.managed_sub_account_snapshots(query));
+    /// assert!(snapshots.is_ok(), "{:?}", snapshots);
+    /// ```
+    pub async fn managed_sub_account_snapshots(
+        &self,
+        query: ManagedSubAccountSnapshotQuery,
+    ) -> Result<Vec<AccountSnapshot>> {
+        let response: AccountSnapshotResponse = self
             .client
-            .post_signed("/sapi/v1/sub-account/futures/enable", &request)
+            .get_signed_p(SAPI_V1_MANAGED_SUB_ACCOUNT_SNAPSHOT, Some(query), self.recv_window)
             .await?;
-        let resp: EnableFuturesOnSubAccountResponse = from_str(data.as_str())?;
-        Ok(resp)
+        Ok(response.snapshot_vos)
+    }
+
+    /// Trading status of the API key, which gets locked when its orders trigger the trading rules
+    pub async fn api_trading_status(&self) -> Result<ApiTradingStatus> {
+        let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
+        let response: ApiTradingStatusResponse = self.client.get_signed_d(SAPI_V1_API_TRADING_STATUS, &request).await?;
+        Ok(response.data)
+    }
+
+    /// Orders placed in the current windows of the order count limits, which are separate from the request
+    /// weight. The order governor, if any, is updated with them.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// for usage in tokio_test::block_on(account.order_count_usage()).unwrap() {
+    ///     println!("{} of {} orders per {} {:?}", usage.count, usage.limit, usage.interval_num, usage.interval);
+    /// }
+    /// ```
+    pub async fn order_count_usage(&self) -> Result<Vec<OrderCountUsage>> {
+        let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
+        let usage: Vec<OrderCountUsage> = self.client.get_signed_d(API_V3_RATE_LIMIT_ORDER, &request).await?;
+        if let Some(governor) = &self.order_governor {
+            governor.update(&usage);
+        }
+        Ok(usage)
+    }
+
+    /// Current usage of the order rate limits of the account, same as [`Self::order_count_usage`]
+    #[deprecated(note = "use order_count_usage")]
+    pub async fn order_rate_limit_usage(&self) -> Result<Vec<OrderRateLimitUsage>> { self.order_count_usage().await }
+
+    /// What the API key is allowed to do
+    pub async fn api_key_permissions(&self) -> Result<ApiKeyPermissions> {
+        let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
+        self.client.get_signed_d(SAPI_V1_API_RESTRICTIONS, &request).await
+    }
+
+    /// Orders expired because of self trade prevention
+    pub async fn prevented_matches(&self, query: PreventedMatchesQuery) -> Result<Vec<PreventedMatch>> {
+        self.client
+            .get_signed_p(API_V3_MY_PREVENTED_MATCHES, Some(query), self.recv_window)
+            .await
+    }
+
+    /// Trading status, order rate limit usage and API key permissions, fetched concurrently
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::default());
+    /// let diagnostics = tokio_test::block_on(account.diagnostics());
+    /// println!("healthy: {}, {:?}", diagnostics.is_healthy(), diagnostics);
+    /// ```
+    pub async fn diagnostics(&self) -> AccountDiagnostics {
+        let (trading_status, order_rate_limits, api_key_permissions) = futures::join!(
+            self.api_trading_status(),
+            self.order_count_usage(),
+            self.api_key_permissions()
+        );
+        AccountDiagnostics {
+            trading_status,
+            order_rate_limits,
+            api_key_permissions,
+        }
+    }
+
+    /// Dividend records (staking rewards, airdrops, distributions), of all assets if `asset` is `None`.
+    ///
+    /// Without a time range, the records of the last 180 days are returned, `limit` defaults to 20 and
+    /// cannot exceed 500.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::default());
+    /// let dividends = tokio_test::block_on(account.asset_dividend_record(Some("BNB"), None, None, Some(100)));
+    /// assert!(dividends.is_ok(), "{:?}", dividends);
+    /// ```
+    pub async fn asset_dividend_record<S>(
+        &self,
+        asset: Option<S>,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        limit: Option<u16>,
+    ) -> Result<DividendRecords>
+    where
+        S: Into<String>,
+    {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        if let Some(asset) = asset {
+            parameters.insert("asset".into(), asset.into());
+        }
+        if let Some(start) = start_time {
+            parameters.insert("startTime".into(), start.to_string());
+        }
+        if let Some(end) = end_time {
+            parameters.insert("endTime".into(), end.to_string());
+        }
+        if let Some(limit) = limit {
+            parameters.insert("limit".into(), limit.to_string());
+        }
+
+        let request = build_signed_request(parameters, self.recv_window)?;
+        self.client.get_signed_d(SAPI_V1_ASSET_DIVIDEND, &request).await
+    }
+
+    /// All dividend records since `start_time`, until `end_time` or now, newest first.
+    ///
+    /// The range is walked backwards in windows of at most 180 days, each window one page of 500
+    /// records at a time.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::default());
+    /// let dividends = tokio_test::block_on(account.asset_dividend_record_range(None::<String>, 1_640_995_200_000, None));
+    /// assert!(dividends.is_ok(), "{:?}", dividends);
+    /// ```
+    pub async fn asset_dividend_record_range<S>(
+        &self,
+        asset: Option<S>,
+        start_time: u64,
+        end_time: Option<u64>,
+    ) -> Result<Vec<DividendRecord>>
+    where
+        S: Into<String>,
+    {
+        let asset: Option<String> = asset.map(Into::into);
+        let mut records: Vec<DividendRecord> = Vec::new();
+        let mut seen: BTreeSet<u64> = BTreeSet::new();
+        let mut end = match end_time {
+            Some(end) => end,
+            None => get_timestamp()?,
+        };
+        while end >= start_time {
+            let window_start = start_time.max(end.saturating_sub(ASSET_DIVIDEND_MAX_RANGE));
+            let page = self
+                .asset_dividend_record(
+                    asset.clone(),
+                    Some(window_start),
+                    Some(end),
+                    Some(ASSET_DIVIDEND_MAX_LIMIT as u16),
+                )
+                .await?;
+            let full_page = page.rows.len() >= ASSET_DIVIDEND_MAX_LIMIT;
+            let oldest = page.rows.iter().map(|r| r.div_time).min();
+            records.extend(page.rows.into_iter().filter(|r| seen.insert(r.id)));
+            end = match oldest {
+                // Records sharing the oldest time may continue on the next page, duplicates are skipped
+                Some(oldest) if full_page && oldest < end => oldest,
+                Some(oldest) if full_page => oldest.saturating_sub(1),
+                _ if window_start == 0 => break,
+                _ => window_start - 1,
+            };
+        }
+        records.sort_by_key(|r| std::cmp::Reverse(r.div_time));
+
+        Ok(records)
+    }
+
+    /// Assets small enough to be converted into BNB, with the BNB expected for each of them
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::default());
+    /// let dust = tokio_test::block_on(account.dust_assets());
+    /// assert!(dust.is_ok(), "{:?}", dust);
+    /// ```
+    pub async fn dust_assets(&self) -> Result<DustableAssets> {
+        // A read-only query, but the endpoint only answers POST requests
+        let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
+        self.client.post_signed_d("/sapi/v1/asset/dust-btc", &request).await
+    }
+
+    /// Balances of the spot wallet, of one asset or of all the assets held, with the amounts frozen or being
+    /// withdrawn that [`Self::get_account`] leaves out
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*};
+    /// let account: Account = Binance::new_with_env(&Config::default());
+    /// let assets = tokio_test::block_on(account.user_asset(None, true));
+    /// assert!(assets.is_ok(), "{:?}", assets);
+    /// ```
+    pub async fn user_asset(&self, asset: Option<String>, need_btc_valuation: bool) -> Result<Vec<SpotUserAsset>> {
+        // A read-only query, but the endpoint only answers POST requests
+        let request = UserAssetRequest {
+            asset,
+            need_btc_valuation,
+        };
+        self.client
+            .post_signed_p(SAPI_V3_USER_ASSET, request, self.recv_window)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Account, OrderRequest, PnlMatching, PnlReport, TestOrderRequest};
+    use crate::client::Client;
+    use crate::client_order_id::PrefixedIdGenerator;
+    use crate::config::Config;
+    use crate::rest_model::{NewOrderResponse, OrderResponse, OrderSide, OrderType, Symbol, TestResponse, TimeInForce,
+                            TradeHistory};
+    use crate::test_server::{ok, response, routed_server, scripted_server};
+    use crate::util::build_request_p;
+
+    fn account(host: String) -> Account {
+        Account {
+            client: Client::new(Some("key".into()), Some("secret".into()), host),
+            recv_window: 5000,
+            client_order_id_generator: None,
+            order_governor: None,
+        }
+    }
+
+    fn trade(id: u64, is_buyer: bool, price: f64, qty: f64, commission: &str, commission_asset: &str) -> TradeHistory {
+        TradeHistory {
+            symbol: "BTCUSDT".to_string(),
+            id,
+            order_id: id,
+            price,
+            qty,
+            quote_qty: price * qty,
+            commission: commission.to_string(),
+            commission_asset: commission_asset.to_string(),
+            time: id,
+            is_buyer,
+            is_maker: false,
+            is_best_match: true,
+        }
+    }
+
+    fn trades() -> Vec<TradeHistory> {
+        vec![
+            trade(1, true, 100.0, 1.0, "0.1", "USDT"),
+            trade(2, true, 200.0, 1.0, "0.001", "BTC"),
+            trade(3, false, 300.0, 1.5, "0.5", "BNB"),
+        ]
+    }
+
+    fn pnl(matching: PnlMatching) -> PnlReport {
+        PnlReport::from_trades("BTCUSDT", "BTC", "USDT", &trades(), matching).unwrap()
+    }
+
+    #[test]
+    fn realized_pnl_fifo() {
+        let report = pnl(PnlMatching::Fifo);
+        assert!((report.gross_pnl - 250.0).abs() < 1e-9, "{:?}", report);
+        assert!((report.fees - 0.3).abs() < 1e-9, "{:?}", report);
+        assert!((report.net_pnl - 249.7).abs() < 1e-9, "{:?}", report);
+        assert_eq!(report.other_fees.get("BNB"), Some(&0.5));
+        assert!((report.open_qty - 0.5).abs() < 1e-9, "{:?}", report);
+        assert!((report.open_avg_price - 200.0).abs() < 1e-9, "{:?}", report);
+    }
+
+    #[test]
+    fn realized_pnl_lifo_and_average() {
+        let lifo = pnl(PnlMatching::Lifo);
+        assert!((lifo.gross_pnl - 200.0).abs() < 1e-9, "{:?}", lifo);
+        assert!((lifo.open_avg_price - 100.0).abs() < 1e-9, "{:?}", lifo);
+
+        let average = pnl(PnlMatching::Average);
+        assert!((average.gross_pnl - 225.0).abs() < 1e-9, "{:?}", average);
+        assert!((average.open_avg_price - 150.0).abs() < 1e-9, "{:?}", average);
+    }
+
+    #[test]
+    fn realized_pnl_short() {
+        let trades = vec![
+            trade(1, false, 300.0, 2.0, "0", "USDT"),
+            trade(2, true, 250.0, 3.0, "0", "USDT"),
+        ];
+        let report = PnlReport::from_trades("BTCUSDT", "BTC", "USDT", &trades, PnlMatching::Fifo).unwrap();
+        assert!((report.gross_pnl - 100.0).abs() < 1e-9, "{:?}", report);
+        assert!((report.open_qty - 1.0).abs() < 1e-9, "{:?}", report);
+    }
+
+    #[test]
+    fn signed_query_of_an_order() {
+        let config = Config::default();
+        let client =
+            Client::new_with_config(Some("key".into()), Some("secret".into()), "http://localhost".into(), &config);
+        let order = OrderRequest {
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            quantity: Some(0.001),
+            ..OrderRequest::default()
+        };
+        let query = order.to_signed_query_unredacted(5000, &client).unwrap();
+        assert!(query.starts_with("symbol=BTCUSDT&side=BUY&type=MARKET&quantity=0.001&recvWindow=5000&timestamp="));
+        let (request, signature) = query.split_once("&signature=").unwrap();
+        assert_eq!(signature, client.signature(request));
+        assert_eq!(signature.len(), 64);
+        let redacted = order.to_signed_query(5000, &client).unwrap();
+        let (_, redacted_signature) = redacted.split_once("&signature=").unwrap();
+        assert_eq!(redacted_signature.len(), 8 + "<redacted>".len());
+    }
+
+    #[test]
+    fn generated_client_order_ids() {
+        let account = account("http://localhost".into())
+            .set_client_order_id_generator(PrefixedIdGenerator::with_session("bot-", "s1-").unwrap());
+        let mut order = OrderRequest::default();
+        account.assign_client_order_id(&mut order);
+        assert_eq!(order.new_client_order_id.as_deref(), Some("bot-s1-0"));
+        // ids set by the caller are kept, but checked
+        account.assign_client_order_id(&mut order);
+        assert_eq!(order.new_client_order_id.as_deref(), Some("bot-s1-0"));
+        order.new_client_order_id = Some("{0b7c8f0e-1c1b-4a8e-9f5c-9d4a7e3b2c1d}".to_string());
+        assert!(order.valid().is_err());
+    }
+
+    #[test]
+    fn iceberg_parts() {
+        let symbol: Symbol = serde_json::from_value(serde_json::json!({
+            "symbol": "BTCUSDT", "status": "TRADING", "baseAsset": "BTC", "baseAssetPrecision": 8,
+            "quoteAsset": "USDT", "quotePrecision": 8, "quoteAssetPrecision": 8, "baseCommissionPrecision": 8,
+            "quoteCommissionPrecision": 8, "orderTypes": ["LIMIT"], "icebergAllowed": true, "ocoAllowed": true,
+            "quoteOrderQtyMarketAllowed": true, "isSpotTradingAllowed": true, "isMarginTradingAllowed": true,
+            "filters": [{"filterType": "ICEBERG_PARTS", "limit": 10}], "permissions": ["SPOT"]
+        }))
+        .unwrap();
+        let order = OrderRequest::iceberg(&symbol, OrderSide::Buy, 20000.0, 1.0, 0.1).unwrap();
+        assert_eq!(order.iceberg_qty, Some(0.1));
+        assert_eq!(order.time_in_force, Some(TimeInForce::GTC));
+        assert!(order.valid().is_ok());
+        assert!(OrderRequest::iceberg(&symbol, OrderSide::Buy, 20000.0, 1.0, 0.09).is_err());
+        assert!(OrderRequest::iceberg(&symbol, OrderSide::Buy, 20000.0, 1.0, 1.0).is_err());
+    }
+
+    #[test]
+    fn test_order_with_commission_rates() {
+        let request = TestOrderRequest {
+            order: OrderRequest {
+                symbol: "BTCUSDT".to_string(),
+                side: OrderSide::Buy,
+                order_type: OrderType::Market,
+                quantity: Some(1.0),
+                ..OrderRequest::default()
+            },
+            compute_commission_rates: Some(true),
+        };
+        assert_eq!(
+            build_request_p(request).unwrap(),
+            "symbol=BTCUSDT&side=BUY&type=MARKET&quantity=1&computeCommissionRates=true"
+        );
+
+        let response: TestResponse = serde_json::from_str(
+            r#"{"standardCommissionForOrder":{"maker":"0.00000112","taker":"0.00000114"},
+            "taxCommissionForOrder":{"maker":"0.00000112","taker":"0.00000114"},
+            "discount":{"enabledForAccount":true,"enabledForSymbol":true,"discountAsset":"BNB","discount":"0.25000000"}}"#,
+        )
+        .unwrap();
+        assert_eq!(response.standard_commission_for_order.unwrap().taker, 0.00000114);
+        assert_eq!(response.discount.unwrap().discount, 0.25);
+        let empty: TestResponse = serde_json::from_str("{}").unwrap();
+        assert!(empty.standard_commission_for_order.is_none());
+    }
+
+    #[test]
+    fn new_order_response_types() {
+        let market = OrderRequest::default();
+        assert_eq!(market.response_type(), OrderResponse::Full);
+        let stop_loss = OrderRequest {
+            order_type: OrderType::StopLoss,
+            ..OrderRequest::default()
+        };
+        assert_eq!(stop_loss.response_type(), OrderResponse::Ack);
+        assert_eq!(stop_loss.with_full().response_type(), OrderResponse::Full);
+
+        let ack: NewOrderResponse = serde_json::from_str(
+            r#"{"symbol":"BTCUSDT","orderId":28,"orderListId":-1,"clientOrderId":"c1","transactTime":1507725176595}"#,
+        )
+        .unwrap();
+        assert!(matches!(ack, NewOrderResponse::Ack(_)));
+        let result: NewOrderResponse = serde_json::from_value(serde_json::json!({
+            "symbol": "BTCUSDT", "orderId": 28, "orderListId": -1, "clientOrderId": "6gCrw2kRUAF9CvJDGP16IP",
+            "transactTime": 1507725176595_u64, "price": "0.0", "origQty": "10.0", "executedQty": "10.0",
+            "cummulativeQuoteQty": "10.0", "status": "FILLED", "timeInForce": "GTC", "type": "MARKET", "side": "SELL"
+        }))
+        .unwrap();
+        assert_eq!(result.order_id(), 28);
+        assert!(result.transaction().unwrap().fills.is_empty());
+    }
+
+    #[tokio::test]
+    async fn cancel_symbols_isolates_errors() {
+        let host = routed_server(|request| {
+            if request.contains("symbol=BTCUSDT") {
+                ok("[]")
+            } else if request.contains("symbol=ETHUSDT") {
+                response("400 Bad Request", r#"{"code":-2011,"msg":"Unknown order sent."}"#)
+            } else {
+                response("429 Too Many Requests", r#"{"code":-1003,"msg":"Too many requests."}"#)
+            }
+        })
+        .await;
+
+        let account = account(host);
+        let canceled = account
+            .cancel_symbols(&["BTCUSDT", "ETHUSDT", "BNBUSDT", "BTCUSDT"])
+            .await;
+        assert_eq!(canceled.len(), 3);
+        assert!(canceled["BTCUSDT"].as_ref().unwrap().is_empty());
+        assert!(canceled["ETHUSDT"].as_ref().unwrap().is_empty());
+        assert!(canceled["BNBUSDT"].as_ref().unwrap_err().is_rate_limited());
+    }
+
+    /// Responses captured from the testnet when there is nothing to return
+    static NO_OPEN_ORDERS: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json;charset=UTF-8\r\n\
+                                   Content-Length: 2\r\nConnection: close\r\n\r\n[]";
+    static TEST_ORDER_ACCEPTED: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json;charset=UTF-8\r\n\
+                                        Content-Length: 2\r\nConnection: close\r\n\r\n{}";
+    static ERROR_WITH_OK_STATUS: &str = "HTTP/1.1 200 OK\r\nContent-Type: application/json;charset=UTF-8\r\n\
+                                         Content-Length: 38\r\nConnection: close\r\n\r\n\
+                                         {\"code\":-1121,\"msg\":\"Invalid symbol.\"}";
+
+    #[tokio::test]
+    async fn empty_responses() {
+        let (host, _server) = scripted_server(vec![NO_OPEN_ORDERS, TEST_ORDER_ACCEPTED, ERROR_WITH_OK_STATUS]).await;
+
+        let account = account(host);
+        assert!(account.get_open_orders("BTCUSDT").await.unwrap().is_empty());
+        let order = OrderRequest {
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            quantity: Some(1.0),
+            ..OrderRequest::default()
+        };
+        let accepted = account.place_test_order(order, false).await.unwrap();
+        assert!(accepted.standard_commission_for_order.is_none() && accepted.discount.is_none());
+        match account.get_open_orders("BTC").await {
+            Err(crate::errors::Error::BinanceError { response }) => assert_eq!(response.code, -1121),
+            other => panic!("unexpected {:?}", other),
+        }
+    }
+
+    #[cfg(feature = "csv_export")]
+    #[tokio::test]
+    async fn exports_trades_as_csv() {
+        let (host, _server) = scripted_server(vec![ok(
+            r#"[{"symbol":"BTCUSDT","id":28457,"orderId":100234,"price":"4.00000100","qty":"12.00000000",
+            "quoteQty":"48.000012","commission":"10.10000000","commissionAsset":"BNB","time":1499865549590,
+            "isBuyer":true,"isMaker":false,"isBestMatch":true}]"#,
+        )])
+        .await;
+
+        let account = account(host);
+        let mut csv = Vec::new();
+        let mut progress = vec![];
+        let written = account
+            .export_trades_csv("BTCUSDT", None, None, &mut csv, |n| progress.push(n))
+            .await
+            .unwrap();
+        assert_eq!(written, 1);
+        assert_eq!(progress, vec![1]);
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with("symbol,id,orderId,price,qty"), "{}", csv);
+        assert!(lines[1].starts_with("BTCUSDT,28457,100234,"), "{}", csv);
+    }
+
+    fn trades_page(ids: std::ops::Range<u64>, time: impl Fn(u64) -> u64) -> String {
+        let trades: Vec<TradeHistory> = ids
+            .map(|id| TradeHistory {
+                time: time(id),
+                ..trade(id, true, 1.0, 1.0, "0", "BNB")
+            })
+            .collect();
+        ok(&serde_json::to_string(&trades).unwrap())
+    }
+
+    #[tokio::test]
+    async fn trade_history_is_walked_in_windows_of_a_day() {
+        let day = super::MY_TRADES_MAX_RANGE;
+        let limit = super::MY_TRADES_MAX_LIMIT as u64;
+        // an empty first day, a full page the second day continued by id, the last trades on the third day
+        let (host, server) = scripted_server(vec![
+            ok("[]"),
+            trades_page(0..limit, |id| day + id),
+            trades_page(limit..limit + 3, |id| if id == limit { day + id } else { 2 * day }),
+            trades_page(limit + 1..limit + 3, |_| 2 * day),
+        ])
+        .await;
+        let trades = account(host)
+            .trade_history_range("BTCUSDT", Some(0), Some(3 * day - 1))
+            .await
+            .unwrap();
+        assert_eq!(trades.iter().map(|t| t.id).collect::<Vec<_>>(), (0..limit + 3).collect::<Vec<_>>());
+        let requests = server.await.unwrap();
+        assert!(requests[0].contains(&format!("endTime={}&", day - 1)), "{}", requests[0]);
+        assert!(requests[0].contains("startTime=0&"), "{}", requests[0]);
+        assert!(requests[1].contains(&format!("startTime={}&", day)), "{}", requests[1]);
+        assert!(requests[2].contains(&format!("fromId={}&", limit)), "{}", requests[2]);
+        assert!(!requests[2].contains("startTime"), "{}", requests[2]);
+        assert!(requests[3].contains(&format!("startTime={}&", 2 * day)), "{}", requests[3]);
+        assert!(requests[3].contains(&format!("endTime={}&", 3 * day - 1)), "{}", requests[3]);
     }
 }
@@ -1,6 +1,7 @@
 use crate::account::*;
 use crate::client::*;
 use crate::config::Config;
+use crate::errors::*;
 use crate::general::*;
 use crate::market::*;
 use crate::userstream::*;
@@ -19,99 +20,212 @@ pub trait Binance: Sized {
         Self::new_with_config(api_key, secret, config)
     }
 
-    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Self;
+    /// Panics when the REST proxy of the config is not a valid url or the secret key is not a valid key, see
+    /// [`Self::try_new_with_config`]
+    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Self {
+        Self::try_new_with_config(api_key, secret_key, config).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    /// Fails with [`Error::InvalidProxy`] when the REST proxy of the config is not a valid url, and with
+    /// [`Error::InvalidKey`] when the secret key is a PEM document which is not a valid Ed25519 or RSA key
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self>;
 }
 
 impl Binance for General {
-    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> General {
-        General {
-            client: Client::new(api_key, secret_key, config.rest_api_endpoint.clone()),
-        }
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<General> {
+        Ok(General {
+            client: Client::try_new(api_key, secret_key, config.rest_api_endpoint.clone(), config)?,
+        })
     }
 }
 
 impl Binance for Account {
-    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Account {
-        Account {
-            client: Client::new(api_key, secret_key, config.rest_api_endpoint.clone()),
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Account> {
+        Ok(Account {
+            client: Client::try_new(api_key, secret_key, config.rest_api_endpoint.clone(), config)?,
+            recv_window: config.recv_window,
+            client_order_id_generator: None,
+            order_governor: config.order_governor.clone(),
+        })
+    }
+}
+
+#[cfg(feature = "broker_api")]
+impl Binance for crate::broker::Broker {
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.rest_api_endpoint.clone(), config)?,
             recv_window: config.recv_window,
-        }
+        })
     }
 }
 
 #[cfg(feature = "savings_api")]
 impl Binance for crate::savings::Savings {
-    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Self {
-        Self {
-            client: Client::new(api_key, secret_key, config.rest_api_endpoint.clone()),
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.rest_api_endpoint.clone(), config)?,
             recv_window: config.recv_window,
-        }
+        })
     }
 }
 
 impl Binance for Market {
-    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Market {
-        Market {
-            client: Client::new(api_key, secret_key, config.rest_api_endpoint.clone()),
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Market> {
+        Ok(Market {
+            client: Client::try_new(api_key, secret_key, config.rest_api_endpoint.clone(), config)?,
             recv_window: config.recv_window,
-        }
+        })
     }
 }
 
 impl Binance for UserStream {
-    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> UserStream {
-        UserStream {
-            client: Client::new(api_key, secret_key, config.rest_api_endpoint.clone()),
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<UserStream> {
+        Ok(UserStream {
+            client: Client::try_new(api_key, secret_key, config.rest_api_endpoint.clone(), config)?,
             recv_window: config.recv_window,
-        }
+        })
     }
 }
 
 #[cfg(feature = "futures_api")]
 impl Binance for crate::futures::general::FuturesGeneral {
-    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Self {
-        Self {
-            client: Client::new(api_key, secret_key, config.futures_rest_api_endpoint.clone()),
-        }
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.futures_rest_api_endpoint.clone(), config)?,
+        })
     }
 }
 
 #[cfg(feature = "futures_api")]
 impl Binance for crate::futures::userstream::FuturesUserStream {
-    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Self {
-        Self {
-            client: Client::new(api_key, secret_key, config.futures_rest_api_endpoint.clone()),
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.futures_rest_api_endpoint.clone(), config)?,
             recv_window: config.recv_window,
-        }
+        })
     }
 }
 
 #[cfg(feature = "futures_api")]
 impl Binance for crate::futures::market::FuturesMarket {
-    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Self {
-        Self {
-            client: Client::new(api_key, secret_key, config.futures_rest_api_endpoint.clone()),
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.futures_rest_api_endpoint.clone(), config)?,
             recv_window: config.recv_window,
-        }
+        })
     }
 }
 
 #[cfg(feature = "futures_api")]
 impl Binance for crate::futures::account::FuturesAccount {
-    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Self {
-        Self {
-            client: Client::new(api_key, secret_key, config.futures_rest_api_endpoint.clone()),
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.futures_rest_api_endpoint.clone(), config)?,
             recv_window: config.recv_window,
-        }
+        })
+    }
+}
+
+#[cfg(feature = "coin_futures_api")]
+impl Binance for crate::coin_futures::general::CoinFuturesGeneral {
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.coin_futures_rest_api_endpoint.clone(), config)?,
+        })
+    }
+}
+
+#[cfg(feature = "coin_futures_api")]
+impl Binance for crate::coin_futures::market::CoinFuturesMarket {
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.coin_futures_rest_api_endpoint.clone(), config)?,
+            recv_window: config.recv_window,
+        })
+    }
+}
+
+#[cfg(feature = "coin_futures_api")]
+impl Binance for crate::coin_futures::account::CoinFuturesAccount {
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.coin_futures_rest_api_endpoint.clone(), config)?,
+            recv_window: config.recv_window,
+        })
+    }
+}
+
+#[cfg(feature = "coin_futures_api")]
+impl Binance for crate::coin_futures::userstream::CoinFuturesUserStream {
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.coin_futures_rest_api_endpoint.clone(), config)?,
+            recv_window: config.recv_window,
+        })
+    }
+}
+
+#[cfg(feature = "options_api")]
+impl Binance for crate::options::general::OptionsGeneral {
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.options_rest_api_endpoint.clone(), config)?,
+        })
+    }
+}
+
+#[cfg(feature = "options_api")]
+impl Binance for crate::options::market::OptionsMarket {
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.options_rest_api_endpoint.clone(), config)?,
+            recv_window: config.recv_window,
+        })
+    }
+}
+
+#[cfg(feature = "options_api")]
+impl Binance for crate::options::account::OptionsAccount {
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.options_rest_api_endpoint.clone(), config)?,
+            recv_window: config.recv_window,
+        })
+    }
+}
+
+#[cfg(feature = "options_api")]
+impl Binance for crate::options::userstream::OptionsUserStream {
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.options_rest_api_endpoint.clone(), config)?,
+            recv_window: config.recv_window,
+        })
+    }
+}
+
+#[cfg(feature = "portfolio_margin_api")]
+impl Binance for crate::portfolio_margin::account::PortfolioMarginAccount {
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(
+                api_key,
+                secret_key,
+                config.portfolio_margin_rest_api_endpoint.clone(),
+                config,
+            )?,
+            recv_window: config.recv_window,
+        })
     }
 }
 
 #[cfg(feature = "margin_api")]
 impl Binance for crate::margin::Margin {
-    fn new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Self {
-        Self {
-            client: Client::new(api_key, secret_key, config.rest_api_endpoint.clone()),
+    fn try_new_with_config(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        Ok(Self {
+            client: Client::try_new(api_key, secret_key, config.rest_api_endpoint.clone(), config)?,
             recv_window: config.recv_window,
-        }
+        })
     }
 }
@@ -0,0 +1,206 @@
+use crate::client::*;
+use crate::errors::*;
+use crate::rest_model::string_or_float;
+use crate::util::*;
+
+static SAPI_V1_BROKER_SUB_ACCOUNT: &str = "/sapi/v1/broker/subAccount";
+static SAPI_V1_BROKER_SUB_ACCOUNT_API: &str = "/sapi/v1/broker/subAccountApi";
+static SAPI_V1_BROKER_SUB_ACCOUNT_API_COMMISSION: &str = "/sapi/v1/broker/subAccountApi/commission";
+static SAPI_V1_BROKER_REBATE_RECENT_RECORD: &str = "/sapi/v1/broker/rebate/recentRecord";
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerSubAccount {
+    pub subaccount_id: String,
+    pub email: String,
+    pub tag: Option<String>,
+}
+
+/// A sub account with its spot and margin commissions
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerSubAccountInfo {
+    pub subaccount_id: String,
+    pub email: String,
+    pub tag: Option<String>,
+    #[serde(with = "string_or_float")]
+    pub maker_commission: f64,
+    #[serde(with = "string_or_float")]
+    pub taker_commission: f64,
+    #[serde(with = "string_or_float")]
+    pub margin_maker_commission: f64,
+    #[serde(with = "string_or_float")]
+    pub margin_taker_commission: f64,
+    pub create_time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerSubAccountApi {
+    pub subaccount_id: String,
+    pub api_key: String,
+    pub secret_key: String,
+    pub can_trade: bool,
+    pub margin_trade: bool,
+    pub futures_trade: bool,
+}
+
+/// Commission rates of a sub account api key, as fractions (0.001 is 0.1%)
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BrokerCommission {
+    pub subaccount_id: String,
+    #[serde(with = "string_or_float")]
+    pub maker_commission: f64,
+    #[serde(with = "string_or_float")]
+    pub taker_commission: f64,
+    /// Margin commissions are -1 when margin is not enabled for the sub account
+    #[serde(with = "string_or_float")]
+    pub margin_maker_commission: f64,
+    #[serde(with = "string_or_float")]
+    pub margin_taker_commission: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RebateRecord {
+    pub subaccount_id: String,
+    #[serde(with = "string_or_float")]
+    pub income: f64,
+    pub asset: String,
+    pub symbol: String,
+    pub trade_id: u64,
+    pub time: u64,
+    pub status: u8,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountApiRequest {
+    pub sub_account_id: String,
+    pub can_trade: bool,
+    pub margin_trade: Option<bool>,
+    pub futures_trade: Option<bool>,
+}
+
+/// New commissions of a sub account, margin commissions are left unchanged when `None`
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CommissionRequest {
+    pub sub_account_id: String,
+    pub maker_commission: f64,
+    pub taker_commission: f64,
+    pub margin_maker_commission: Option<f64>,
+    pub margin_taker_commission: Option<f64>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SubAccountsQuery {
+    pub sub_account_id: Option<String>,
+    /// Default 1
+    pub page: Option<u32>,
+    /// Default 500
+    pub size: Option<u32>,
+}
+
+/// Rebate records of the last 7 days by default, and of at most 30 days ago
+#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RebateQuery {
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    /// Default 1
+    pub page: Option<u32>,
+    /// Default 500, max 500
+    pub size: Option<u32>,
+}
+
+#[derive(Serialize)]
+struct TagRequest {
+    tag: Option<String>,
+}
+
+/// Broker program endpoints, to manage the sub accounts of a broker and query its rebates
+#[derive(Clone)]
+pub struct Broker {
+    pub client: Client,
+    pub recv_window: u64,
+}
+
+impl Broker {
+    /// Create a broker sub account
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, broker::*, config::*};
+    /// let broker: Broker = Binance::new_with_env(&Config::default());
+    /// let sub_account = tokio_test::block_on(broker.broker_sub_account_create(Some("desk-1".to_string())));
+    /// assert!(sub_account.is_ok(), "{:?}", sub_account)
+    /// ```
+    pub async fn broker_sub_account_create(&self, tag: Option<String>) -> Result<BrokerSubAccount> {
+        let request = build_signed_request_p(TagRequest { tag }, self.recv_window)?;
+        self.client.post_signed_d(SAPI_V1_BROKER_SUB_ACCOUNT, &request).await
+    }
+
+    /// Sub accounts of the broker with their commissions
+    pub async fn broker_sub_accounts(&self, query: SubAccountsQuery) -> Result<Vec<BrokerSubAccountInfo>> {
+        self.client
+            .get_signed_p(SAPI_V1_BROKER_SUB_ACCOUNT, Some(query), self.recv_window)
+            .await
+    }
+
+    /// Create an api key for a sub account, the secret key is only ever returned here
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, broker::*, config::*};
+    /// let broker: Broker = Binance::new_with_env(&Config::default());
+    /// let request = SubAccountApiRequest {
+    ///     sub_account_id: "1".to_string(),
+    ///     can_trade: true,
+    ///     ..SubAccountApiRequest::default()
+    /// };
+    /// let api = tokio_test::block_on(broker.broker_sub_account_api_create(request));
+    /// assert!(api.is_ok(), "{:?}", api)
+    /// ```
+    pub async fn broker_sub_account_api_create(&self, request: SubAccountApiRequest) -> Result<BrokerSubAccountApi> {
+        self.client
+            .post_signed_p(SAPI_V1_BROKER_SUB_ACCOUNT_API, request, self.recv_window)
+            .await
+    }
+
+    /// Change the spot and margin commissions of a sub account
+    pub async fn broker_sub_account_commission(&self, request: CommissionRequest) -> Result<BrokerCommission> {
+        self.client
+            .post_signed_p(SAPI_V1_BROKER_SUB_ACCOUNT_API_COMMISSION, request, self.recv_window)
+            .await
+    }
+
+    /// Recent rebates earned on the trades of the sub accounts
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, broker::*, config::*};
+    /// let broker: Broker = Binance::new_with_env(&Config::default());
+    /// let rebates = tokio_test::block_on(broker.broker_rebate_recent_record(RebateQuery::default()));
+    /// assert!(rebates.is_ok(), "{:?}", rebates)
+    /// ```
+    pub async fn broker_rebate_recent_record(&self, query: RebateQuery) -> Result<Vec<RebateRecord>> {
+        self.client
+            .get_signed_p(SAPI_V1_BROKER_REBATE_RECENT_RECORD, Some(query), self.recv_window)
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn commission_accepts_numbers_and_strings() {
+        let commission: BrokerCommission = serde_json::from_str(
+            r#"{"subaccountId":"1","makerCommission":0.002,"takerCommission":"0.003","marginMakerCommission":-1,"marginTakerCommission":-1}"#,
+        )
+        .unwrap();
+        assert_eq!(commission.taker_commission, 0.003);
+        assert_eq!(commission.margin_maker_commission, -1.0);
+    }
+}
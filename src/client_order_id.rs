@@ -0,0 +1,117 @@
+//! Generation of client order ids, so that the orders of a bot can be told apart and retried safely.
+//!
+//! Binance only generates an id when the order has none, with a generator set on the
+//! [`crate::account::Account`] every order sent without `new_client_order_id` gets one from it.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use ring::rand::{SecureRandom, SystemRandom};
+
+use crate::errors::*;
+use crate::util::validate_client_order_id;
+
+/// Longest prefix of [`PrefixedIdGenerator`], leaving room for the session and a counter of any size
+const MAX_PREFIX_LENGTH: usize = 15;
+/// Random bytes of the session of [`PrefixedIdGenerator`]
+const SESSION_BYTES: usize = 4;
+
+/// Source of client order ids, shared by all the clones of an account
+pub trait ClientOrderIdGenerator: Send + Sync {
+    /// A new id, which must not have been used by another order of the account
+    fn next_id(&self) -> String;
+}
+
+/// Ids made of a fixed prefix, a session and a counter, e.g. `mybot-3fa85f64-1a`.
+///
+/// The prefix makes the orders recognizable in the UI and the session keeps ids unique across restarts,
+/// a session can be given to get a reproducible sequence of ids.
+/// # Examples
+/// ```rust
+/// use binance::client_order_id::*;
+/// let generator = PrefixedIdGenerator::with_session("mybot-", "replay-").unwrap();
+/// assert_eq!(generator.next_id(), "mybot-replay-0");
+/// assert_eq!(generator.next_id(), "mybot-replay-1");
+/// ```
+#[derive(Debug)]
+pub struct PrefixedIdGenerator {
+    prefix: String,
+    session: String,
+    counter: AtomicU64,
+}
+
+impl PrefixedIdGenerator {
+    /// Generator with a random session
+    pub fn new<S: Into<String>>(prefix: S) -> Result<Self> {
+        let mut session = [0u8; SESSION_BYTES];
+        SystemRandom::new()
+            .fill(&mut session)
+            .map_err(|_| Error::Msg("cannot generate a random session".to_string()))?;
+        Self::with_session(prefix, format!("{}-", hex::encode(session)))
+    }
+
+    /// Generator with a fixed session, which makes the ids reproducible
+    pub fn with_session<S: Into<String>, T: Into<String>>(prefix: S, session: T) -> Result<Self> {
+        let prefix = prefix.into();
+        if prefix.len() > MAX_PREFIX_LENGTH {
+            return Err(Error::InvalidOrderError {
+                msg: format!(
+                    "client order id prefix {} is longer than {} characters",
+                    prefix, MAX_PREFIX_LENGTH
+                ),
+            });
+        }
+        let session = session.into();
+        // The counter only appends allowed characters, the id made with the largest one must be valid
+        validate_client_order_id(&format!("{}{}{}", prefix, session, base36(u64::MAX)))?;
+        Ok(Self {
+            prefix,
+            session,
+            counter: AtomicU64::new(0),
+        })
+    }
+}
+
+impl ClientOrderIdGenerator for PrefixedIdGenerator {
+    fn next_id(&self) -> String {
+        let n = self.counter.fetch_add(1, Ordering::Relaxed);
+        format!("{}{}{}", self.prefix, self.session, base36(n))
+    }
+}
+
+fn base36(mut n: u64) -> String {
+    const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+    let mut digits = vec![];
+    loop {
+        digits.push(DIGITS[(n % 36) as usize]);
+        n /= 36;
+        if n == 0 {
+            break;
+        }
+    }
+    digits.reverse();
+    String::from_utf8(digits).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn prefixed_ids() {
+        let generator = PrefixedIdGenerator::new("mybot-").unwrap();
+        let first = generator.next_id();
+        let second = generator.next_id();
+        assert_ne!(first, second);
+        assert!(first.starts_with("mybot-"), "{}", first);
+        assert!(validate_client_order_id(&first).is_ok());
+        assert_ne!(
+            first,
+            PrefixedIdGenerator::new("mybot-").unwrap().next_id(),
+            "sessions are random"
+        );
+
+        assert_eq!(base36(71), "1z");
+        assert!(PrefixedIdGenerator::new("a-prefix-too-long").is_err());
+        assert!(PrefixedIdGenerator::with_session("bot{", "1").is_err());
+    }
+}
@@ -0,0 +1,98 @@
+//! Source of the wall clock time, replaceable in tests.
+//!
+//! Timestamps of the signed requests, order count windows and reconnection jitter all read the time from
+//! [`now`]. Tests swap the clock of their thread with [`set_clock`], durations measured with
+//! `tokio::time::Instant` are better controlled with `tokio::time::pause`.
+
+use std::time::SystemTime;
+
+/// A source of the current time
+pub(crate) trait Clock: Send + Sync {
+    fn now(&self) -> SystemTime;
+}
+
+/// The clock of the system
+pub(crate) struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> SystemTime { SystemTime::now() }
+}
+
+#[cfg(not(test))]
+pub(crate) fn now() -> SystemTime { SystemClock.now() }
+
+#[cfg(test)]
+pub(crate) use mock::*;
+
+#[cfg(test)]
+mod mock {
+    use std::cell::RefCell;
+    use std::sync::{Arc, Mutex};
+    use std::time::{Duration, SystemTime};
+
+    use super::{Clock, SystemClock};
+
+    thread_local! {
+        static CLOCK: RefCell<Arc<dyn Clock>> = RefCell::new(Arc::new(SystemClock));
+    }
+
+    pub(crate) fn now() -> SystemTime { CLOCK.with(|clock| clock.borrow().now()) }
+
+    /// Use `clock` on the current thread until the guard is dropped, which is enough for the single threaded
+    /// runtime of `#[tokio::test]`
+    pub(crate) fn set_clock(clock: Arc<dyn Clock>) -> ClockGuard {
+        let previous = CLOCK.with(|current| current.replace(clock));
+        ClockGuard { previous }
+    }
+
+    pub(crate) struct ClockGuard {
+        previous: Arc<dyn Clock>,
+    }
+
+    impl Drop for ClockGuard {
+        fn drop(&mut self) {
+            let previous = self.previous.clone();
+            CLOCK.with(|current| *current.borrow_mut() = previous);
+        }
+    }
+
+    /// A clock which only moves when told to
+    pub(crate) struct MockClock {
+        now: Mutex<SystemTime>,
+    }
+
+    impl MockClock {
+        /// Clock set `millis` ms after the epoch
+        pub(crate) fn at_millis(millis: u64) -> Arc<Self> {
+            Arc::new(Self {
+                now: Mutex::new(SystemTime::UNIX_EPOCH + Duration::from_millis(millis)),
+            })
+        }
+
+        pub(crate) fn advance(&self, duration: Duration) { *self.now.lock().unwrap() += duration; }
+    }
+
+    impl Clock for MockClock {
+        fn now(&self) -> SystemTime { *self.now.lock().unwrap() }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::util::get_timestamp;
+
+    #[test]
+    fn mock_clock_replaces_the_system_clock() {
+        let clock = MockClock::at_millis(1_000);
+        {
+            let _guard = set_clock(clock.clone());
+            assert_eq!(get_timestamp().unwrap(), 1_000);
+            clock.advance(Duration::from_millis(500));
+            assert_eq!(get_timestamp().unwrap(), 1_500);
+        }
+        assert!(get_timestamp().unwrap() > 1_500);
+    }
+}
@@ -0,0 +1,372 @@
+use std::collections::BTreeMap;
+
+use crate::account::{OrderCancellation, OrderStatusRequest};
+use crate::client::Client;
+use crate::coin_futures::rest_model::*;
+use crate::errors::*;
+use crate::futures::account::{MarginType, PositionMarginType, PositionSide, WorkingType};
+use crate::futures::rest_model::OrderType;
+use crate::rest_model::{string_or_float_opt, OrderResponse, OrderSide, PairQuery, Success, TimeInForce};
+use crate::util::*;
+
+/// Maximum number of orders accepted by a single batch call
+static MAX_BATCH_ORDERS: usize = 5;
+
+/// COIN-M futures account and trading
+#[derive(Clone)]
+pub struct CoinFuturesAccount {
+    pub client: Client,
+    pub recv_window: u64,
+}
+
+/// Order Request for COIN-M futures, quantities are in contracts
+#[derive(Default, Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderRequest {
+    pub symbol: String,
+    pub side: OrderSide,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub position_side: Option<PositionSide>,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_in_force: Option<TimeInForce>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "string_or_float_opt")]
+    pub quantity: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "string_or_float_opt")]
+    pub price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_client_order_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "string_or_float_opt")]
+    pub stop_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub close_position: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "string_or_float_opt")]
+    pub activation_price: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none", with = "string_or_float_opt")]
+    pub callback_rate: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_type: Option<WorkingType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_protect: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_order_resp_type: Option<OrderResponse>,
+}
+
+/// Query on the orders of a symbol, or of all the symbols of a pair
+#[derive(Default, Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrdersQuery {
+    pub symbol: Option<String>,
+    pub pair: Option<String>,
+    pub order_id: Option<u64>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    /// Default 50 max 100
+    pub limit: Option<u16>,
+}
+
+/// Query on the trades of a symbol, or of all the symbols of a pair
+#[derive(Default, Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTradesQuery {
+    pub symbol: Option<String>,
+    pub pair: Option<String>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub from_id: Option<u64>,
+    /// Default 50 max 1000
+    pub limit: Option<u16>,
+}
+
+#[derive(Default, Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IncomeQuery {
+    pub symbol: Option<String>,
+    /// TRANSFER, WELCOME_BONUS, FUNDING_FEE, REALIZED_PNL, COMMISSION, INSURANCE_CLEAR, DELIVERED_SETTELMENT
+    pub income_type: Option<String>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    /// Default 100 max 1000
+    pub limit: Option<u16>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PositionRiskQuery {
+    margin_asset: Option<String>,
+    pair: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchOrdersRequest {
+    batch_orders: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchCancelRequest {
+    symbol: String,
+    order_id_list: Option<String>,
+    orig_client_order_id_list: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangeLeverageRequest {
+    symbol: String,
+    leverage: u8,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangeMarginTypeRequest {
+    symbol: String,
+    margin_type: MarginType,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PositionMarginRequest {
+    symbol: String,
+    position_side: Option<PositionSide>,
+    amount: f64,
+    /// 1: add margin, 2: reduce margin
+    #[serde(rename = "type")]
+    adjustment_type: u8,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ChangePositionModeRequest {
+    dual_side_position: String,
+}
+
+impl CoinFuturesAccount {
+    /// Place an order
+    pub async fn place_order(&self, order: OrderRequest) -> Result<Order> {
+        self.client
+            .post_signed_p("/dapi/v1/order", order, self.recv_window)
+            .await
+    }
+
+    /// Place up to 5 orders at once, each order succeeds or fails individually
+    pub async fn place_batch_orders(&self, orders: Vec<OrderRequest>) -> Result<Vec<BatchOrderResult>> {
+        if orders.len() > MAX_BATCH_ORDERS {
+            return Err(Error::InvalidOrderError {
+                msg: format!("At most {} orders can be sent in a batch", MAX_BATCH_ORDERS),
+            });
+        }
+        let request = BatchOrdersRequest {
+            batch_orders: to_batch_json(&orders)?,
+        };
+        self.client
+            .post_signed_p("/dapi/v1/batchOrders", request, self.recv_window)
+            .await
+    }
+
+    /// Place a cancellation order
+    pub async fn cancel_order(&self, o: OrderCancellation) -> Result<Order> {
+        let recv_window = o.recv_window.unwrap_or(self.recv_window);
+        self.client.delete_signed_p("/dapi/v1/order", &o, recv_window).await
+    }
+
+    /// Cancel up to 10 orders of a symbol, by order id or by client order id
+    pub async fn cancel_batch_orders<S>(
+        &self,
+        symbol: S,
+        order_ids: Vec<u64>,
+        orig_client_order_ids: Vec<String>,
+    ) -> Result<Vec<BatchOrderResult>>
+    where
+        S: Into<String>,
+    {
+        let request = BatchCancelRequest {
+            symbol: symbol.into(),
+            order_id_list: (!order_ids.is_empty())
+                .then(|| serde_json::to_string(&order_ids))
+                .transpose()?,
+            orig_client_order_id_list: (!orig_client_order_ids.is_empty())
+                .then(|| serde_json::to_string(&orig_client_order_ids))
+                .transpose()?,
+        };
+        self.client
+            .delete_signed_p("/dapi/v1/batchOrders", request, self.recv_window)
+            .await
+    }
+
+    /// Cancel all open orders of a symbol
+    pub async fn cancel_all_open_orders<S>(&self, symbol: S) -> Result<()>
+    where
+        S: Into<String>,
+    {
+        self.client
+            .delete_signed_p::<Success, _>(
+                "/dapi/v1/allOpenOrders",
+                PairQuery { symbol: symbol.into() },
+                self.recv_window,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Query an order by order id or client order id
+    pub async fn get_order(&self, o: OrderStatusRequest) -> Result<Order> {
+        let recv_window = o.recv_window.unwrap_or(self.recv_window);
+        self.client.get_signed_p("/dapi/v1/order", Some(o), recv_window).await
+    }
+
+    /// All orders of a symbol or pair, active, canceled or filled
+    pub async fn get_all_orders(&self, query: OrdersQuery) -> Result<Vec<Order>> {
+        self.client
+            .get_signed_p("/dapi/v1/allOrders", Some(query), self.recv_window)
+            .await
+    }
+
+    /// Open orders of a symbol, of all the symbols of a pair, or of the whole account if both are `None`
+    pub async fn get_open_orders(&self, symbol: Option<String>, pair: Option<String>) -> Result<Vec<Order>> {
+        let query = OrdersQuery {
+            symbol,
+            pair,
+            ..OrdersQuery::default()
+        };
+        self.client
+            .get_signed_p("/dapi/v1/openOrders", Some(query), self.recv_window)
+            .await
+    }
+
+    /// Account information: margin assets and positions
+    pub async fn account_info(&self) -> Result<AccountInformation> {
+        let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
+        self.client.get_signed_d("/dapi/v1/account", request.as_str()).await
+    }
+
+    /// Balances of the margin assets
+    pub async fn account_balance(&self) -> Result<Vec<AccountBalance>> {
+        let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
+        self.client.get_signed_d("/dapi/v1/balance", request.as_str()).await
+    }
+
+    /// Positions filtered by margin asset or pair
+    pub async fn position_information(
+        &self,
+        margin_asset: Option<String>,
+        pair: Option<String>,
+    ) -> Result<Vec<Position>> {
+        self.client
+            .get_signed_p(
+                "/dapi/v1/positionRisk",
+                Some(PositionRiskQuery { margin_asset, pair }),
+                self.recv_window,
+            )
+            .await
+    }
+
+    /// Trades of a symbol or pair
+    pub async fn user_trades(&self, query: UserTradesQuery) -> Result<Vec<UserTrade>> {
+        self.client
+            .get_signed_p("/dapi/v1/userTrades", Some(query), self.recv_window)
+            .await
+    }
+
+    /// Income history: funding fees, realized pnl, commissions...
+    pub async fn income(&self, query: IncomeQuery) -> Result<Vec<Income>> {
+        self.client
+            .get_signed_p("/dapi/v1/income", Some(query), self.recv_window)
+            .await
+    }
+
+    /// Leverage brackets of a pair (e.g. BTCUSD), or of all pairs
+    pub async fn leverage_brackets(&self, pair: Option<String>) -> Result<Vec<PairBrackets>> {
+        let query = PositionRiskQuery {
+            margin_asset: None,
+            pair,
+        };
+        self.client
+            .get_signed_p("/dapi/v1/leverageBracket", Some(query), self.recv_window)
+            .await
+    }
+
+    /// Current position mode, `true` for hedge mode
+    pub async fn get_position_mode(&self) -> Result<PositionMode> {
+        let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
+        self.client
+            .get_signed_d("/dapi/v1/positionSide/dual", request.as_str())
+            .await
+    }
+
+    /// Change the position mode, `true` for hedge mode
+    pub async fn change_position_mode(&self, dual_side_position: bool) -> Result<()> {
+        self.client
+            .post_signed_p::<Success, _>(
+                "/dapi/v1/positionSide/dual",
+                ChangePositionModeRequest {
+                    dual_side_position: dual_side_position.to_string(),
+                },
+                self.recv_window,
+            )
+            .await?;
+        Ok(())
+    }
+
+    pub async fn change_initial_leverage<S>(&self, symbol: S, leverage: u8) -> Result<ChangeLeverageResponse>
+    where
+        S: Into<String>,
+    {
+        self.client
+            .post_signed_p(
+                "/dapi/v1/leverage",
+                ChangeLeverageRequest {
+                    symbol: symbol.into(),
+                    leverage,
+                },
+                self.recv_window,
+            )
+            .await
+    }
+
+    pub async fn change_margin_type<S>(&self, symbol: S, margin_type: MarginType) -> Result<()>
+    where
+        S: Into<String>,
+    {
+        self.client
+            .post_signed_p::<Success, _>(
+                "/dapi/v1/marginType",
+                ChangeMarginTypeRequest {
+                    symbol: symbol.into(),
+                    margin_type,
+                },
+                self.recv_window,
+            )
+            .await?;
+        Ok(())
+    }
+
+    /// Add margin to or remove margin from an isolated position, `position_side` is mandatory in hedge mode
+    pub async fn adjust_position_margin<S>(
+        &self,
+        symbol: S,
+        amount: f64,
+        position_side: Option<PositionSide>,
+        adjustment_type: PositionMarginType,
+    ) -> Result<PositionMarginResponse>
+    where
+        S: Into<String>,
+    {
+        self.client
+            .post_signed_p(
+                "/dapi/v1/positionMargin",
+                PositionMarginRequest {
+                    symbol: symbol.into(),
+                    position_side,
+                    amount,
+                    adjustment_type: adjustment_type as u8,
+                },
+                self.recv_window,
+            )
+            .await
+    }
+}
@@ -0,0 +1,40 @@
+use crate::client::*;
+use crate::coin_futures::rest_model::*;
+use crate::errors::*;
+
+#[derive(Clone)]
+pub struct CoinFuturesGeneral {
+    pub client: Client,
+}
+
+impl CoinFuturesGeneral {
+    // Test connectivity
+    pub async fn ping(&self) -> Result<String> {
+        self.client.get("/dapi/v1/ping", "").await?;
+        Ok("pong".into())
+    }
+
+    // Check server time
+    pub async fn get_server_time(&self) -> Result<ServerTime> { self.client.get_p("/dapi/v1/time", "").await }
+
+    // Obtain exchange information
+    // - Current exchange trading rules and symbol information
+    pub async fn exchange_info(&self) -> Result<ExchangeInformation> {
+        self.client.get_p("/dapi/v1/exchangeInfo", "").await
+    }
+
+    // Get Symbol information
+    pub async fn get_symbol_info<S>(&self, symbol: S) -> Result<Symbol>
+    where
+        S: Into<String>,
+    {
+        let symbol_string = symbol.into();
+        let upper_symbol = symbol_string.to_uppercase();
+        self.exchange_info()
+            .await?
+            .symbols
+            .into_iter()
+            .find(|item| item.symbol == upper_symbol)
+            .ok_or(Error::UnknownSymbol(symbol_string))
+    }
+}
@@ -0,0 +1,477 @@
+use serde_json::Value;
+
+use crate::client::*;
+use crate::coin_futures::rest_model::*;
+use crate::errors::*;
+use crate::futures::market::DEPTH_LIMITS;
+use crate::futures::rest_model::ContractType;
+use crate::util::*;
+
+/// COIN-M futures market data, all endpoints are public
+#[derive(Clone)]
+pub struct CoinFuturesMarket {
+    pub client: Client,
+    pub recv_window: u64,
+}
+
+fn to_kline(row: &[Value]) -> Kline {
+    Kline {
+        open_time: to_i64(&row[0]),
+        open: to_f64(&row[1]),
+        high: to_f64(&row[2]),
+        low: to_f64(&row[3]),
+        close: to_f64(&row[4]),
+        volume: to_f64(&row[5]),
+        close_time: to_i64(&row[6]),
+        base_volume: to_f64(&row[7]),
+        number_of_trades: to_i64(&row[8]),
+        taker_buy_volume: to_f64(&row[9]),
+        taker_buy_base_volume: to_f64(&row[10]),
+    }
+}
+
+fn to_price_kline(row: &[Value]) -> PriceKline {
+    PriceKline {
+        open_time: to_i64(&row[0]),
+        open: to_f64(&row[1]),
+        high: to_f64(&row[2]),
+        low: to_f64(&row[3]),
+        close: to_f64(&row[4]),
+        close_time: to_i64(&row[6]),
+        number_of_basic_data: to_i64(&row[8]),
+    }
+}
+
+impl CoinFuturesMarket {
+    /// Order book (Default 500; max 1000)
+    pub async fn get_depth<S>(&self, symbol: S) -> Result<OrderBook>
+    where
+        S: Into<String>,
+    {
+        let query = HistoryQuery {
+            symbol: Some(symbol.into()),
+            ..HistoryQuery::default()
+        };
+        self.client.get_d("/dapi/v1/depth", Some(query)).await
+    }
+
+    /// Order book with a custom depth limit
+    /// Supported limits are: 5, 10, 20, 50, 100, 500, 1000
+    pub async fn get_custom_depth<S>(&self, symbol: S, limit: u16) -> Result<OrderBook>
+    where
+        S: Into<String>,
+    {
+        if !DEPTH_LIMITS.contains(&limit) {
+            return Err(Error::InvalidLimit(limit));
+        }
+        let query = HistoryQuery {
+            symbol: Some(symbol.into()),
+            limit: Some(limit),
+            ..HistoryQuery::default()
+        };
+        self.client.get_d("/dapi/v1/depth", Some(query)).await
+    }
+
+    /// Recent trades for a symbol
+    pub async fn get_trades<S>(&self, symbol: S) -> Result<Vec<Trade>>
+    where
+        S: Into<String>,
+    {
+        let query = HistoryQuery {
+            symbol: Some(symbol.into()),
+            ..HistoryQuery::default()
+        };
+        self.client.get_d("/dapi/v1/trades", Some(query)).await
+    }
+
+    /// Get aggregated trades
+    pub async fn get_agg_trades<S1, S2, S3, S4, S5>(
+        &self,
+        symbol: S1,
+        from_id: S2,
+        start_time: S3,
+        end_time: S4,
+        limit: S5,
+    ) -> Result<AggTrades>
+    where
+        S1: Into<String>,
+        S2: Into<Option<u64>>,
+        S3: Into<Option<u64>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u16>>,
+    {
+        let query = HistoryQuery {
+            symbol: Some(symbol.into()),
+            from_id: from_id.into(),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            limit: limit.into(),
+            ..HistoryQuery::default()
+        };
+        self.client.get_d("/dapi/v1/aggTrades", Some(query)).await
+    }
+
+    /// Returns up to 'limit' klines for given symbol and interval ("1m", "5m", ...)
+    pub async fn get_klines<S1, S2, S3, S4, S5>(
+        &self,
+        symbol: S1,
+        interval: S2,
+        limit: S3,
+        start_time: S4,
+        end_time: S5,
+    ) -> Result<Vec<Kline>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u16>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u64>>,
+    {
+        let query = HistoryQuery {
+            symbol: Some(symbol.into()),
+            interval: Some(interval.into()),
+            limit: limit.into(),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            ..HistoryQuery::default()
+        };
+        let data: Vec<Vec<Value>> = self.client.get_d("/dapi/v1/klines", Some(query)).await?;
+        Ok(data.iter().map(|row| to_kline(row)).collect())
+    }
+
+    /// Returns up to 'limit' klines of a pair's contract type (perpetual, current quarter, ...)
+    pub async fn get_continuous_contract_klines<S1, S2, S3, S4, S5>(
+        &self,
+        pair: S1,
+        contract_type: ContractType,
+        interval: S2,
+        limit: S3,
+        start_time: S4,
+        end_time: S5,
+    ) -> Result<Vec<Kline>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u16>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u64>>,
+    {
+        let query = HistoryQuery {
+            pair: Some(pair.into()),
+            contract_type: Some(contract_type),
+            interval: Some(interval.into()),
+            limit: limit.into(),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            ..HistoryQuery::default()
+        };
+        let data: Vec<Vec<Value>> = self.client.get_d("/dapi/v1/continuousKlines", Some(query)).await?;
+        Ok(data.iter().map(|row| to_kline(row)).collect())
+    }
+
+    /// Returns up to 'limit' index price klines for a pair
+    pub async fn get_index_price_klines<S1, S2, S3, S4, S5>(
+        &self,
+        pair: S1,
+        interval: S2,
+        limit: S3,
+        start_time: S4,
+        end_time: S5,
+    ) -> Result<Vec<PriceKline>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u16>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u64>>,
+    {
+        let query = HistoryQuery {
+            pair: Some(pair.into()),
+            interval: Some(interval.into()),
+            limit: limit.into(),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            ..HistoryQuery::default()
+        };
+        let data: Vec<Vec<Value>> = self.client.get_d("/dapi/v1/indexPriceKlines", Some(query)).await?;
+        Ok(data.iter().map(|row| to_price_kline(row)).collect())
+    }
+
+    /// Returns up to 'limit' mark price klines for a symbol
+    pub async fn get_mark_price_klines<S1, S2, S3, S4, S5>(
+        &self,
+        symbol: S1,
+        interval: S2,
+        limit: S3,
+        start_time: S4,
+        end_time: S5,
+    ) -> Result<Vec<PriceKline>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u16>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u64>>,
+    {
+        let query = HistoryQuery {
+            symbol: Some(symbol.into()),
+            interval: Some(interval.into()),
+            limit: limit.into(),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            ..HistoryQuery::default()
+        };
+        let data: Vec<Vec<Value>> = self.client.get_d("/dapi/v1/markPriceKlines", Some(query)).await?;
+        Ok(data.iter().map(|row| to_price_kline(row)).collect())
+    }
+
+    /// Mark price, index price and funding rate of all symbols, or of the symbols of a pair
+    pub async fn get_mark_prices(&self, pair: Option<String>) -> Result<Vec<MarkPrice>> {
+        let query = HistoryQuery {
+            pair,
+            ..HistoryQuery::default()
+        };
+        self.client.get_d("/dapi/v1/premiumIndex", Some(query)).await
+    }
+
+    /// 24hr ticker price change statistics for a symbol
+    pub async fn get_24h_price_stats<S>(&self, symbol: S) -> Result<Vec<PriceStats>>
+    where
+        S: Into<String>,
+    {
+        let query = HistoryQuery {
+            symbol: Some(symbol.into()),
+            ..HistoryQuery::default()
+        };
+        self.client.get_d("/dapi/v1/ticker/24hr", Some(query)).await
+    }
+
+    /// 24hr ticker price change statistics for all symbols
+    pub async fn get_all_24h_price_stats(&self) -> Result<Vec<PriceStats>> {
+        self.client.get_p("/dapi/v1/ticker/24hr", "").await
+    }
+
+    /// Latest price of a symbol
+    pub async fn get_price<S>(&self, symbol: S) -> Result<Vec<SymbolPrice>>
+    where
+        S: Into<String>,
+    {
+        let query = HistoryQuery {
+            symbol: Some(symbol.into()),
+            ..HistoryQuery::default()
+        };
+        self.client.get_d("/dapi/v1/ticker/price", Some(query)).await
+    }
+
+    /// Latest price of all symbols
+    pub async fn get_all_prices(&self) -> Result<Vec<SymbolPrice>> {
+        self.client.get_p("/dapi/v1/ticker/price", "").await
+    }
+
+    /// Best price/qty on the order book for a symbol
+    pub async fn get_book_ticker<S>(&self, symbol: S) -> Result<Vec<BookTicker>>
+    where
+        S: Into<String>,
+    {
+        let query = HistoryQuery {
+            symbol: Some(symbol.into()),
+            ..HistoryQuery::default()
+        };
+        self.client.get_d("/dapi/v1/ticker/bookTicker", Some(query)).await
+    }
+
+    /// Best price/qty on the order book for all symbols
+    pub async fn get_all_book_tickers(&self) -> Result<Vec<BookTicker>> {
+        self.client.get_p("/dapi/v1/ticker/bookTicker", "").await
+    }
+
+    /// Open interest of a symbol, in contracts
+    pub async fn open_interest<S>(&self, symbol: S) -> Result<OpenInterest>
+    where
+        S: Into<String>,
+    {
+        let query = HistoryQuery {
+            symbol: Some(symbol.into()),
+            ..HistoryQuery::default()
+        };
+        self.client.get_d("/dapi/v1/openInterest", Some(query)).await
+    }
+
+    /// Get open interest history of a pair's contract type
+    pub async fn get_open_interest_history<S1, S2, S3, S4, S5>(
+        &self,
+        pair: S1,
+        contract_type: ContractType,
+        period: S2,
+        start_time: S3,
+        end_time: S4,
+        limit: S5,
+    ) -> Result<Vec<OpenInterestHistory>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u64>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u16>>,
+    {
+        let query = HistoryQuery {
+            pair: Some(pair.into()),
+            contract_type: Some(contract_type),
+            period: Some(period.into()),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            limit: limit.into(),
+            ..HistoryQuery::default()
+        };
+        query.validate()?;
+        self.client.get_d("/futures/data/openInterestHist", Some(query)).await
+    }
+
+    /// Get Top Trader Account Long/Short Ratio
+    pub async fn get_trader_account_long_short_ratio<S1, S2, S3, S4, S5>(
+        &self,
+        pair: S1,
+        period: S2,
+        start_time: S3,
+        end_time: S4,
+        limit: S5,
+    ) -> Result<Vec<LongShortRatio>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u64>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u16>>,
+    {
+        self.pair_stats("/futures/data/topLongShortAccountRatio", pair, period, start_time, end_time, limit)
+            .await
+    }
+
+    /// Get Top Trader Position Long/Short Ratio
+    pub async fn get_trader_position_long_short_ratio<S1, S2, S3, S4, S5>(
+        &self,
+        pair: S1,
+        period: S2,
+        start_time: S3,
+        end_time: S4,
+        limit: S5,
+    ) -> Result<Vec<LongShortRatio>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u64>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u16>>,
+    {
+        self.pair_stats("/futures/data/topLongShortPositionRatio", pair, period, start_time, end_time, limit)
+            .await
+    }
+
+    /// Get Long/Short Ratio
+    pub async fn get_long_short_ratio<S1, S2, S3, S4, S5>(
+        &self,
+        pair: S1,
+        period: S2,
+        start_time: S3,
+        end_time: S4,
+        limit: S5,
+    ) -> Result<Vec<LongShortRatio>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u64>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u16>>,
+    {
+        self.pair_stats("/futures/data/globalLongShortAccountRatio", pair, period, start_time, end_time, limit)
+            .await
+    }
+
+    /// Get Taker Buy/Sell Volume of a pair's contract type
+    pub async fn get_taker_buy_sell_volume<S1, S2, S3, S4, S5>(
+        &self,
+        pair: S1,
+        contract_type: ContractType,
+        period: S2,
+        start_time: S3,
+        end_time: S4,
+        limit: S5,
+    ) -> Result<Vec<TakerBuySellVolume>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u64>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u16>>,
+    {
+        let query = HistoryQuery {
+            pair: Some(pair.into()),
+            contract_type: Some(contract_type),
+            period: Some(period.into()),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            limit: limit.into(),
+            ..HistoryQuery::default()
+        };
+        query.validate()?;
+        self.client.get_d("/futures/data/takerBuySellVol", Some(query)).await
+    }
+
+    /// Get the basis of a pair's contract type
+    pub async fn get_basis<S1, S2, S3, S4, S5>(
+        &self,
+        pair: S1,
+        contract_type: ContractType,
+        period: S2,
+        start_time: S3,
+        end_time: S4,
+        limit: S5,
+    ) -> Result<Vec<Basis>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u64>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u16>>,
+    {
+        let query = HistoryQuery {
+            pair: Some(pair.into()),
+            contract_type: Some(contract_type),
+            period: Some(period.into()),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            limit: limit.into(),
+            ..HistoryQuery::default()
+        };
+        query.validate()?;
+        self.client.get_d("/futures/data/basis", Some(query)).await
+    }
+
+    async fn pair_stats<S1, S2, S3, S4, S5>(
+        &self,
+        endpoint: &str,
+        pair: S1,
+        period: S2,
+        start_time: S3,
+        end_time: S4,
+        limit: S5,
+    ) -> Result<Vec<LongShortRatio>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u64>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u16>>,
+    {
+        let query = HistoryQuery {
+            pair: Some(pair.into()),
+            period: Some(period.into()),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            limit: limit.into(),
+            ..HistoryQuery::default()
+        };
+        query.validate()?;
+        self.client.get_d(endpoint, Some(query)).await
+    }
+}
@@ -0,0 +1,8 @@
+/// COIN-M futures API modules, contracts are margined and settled in the base asset
+/// # Examples
+/// See examples/binance_coin_futures.rs
+pub mod account;
+pub mod general;
+pub mod market;
+pub mod rest_model;
+pub mod userstream;
@@ -0,0 +1,582 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::BinanceContentError;
+use crate::futures::account::PositionSide;
+use crate::futures::rest_model::{string_or_bool, ContractType, Filters, OrderType, WorkingType, PERIODS};
+use crate::rest_model::{string_or_float, string_or_float_opt, Asks, Bids, OrderSide, OrderStatus, RateLimit,
+                        TimeInForce};
+pub use crate::futures::rest_model::{AggTrade, AggTrades};
+pub use crate::rest_model::ServerTime;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeInformation {
+    pub timezone: String,
+    pub server_time: u64,
+    pub rate_limits: Vec<RateLimit>,
+    pub exchange_filters: Vec<Filters>,
+    pub symbols: Vec<Symbol>,
+}
+
+/// A COIN-M contract, symbols are a pair and a delivery (BTCUSD_PERP, BTCUSD_240628)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Symbol {
+    pub symbol: String,
+    pub pair: String,
+    pub contract_type: ContractType,
+    pub delivery_date: u64,
+    pub onboard_date: u64,
+    pub contract_status: ContractStatus,
+    /// Value of one contract, in USD
+    pub contract_size: u64,
+    pub margin_asset: String,
+    #[serde(with = "string_or_float")]
+    pub maint_margin_percent: f64,
+    #[serde(with = "string_or_float")]
+    pub required_margin_percent: f64,
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub price_precision: u16,
+    pub quantity_precision: u16,
+    pub base_asset_precision: u64,
+    pub quote_precision: u64,
+    pub equal_qty_precision: u16,
+    pub underlying_type: String,
+    pub underlying_sub_type: Vec<String>,
+    #[serde(with = "string_or_float")]
+    pub trigger_protect: f64,
+    pub filters: Vec<Filters>,
+    pub order_types: Vec<OrderType>,
+    pub time_in_force: Vec<TimeInForce>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ContractStatus {
+    PendingTrading,
+    Trading,
+    PreDelivering,
+    Delivering,
+    Delivered,
+    PreSettle,
+    Settling,
+    Close,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderBook {
+    pub last_update_id: u64,
+    pub symbol: String,
+    pub pair: String,
+    /// Message output time
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    /// Transaction time
+    #[serde(rename = "T")]
+    pub trade_order_time: u64,
+    pub bids: Vec<Bids>,
+    pub asks: Vec<Asks>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Trade {
+    pub id: u64,
+    pub is_buyer_maker: bool,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    /// Quantity in contracts
+    #[serde(with = "string_or_float")]
+    pub qty: f64,
+    /// Quantity in base asset
+    #[serde(with = "string_or_float")]
+    pub base_qty: f64,
+    pub time: u64,
+}
+
+/// Kline of a COIN-M contract, volumes are in contracts and base volumes in the base asset
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Kline {
+    pub open_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub volume: f64,
+    pub close_time: i64,
+    pub base_volume: f64,
+    pub number_of_trades: i64,
+    pub taker_buy_volume: f64,
+    pub taker_buy_base_volume: f64,
+}
+
+/// Kline of the index or mark price of a COIN-M pair
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceKline {
+    pub open_time: i64,
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub close_time: i64,
+    /// Number of basic data points used to build the kline
+    pub number_of_basic_data: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkPrice {
+    pub symbol: String,
+    pub pair: String,
+    #[serde(with = "string_or_float")]
+    pub mark_price: f64,
+    #[serde(with = "string_or_float")]
+    pub index_price: f64,
+    /// Only meaningful in the last hour before delivery
+    #[serde(default, with = "string_or_float_opt")]
+    pub estimated_settle_price: Option<f64>,
+    /// Empty for delivery contracts
+    #[serde(default, with = "string_or_float_opt")]
+    pub last_funding_rate: Option<f64>,
+    #[serde(default, with = "string_or_float_opt")]
+    pub interest_rate: Option<f64>,
+    pub next_funding_time: u64,
+    pub time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PriceStats {
+    pub symbol: String,
+    pub pair: String,
+    #[serde(with = "string_or_float")]
+    pub price_change: f64,
+    #[serde(with = "string_or_float")]
+    pub price_change_percent: f64,
+    #[serde(with = "string_or_float")]
+    pub weighted_avg_price: f64,
+    #[serde(with = "string_or_float")]
+    pub last_price: f64,
+    #[serde(with = "string_or_float")]
+    pub last_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub open_price: f64,
+    #[serde(with = "string_or_float")]
+    pub high_price: f64,
+    #[serde(with = "string_or_float")]
+    pub low_price: f64,
+    /// Volume in contracts
+    #[serde(with = "string_or_float")]
+    pub volume: f64,
+    /// Volume in base asset
+    #[serde(with = "string_or_float")]
+    pub base_volume: f64,
+    pub open_time: u64,
+    pub close_time: u64,
+    pub first_id: u64,
+    pub last_id: u64,
+    pub count: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SymbolPrice {
+    pub symbol: String,
+    #[serde(rename = "ps")]
+    pub pair: String,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    pub time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BookTicker {
+    pub symbol: String,
+    pub pair: String,
+    #[serde(with = "string_or_float")]
+    pub bid_price: f64,
+    #[serde(with = "string_or_float")]
+    pub bid_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub ask_price: f64,
+    #[serde(with = "string_or_float")]
+    pub ask_qty: f64,
+    pub time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterest {
+    pub symbol: String,
+    pub pair: String,
+    /// Open interest in contracts
+    #[serde(with = "string_or_float")]
+    pub open_interest: f64,
+    pub contract_type: ContractType,
+    pub time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterestHistory {
+    pub pair: String,
+    pub contract_type: ContractType,
+    #[serde(with = "string_or_float")]
+    pub sum_open_interest: f64,
+    #[serde(with = "string_or_float")]
+    pub sum_open_interest_value: f64,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LongShortRatio {
+    pub pair: String,
+    #[serde(with = "string_or_float")]
+    pub long_short_ratio: f64,
+    #[serde(default, with = "string_or_float_opt", alias = "longPosition")]
+    pub long_account: Option<f64>,
+    #[serde(default, with = "string_or_float_opt", alias = "shortPosition")]
+    pub short_account: Option<f64>,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TakerBuySellVolume {
+    pub pair: String,
+    pub contract_type: ContractType,
+    #[serde(with = "string_or_float")]
+    pub taker_buy_vol: f64,
+    #[serde(with = "string_or_float")]
+    pub taker_sell_vol: f64,
+    #[serde(with = "string_or_float")]
+    pub taker_buy_vol_value: f64,
+    #[serde(with = "string_or_float")]
+    pub taker_sell_vol_value: f64,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Basis {
+    pub pair: String,
+    pub contract_type: ContractType,
+    #[serde(with = "string_or_float")]
+    pub futures_price: f64,
+    #[serde(with = "string_or_float")]
+    pub index_price: f64,
+    #[serde(with = "string_or_float")]
+    pub basis: f64,
+    #[serde(with = "string_or_float")]
+    pub basis_rate: f64,
+    pub timestamp: u64,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct HistoryQuery {
+    pub symbol: Option<String>,
+    pub pair: Option<String>,
+    pub contract_type: Option<ContractType>,
+    pub interval: Option<String>,
+    pub period: Option<String>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub from_id: Option<u64>,
+    pub limit: Option<u16>,
+}
+
+impl HistoryQuery {
+    pub fn validate(&self) -> crate::errors::Result<()> {
+        if let Some(period) = &self.period {
+            if !PERIODS.contains(&period.as_str()) {
+                return Err(crate::errors::Error::InvalidPeriod(period.clone()));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Order {
+    pub client_order_id: String,
+    /// Executed quantity in contracts
+    #[serde(with = "string_or_float")]
+    pub cum_qty: f64,
+    /// Executed quantity in base asset
+    #[serde(with = "string_or_float")]
+    pub cum_base: f64,
+    #[serde(with = "string_or_float")]
+    pub executed_qty: f64,
+    pub order_id: u64,
+    #[serde(with = "string_or_float")]
+    pub avg_price: f64,
+    #[serde(with = "string_or_float")]
+    pub orig_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    pub reduce_only: bool,
+    pub side: OrderSide,
+    pub position_side: PositionSide,
+    pub status: OrderStatus,
+    #[serde(default, with = "string_or_float_opt")]
+    pub stop_price: Option<f64>,
+    pub close_position: bool,
+    pub symbol: String,
+    pub pair: String,
+    pub time_in_force: TimeInForce,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    pub orig_type: OrderType,
+    #[serde(default, with = "string_or_float_opt")]
+    pub activate_price: Option<f64>,
+    #[serde(default, with = "string_or_float_opt")]
+    pub price_rate: Option<f64>,
+    /// Only present when querying orders
+    pub time: Option<u64>,
+    pub update_time: u64,
+    pub working_type: WorkingType,
+    pub price_protect: bool,
+}
+
+/// Result of one order of a batch, failed orders are reported individually
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum BatchOrderResult {
+    Order(Box<Order>),
+    Error(BinanceContentError),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInformation {
+    pub assets: Vec<AccountAsset>,
+    pub positions: Vec<AccountPosition>,
+    pub can_deposit: bool,
+    pub can_trade: bool,
+    pub can_withdraw: bool,
+    pub fee_tier: u64,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountAsset {
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub wallet_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub unrealized_profit: f64,
+    #[serde(with = "string_or_float")]
+    pub margin_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub maint_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub initial_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub position_initial_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub open_order_initial_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub max_withdraw_amount: f64,
+    #[serde(with = "string_or_float")]
+    pub cross_wallet_balance: f64,
+    #[serde(with = "string_or_float", rename = "crossUnPnl")]
+    pub cross_unrealized_pnl: f64,
+    #[serde(with = "string_or_float")]
+    pub available_balance: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountPosition {
+    pub symbol: String,
+    #[serde(with = "string_or_float", rename = "positionAmt")]
+    pub position_amount: f64,
+    #[serde(with = "string_or_float")]
+    pub initial_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub maint_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub unrealized_profit: f64,
+    #[serde(with = "string_or_float")]
+    pub position_initial_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub open_order_initial_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub leverage: f64,
+    pub isolated: bool,
+    pub position_side: PositionSide,
+    #[serde(with = "string_or_float")]
+    pub entry_price: f64,
+    /// Maximum quantity in contracts allowed at the current leverage
+    #[serde(with = "string_or_float")]
+    pub max_qty: f64,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountBalance {
+    pub account_alias: String,
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub balance: f64,
+    #[serde(with = "string_or_float")]
+    pub withdraw_available: f64,
+    #[serde(with = "string_or_float")]
+    pub cross_wallet_balance: f64,
+    #[serde(with = "string_or_float", rename = "crossUnPnl")]
+    pub cross_unrealized_pnl: f64,
+    #[serde(with = "string_or_float")]
+    pub available_balance: f64,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub symbol: String,
+    #[serde(with = "string_or_float", rename = "positionAmt")]
+    pub position_amount: f64,
+    #[serde(with = "string_or_float")]
+    pub entry_price: f64,
+    #[serde(with = "string_or_float")]
+    pub mark_price: f64,
+    #[serde(with = "string_or_float", rename = "unRealizedProfit")]
+    pub unrealized_profit: f64,
+    #[serde(with = "string_or_float")]
+    pub liquidation_price: f64,
+    #[serde(with = "string_or_float")]
+    pub leverage: f64,
+    #[serde(with = "string_or_float")]
+    pub max_qty: f64,
+    pub margin_type: String,
+    #[serde(with = "string_or_float")]
+    pub isolated_margin: f64,
+    #[serde(with = "string_or_bool")]
+    pub is_auto_add_margin: bool,
+    pub position_side: PositionSide,
+    #[serde(with = "string_or_float")]
+    pub notional_value: f64,
+    #[serde(with = "string_or_float")]
+    pub isolated_wallet: f64,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTrade {
+    pub symbol: String,
+    pub id: u64,
+    pub order_id: u64,
+    pub pair: String,
+    pub side: OrderSide,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    /// Quantity in contracts
+    #[serde(with = "string_or_float")]
+    pub qty: f64,
+    #[serde(with = "string_or_float")]
+    pub realized_pnl: f64,
+    pub margin_asset: String,
+    /// Quantity in base asset
+    #[serde(with = "string_or_float")]
+    pub base_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub commission: f64,
+    pub commission_asset: String,
+    pub time: u64,
+    pub position_side: PositionSide,
+    pub buyer: bool,
+    pub maker: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Income {
+    pub symbol: String,
+    pub income_type: String,
+    #[serde(with = "string_or_float")]
+    pub income: f64,
+    pub asset: String,
+    pub info: String,
+    pub time: u64,
+    pub tran_id: u64,
+    pub trade_id: String,
+}
+
+/// Leverage bracket of a COIN-M pair, bounds are expressed in base asset quantity
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct LeverageBracket {
+    pub bracket: u8,
+    pub initial_leverage: u8,
+    pub qty_cap: f64,
+    // The v1 endpoint misspells this field
+    #[serde(alias = "qtylFloor")]
+    pub qty_floor: f64,
+    pub maint_margin_ratio: f64,
+    pub cum: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PairBrackets {
+    pub pair: String,
+    pub brackets: Vec<LeverageBracket>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ChangeLeverageResponse {
+    pub leverage: u8,
+    #[serde(with = "string_or_float")]
+    pub max_qty: f64,
+    pub symbol: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionMode {
+    pub dual_side_position: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionMarginResponse {
+    pub amount: f64,
+    pub code: i64,
+    pub msg: String,
+    #[serde(rename = "type")]
+    pub margin_type: u8,
+}
+
+#[cfg(test)]
+mod test {
+    use super::BatchOrderResult;
+
+    #[test]
+    fn batch_order_result_serde() {
+        let data = r#"[{
+            "clientOrderId": "myOrder1", "cumQty": "0", "cumBase": "0", "executedQty": "0", "orderId": 283194212,
+            "avgPrice": "0.0", "origQty": "10", "price": "0", "reduceOnly": false, "side": "BUY",
+            "positionSide": "SHORT", "status": "NEW", "stopPrice": "9300", "closePosition": false,
+            "symbol": "BTCUSD_200925", "pair": "BTCUSD", "timeInForce": "GTC", "type": "TRAILING_STOP_MARKET",
+            "origType": "TRAILING_STOP_MARKET", "activatePrice": "9020", "priceRate": "0.3",
+            "updateTime": 1571110484038, "workingType": "CONTRACT_PRICE", "priceProtect": false
+        }, {"code": -2022, "msg": "ReduceOnly Order is rejected."}]"#;
+        let results: Vec<BatchOrderResult> = serde_json::from_str(data).unwrap();
+        assert!(matches!(&results[0], BatchOrderResult::Order(o) if o.order_id == 283194212));
+        assert!(matches!(&results[1], BatchOrderResult::Error(e) if e.code == -2022));
+    }
+}
@@ -1,3 +1,4 @@
+use reqwest::StatusCode;
 use serde_json::Value;
 use std::collections::HashMap;
 use thiserror::Error;
@@ -10,6 +11,100 @@ pub struct BinanceContentError {
 
     #[serde(flatten)]
     extra: HashMap<String, Value>,
+    /// Status of the REST response carrying the error
+    #[serde(skip)]
+    status: Option<StatusCode>,
+}
+
+impl BinanceContentError {
+    pub fn error_code(&self) -> BinanceErrorCode { BinanceErrorCode::from(self.code as i32) }
+
+    /// HTTP status of the response, `None` for the errors of the websocket APIs
+    pub fn status(&self) -> Option<StatusCode> { self.status }
+
+    pub(crate) fn set_status(&mut self, status: StatusCode) { self.status = Some(status); }
+}
+
+macro_rules! binance_error_codes {
+    ($($(#[$doc:meta])* $name:ident = $code:literal,)*) => {
+        /// Documented error codes of Binance, which come with a message giving the details
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum BinanceErrorCode {
+            $($(#[$doc])* $name,)*
+            /// Any other code
+            Other(i32),
+        }
+
+        impl From<i32> for BinanceErrorCode {
+            fn from(code: i32) -> Self {
+                match code {
+                    $($code => BinanceErrorCode::$name,)*
+                    other => BinanceErrorCode::Other(other),
+                }
+            }
+        }
+
+        impl BinanceErrorCode {
+            pub fn code(&self) -> i32 {
+                match self {
+                    $(BinanceErrorCode::$name => $code,)*
+                    BinanceErrorCode::Other(code) => *code,
+                }
+            }
+        }
+    };
+}
+
+binance_error_codes! {
+    Unknown = -1000,
+    Disconnected = -1001,
+    Unauthorized = -1002,
+    /// Too much request weight used, or too many requests queued
+    TooManyRequests = -1003,
+    UnexpectedResponse = -1006,
+    /// No answer from the backend within the timeout, the request may still have been executed
+    Timeout = -1007,
+    ServerBusy = -1008,
+    InvalidMessage = -1013,
+    UnknownOrderComposition = -1014,
+    /// Too many new orders, the order count limit was reached
+    TooManyOrders = -1015,
+    ServiceShuttingDown = -1016,
+    UnsupportedOperation = -1020,
+    /// The timestamp is outside of the receive window
+    InvalidTimestamp = -1021,
+    InvalidSignature = -1022,
+    IllegalChars = -1100,
+    TooManyParameters = -1101,
+    MandatoryParamEmptyOrMalformed = -1102,
+    UnknownParam = -1103,
+    UnreadParameters = -1104,
+    ParamEmpty = -1105,
+    ParamNotRequired = -1106,
+    BadPrecision = -1111,
+    NoDepth = -1112,
+    TifNotRequired = -1114,
+    InvalidTif = -1115,
+    InvalidOrderType = -1116,
+    InvalidSide = -1117,
+    EmptyNewClientOrderId = -1118,
+    EmptyOrigClientOrderId = -1119,
+    BadInterval = -1120,
+    InvalidSymbol = -1121,
+    InvalidListenKey = -1125,
+    MoreThanXxHours = -1127,
+    OptionalParamsBadCombo = -1128,
+    InvalidParameter = -1130,
+    /// The order was rejected, e.g. for an insufficient balance
+    NewOrderRejected = -2010,
+    CancelRejected = -2011,
+    NoSuchOrder = -2013,
+    BadApiKeyFormat = -2014,
+    /// Invalid API key, IP, or permissions for the action
+    RejectedMbxKey = -2015,
+    NoTradingWindow = -2016,
+    /// Futures: the margin is insufficient for the order
+    MarginInsufficient = -2019,
 }
 
 /// First errors are technical errors
@@ -18,8 +113,22 @@ pub struct BinanceContentError {
 /// Unhandled binance errors are Msg
 #[derive(Error, Debug)]
 pub enum Error {
+    /// Other HTTP errors, such as failures while reading the body
     #[error(transparent)]
-    ReqError(#[from] reqwest::Error),
+    ReqError(reqwest::Error),
+    /// The server could not be reached: DNS resolution, refused or reset connection
+    #[error("connection failed: {0}")]
+    Connection(reqwest::Error),
+    /// No response within the timeout, the request may still have been executed: the state of an order is
+    /// unknown until it is queried
+    #[error("request timed out: {0}")]
+    Timeout(reqwest::Error),
+    /// TLS handshake or certificate failure, retrying will not help
+    #[error("TLS failure: {0}")]
+    Tls(reqwest::Error),
+    /// The request could not be built, e.g. an invalid url
+    #[error("invalid request: {0}")]
+    Request(reqwest::Error),
     #[error(transparent)]
     InvalidHeaderError(#[from] reqwest::header::InvalidHeaderValue),
     #[error(transparent)]
@@ -43,6 +152,48 @@ pub enum Error {
         #[from]
         response: BinanceContentError,
     },
+    /// -1021, the request timestamp is outside of the receive window
+    #[error("{response}, {}", clock_drift_hint(.time_offset))]
+    TimestampOutsideRecvWindow {
+        response: BinanceContentError,
+        /// Offset between the server and the local clock in ms, when time sync is enabled
+        time_offset: Option<i64>,
+    },
+    /// The body is not JSON, typically an HTML error page served during an outage
+    #[error("non JSON response with status {status}: {body_snippet}")]
+    NonJsonResponse {
+        status: reqwest::StatusCode,
+        body_snippet: String,
+    },
+    /// The body exceeded [`crate::config::Config::max_response_bytes`]
+    #[error("response larger than {limit} bytes")]
+    ResponseTooLarge { limit: usize },
+    /// The server closed the websocket connection
+    #[error("websocket closed: {0}")]
+    WebsocketClosed(crate::ws_model::CloseReason),
+    /// No websocket message arrived within the timeout of the watchdog
+    #[error("no websocket message for {0:?}")]
+    WebsocketStale(std::time::Duration),
+    /// The websocket proxy could not be reached or refused to open the tunnel
+    #[error("websocket proxy handshake failed: {0}")]
+    WebsocketProxy(String),
+    /// The TLS handshake of the websocket failed, or the TLS settings of the config are invalid
+    #[error("websocket TLS handshake failed: {0}")]
+    WebsocketTls(String),
+    /// The request still failed after the retries of the [`crate::config::RetryPolicy`]
+    #[error("{last} (after {attempts} attempts and {waited:?} waited)")]
+    RetriesExhausted {
+        attempts: u32,
+        waited: std::time::Duration,
+        last: Box<Error>,
+    },
+    /// The REST proxy of the config is not a valid url
+    #[error("invalid proxy url: {0}")]
+    InvalidProxy(String),
+    #[error("order rate limit of {limit} orders per {interval:?} reached")]
+    OrderRateLimited { limit: i32, interval: std::time::Duration },
+    #[error("invalid private key: {0}")]
+    InvalidKey(String),
     #[error("invalid listen key : {0}")]
     InvalidListenKey(String),
     #[error("unknown symbol {0}")]
@@ -53,6 +204,8 @@ pub enum Error {
     InvalidPrice,
     #[error("invalid period {0}")]
     InvalidPeriod(String),
+    #[error("invalid limit {0}")]
+    InvalidLimit(u16),
     #[error("internal server error")]
     InternalServerError,
     #[error("service unavailable")]
@@ -63,6 +216,129 @@ pub enum Error {
     Msg(String),
 }
 
+impl Error {
+    /// Transient failures, the same request may succeed later
+    pub fn is_retryable(&self) -> bool { matches!(self, Error::Connection(_) | Error::Timeout(_)) }
+
+    /// The request timed out after being sent, whether it was executed is unknown
+    pub fn is_timeout(&self) -> bool {
+        match self {
+            Error::Timeout(_) => true,
+            Error::RetriesExhausted { last, .. } => last.is_timeout(),
+            _ => false,
+        }
+    }
+
+    /// The request or order rate limit was hit, further requests should wait
+    pub fn is_rate_limited(&self) -> bool {
+        match self {
+            Error::BinanceError { response } => {
+                let status = response.status();
+                matches!(
+                    response.error_code(),
+                    BinanceErrorCode::TooManyRequests | BinanceErrorCode::TooManyOrders
+                ) || status == Some(StatusCode::TOO_MANY_REQUESTS)
+                    || status == Some(StatusCode::IM_A_TEAPOT)
+            }
+            Error::OrderRateLimited { .. } => true,
+            Error::RetriesExhausted { last, .. } => last.is_rate_limited(),
+            _ => false,
+        }
+    }
+
+    /// Code of the errors returned by Binance
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, account::*, config::*, errors::*};
+    /// let account: Account = Binance::new_with_env(&Config::testnet());
+    /// match tokio_test::block_on(account.cancel_all_open_orders("BTCUSDT")) {
+    ///     Err(e) if e.binance_code() == Some(BinanceErrorCode::CancelRejected) => println!("no open order"),
+    ///     other => println!("{:?}", other),
+    /// }
+    /// ```
+    pub fn binance_code(&self) -> Option<BinanceErrorCode> {
+        match self {
+            Error::BinanceError { response } | Error::TimestampOutsideRecvWindow { response, .. } => {
+                Some(response.error_code())
+            }
+            Error::InvalidListenKey(_) => Some(BinanceErrorCode::InvalidListenKey),
+            Error::InvalidPrice => Some(BinanceErrorCode::InvalidMessage),
+            Error::RetriesExhausted { last, .. } => last.binance_code(),
+            _ => None,
+        }
+    }
+
+    /// The order was rejected for lack of balance, or of margin for futures
+    pub fn is_insufficient_balance(&self) -> bool {
+        match self {
+            Error::BinanceError { response } => match response.error_code() {
+                BinanceErrorCode::MarginInsufficient => true,
+                BinanceErrorCode::NewOrderRejected => response.msg.to_lowercase().contains("insufficient balance"),
+                _ => false,
+            },
+            Error::RetriesExhausted { last, .. } => last.is_insufficient_balance(),
+            _ => false,
+        }
+    }
+}
+
+/// Failures of the CSV exports, the I/O errors of the writer are kept as such
+#[cfg(feature = "csv_export")]
+impl From<csv::Error> for Error {
+    fn from(e: csv::Error) -> Self {
+        let msg = e.to_string();
+        match e.into_kind() {
+            csv::ErrorKind::Io(e) => Error::IoError(e),
+            _ => Error::Msg(msg),
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        // A request which timed out while connecting was never sent
+        if e.is_timeout() && !e.is_connect() {
+            Error::Timeout(e)
+        } else if e.is_builder() {
+            Error::Request(e)
+        } else if is_tls_error(&e) {
+            Error::Tls(e)
+        } else if e.is_connect() {
+            Error::Connection(e)
+        } else {
+            Error::ReqError(e)
+        }
+    }
+}
+
+/// reqwest does not expose TLS failures, they are only described by the underlying errors
+fn is_tls_error(e: &reqwest::Error) -> bool {
+    let mut source = std::error::Error::source(e);
+    while let Some(cause) = source {
+        let description = cause.to_string().to_lowercase();
+        if ["certificate", "tls", "ssl"]
+            .iter()
+            .any(|keyword| description.contains(keyword))
+        {
+            return true;
+        }
+        source = cause.source();
+    }
+    false
+}
+
+fn clock_drift_hint(time_offset: &Option<i64>) -> String {
+    match time_offset {
+        Some(offset) => format!(
+            "the server clock is {}ms off the local clock, sync the system clock or increase recv_window",
+            offset
+        ),
+        None => "the local clock has likely drifted from the server clock, sync the system clock or enable time \
+                 sync"
+            .to_string(),
+    }
+}
+
 /// Custom error messages
 pub mod error_messages {
     pub const INVALID_PRICE: &str = "Invalid price.";
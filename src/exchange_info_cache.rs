@@ -0,0 +1,160 @@
+//! Cache of the spot exchange information, to look symbols and filters up without a request each time.
+//!
+//! `exchangeInfo` is heavy in request weight and rarely changes. Binance does not support conditional
+//! requests on it, so the cache is refreshed once it is older than its time to live, or on demand.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::sync::RwLock;
+use tokio::time::Instant;
+
+use crate::errors::*;
+use crate::general::General;
+use crate::rest_model::{ExchangeInformation, Filters, Symbol};
+
+/// Exchange information fetched at most once per time to live, shared by the clones of the cache
+/// # Examples
+/// ```rust,no_run
+/// use std::time::Duration;
+/// use binance::{api::*, config::*, exchange_info_cache::*, general::*};
+/// let general: General = Binance::new(None, None);
+/// let cache = ExchangeInfoCache::new(general, Duration::from_secs(3600));
+/// tokio_test::block_on(async {
+///     let symbol = cache.symbol("BTCUSDT").await.unwrap();
+///     // served from the cache
+///     let filters = cache.filters("BTCUSDT").await.unwrap();
+///     println!("{:?} {:?}", symbol.status, filters);
+/// });
+/// ```
+#[derive(Clone)]
+pub struct ExchangeInfoCache {
+    general: General,
+    ttl: Duration,
+    cached: Arc<RwLock<Option<Arc<Cached>>>>,
+}
+
+struct Cached {
+    info: Arc<ExchangeInformation>,
+    /// Index of the symbols in `info`, by name
+    symbols: HashMap<String, usize>,
+    fetched_at: Instant,
+}
+
+impl ExchangeInfoCache {
+    /// Empty cache, filled by the first lookup
+    pub fn new(general: General, ttl: Duration) -> Self {
+        Self {
+            general,
+            ttl,
+            cached: Arc::new(RwLock::new(None)),
+        }
+    }
+
+    /// The exchange information, fetched again if the cache is stale
+    pub async fn info(&self) -> Result<Arc<ExchangeInformation>> { Ok(self.cached().await?.info.clone()) }
+
+    /// Fetch the exchange information now, whether the cache is stale or not
+    pub async fn refresh(&self) -> Result<Arc<ExchangeInformation>> {
+        let mut cached = self.cached.write().await;
+        Ok(self.fetch(&mut cached).await?.info.clone())
+    }
+
+    /// Drop the cached information, the next lookup fetches it again
+    pub async fn invalidate(&self) { self.cached.write().await.take(); }
+
+    /// Information of one symbol
+    pub async fn symbol<S: AsRef<str>>(&self, symbol: S) -> Result<Symbol> {
+        let symbol = symbol.as_ref().to_uppercase();
+        let cached = self.cached().await?;
+        match cached.symbols.get(&symbol) {
+            Some(i) => Ok(cached.info.symbols[*i].clone()),
+            None => Err(Error::UnknownSymbol(symbol)),
+        }
+    }
+
+    /// Trading rules of one symbol
+    pub async fn filters<S: AsRef<str>>(&self, symbol: S) -> Result<Vec<Filters>> {
+        Ok(self.symbol(symbol).await?.filters)
+    }
+
+    /// Information of all the symbols
+    pub async fn all_symbols(&self) -> Result<Vec<Symbol>> { Ok(self.info().await?.symbols.clone()) }
+
+    async fn cached(&self) -> Result<Arc<Cached>> {
+        if let Some(cached) = self.cached.read().await.as_ref().filter(|c| self.is_fresh(c)) {
+            return Ok(cached.clone());
+        }
+        let mut cached = self.cached.write().await;
+        // Another task may have refreshed it while waiting for the lock
+        if let Some(fresh) = cached.as_ref().filter(|c| self.is_fresh(c)) {
+            return Ok(fresh.clone());
+        }
+        self.fetch(&mut cached).await
+    }
+
+    fn is_fresh(&self, cached: &Cached) -> bool { cached.fetched_at.elapsed() < self.ttl }
+
+    async fn fetch(&self, cached: &mut Option<Arc<Cached>>) -> Result<Arc<Cached>> {
+        let info = Arc::new(self.general.exchange_info().await?);
+        let symbols = info
+            .symbols
+            .iter()
+            .enumerate()
+            .map(|(i, symbol)| (symbol.symbol.clone(), i))
+            .collect();
+        let fresh = Arc::new(Cached {
+            info,
+            symbols,
+            fetched_at: Instant::now(),
+        });
+        *cached = Some(fresh.clone());
+        Ok(fresh)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::path::PathBuf;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::api::Binance;
+    use crate::config::Config;
+    use crate::test_server::{ok, routed_server};
+
+    /// Serve the exchange information fixture, counting the requests
+    async fn exchange_info_server(requests: Arc<AtomicUsize>) -> String {
+        let mut fixture = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        fixture.push("test_data/exchangeInfo.json");
+        let body = std::fs::read_to_string(fixture).unwrap();
+        routed_server(move |request| {
+            assert!(request.starts_with("GET /api/v3/exchangeInfo"));
+            requests.fetch_add(1, Ordering::SeqCst);
+            ok(&body)
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn serves_lookups_from_the_cache() {
+        let requests = Arc::new(AtomicUsize::new(0));
+        let host = exchange_info_server(requests.clone()).await;
+        let general: General = Binance::new_with_config(None, None, &Config::default().set_rest_api_endpoint(host));
+
+        let cache = ExchangeInfoCache::new(general.clone(), Duration::from_secs(3600));
+        assert_eq!(cache.symbol("ethbtc").await.unwrap().base_asset, "ETH");
+        assert!(!cache.filters("LTCBTC").await.unwrap().is_empty());
+        assert_eq!(cache.all_symbols().await.unwrap().len(), 841);
+        assert!(matches!(cache.symbol("NOPE").await, Err(Error::UnknownSymbol(_))));
+        assert_eq!(requests.load(Ordering::SeqCst), 1);
+        cache.refresh().await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 2);
+
+        let stale = ExchangeInfoCache::new(general, Duration::ZERO);
+        stale.symbol("ETHBTC").await.unwrap();
+        stale.symbol("ETHBTC").await.unwrap();
+        assert_eq!(requests.load(Ordering::SeqCst), 4);
+    }
+}
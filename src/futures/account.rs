@@ -1,18 +1,26 @@
 use std::collections::BTreeMap;
 use std::fmt;
 
+use futures::stream::{self, Stream};
+#[cfg(feature = "csv_export")]
+use futures::TryStreamExt;
 use serde::Serializer;
 
 use crate::account::OrderCancellation;
 use crate::client::Client;
 use crate::errors::*;
 use crate::rest_model::{OrderSide, TimeInForce};
-use crate::rest_model::{PairAndWindowQuery, PairQuery};
+use crate::rest_model::{PairAndWindowQuery, PairQuery, Success};
 use crate::util::*;
 
 use super::rest_model::{
-    AccountBalance, AccountInfo, CanceledOrder, ChangeLeverageResponse, OpenOrder, OrderType, Position, Transaction,
+    AccountBalance, AccountInfo, AdlQuantile, CanceledOrder, ChangeLeverageResponse, CountdownCancelAll, Income,
+    LeverageBracket, OpenOrder, OrderType, Position, PositionMarginResponse, SymbolBrackets, Transaction,
 };
+pub use super::rest_model::WorkingType;
+
+/// Maximum number of incomes returned by a single income call
+const INCOME_MAX_LIMIT: usize = 1000;
 
 #[derive(Clone)]
 pub struct FuturesAccount {
@@ -20,21 +28,120 @@ pub struct FuturesAccount {
     pub recv_window: u64,
 }
 
-#[derive(Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum PositionSide {
     Both,
     Long,
     Short,
 }
 
-#[derive(Deserialize, Serialize)]
-#[serde(rename_all = "camelCase")]
-pub enum WorkingType {
-    MarkPrice,
-    ContractPrice,
-    #[serde(other)]
-    Other,
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum MarginType {
+    Isolated,
+    Crossed,
+}
+
+/// Direction of an isolated position margin adjustment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionMarginType {
+    Add = 1,
+    Reduce = 2,
+}
+
+/// Inputs of the liquidation price estimation for a single position
+///
+/// The estimation follows the formula documented by Binance for USD-M futures in one-way mode:
+///
+/// `LP = (WB - TMM + UPNL + cum - amount * entry_price) / (|amount| * MMR - amount)`
+///
+/// where `WB` is the wallet balance backing the position, `TMM` and `UPNL` are the maintenance margin and
+/// unrealized PnL of all *other* positions sharing that balance (zero in isolated mode), and `MMR`/`cum`
+/// come from the leverage bracket the position notional falls into at the liquidation price.
+/// Funding, fees and the insurance clearance are not taken into account.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LiquidationPriceParams {
+    pub margin_type: MarginType,
+    pub entry_price: f64,
+    /// Signed position size, positive when long and negative when short
+    pub position_amount: f64,
+    /// Isolated wallet balance of the position, or the cross wallet balance in cross margin mode
+    pub wallet_balance: f64,
+    /// Maintenance margin of the other cross positions, ignored in isolated mode
+    pub other_maint_margin: f64,
+    /// Unrealized PnL of the other cross positions, ignored in isolated mode
+    pub other_unrealized_pnl: f64,
+}
+
+impl LiquidationPriceParams {
+    /// What-if parameters for a new isolated position opened at `entry_price` with the given leverage,
+    /// the isolated wallet balance is the initial margin
+    pub fn isolated(entry_price: f64, position_amount: f64, leverage: u8) -> Self {
+        LiquidationPriceParams {
+            margin_type: MarginType::Isolated,
+            entry_price,
+            position_amount,
+            wallet_balance: position_amount.abs() * entry_price / f64::from(leverage.max(1)),
+            other_maint_margin: 0.0,
+            other_unrealized_pnl: 0.0,
+        }
+    }
+
+    /// What-if parameters for a position in cross margin mode
+    pub fn cross(entry_price: f64, position_amount: f64, cross_wallet_balance: f64) -> Self {
+        LiquidationPriceParams {
+            margin_type: MarginType::Crossed,
+            entry_price,
+            position_amount,
+            wallet_balance: cross_wallet_balance,
+            other_maint_margin: 0.0,
+            other_unrealized_pnl: 0.0,
+        }
+    }
+
+    fn liquidation_price_with(&self, bracket: &LeverageBracket) -> f64 {
+        let (other_mm, other_upnl) = match self.margin_type {
+            MarginType::Isolated => (0.0, 0.0),
+            MarginType::Crossed => (self.other_maint_margin, self.other_unrealized_pnl),
+        };
+        let amount = self.position_amount;
+        (self.wallet_balance - other_mm + other_upnl + bracket.cum - amount * self.entry_price)
+            / (amount.abs() * bracket.maint_margin_ratio - amount)
+    }
+
+    /// Estimated liquidation price, `None` if the position is empty or cannot be liquidated
+    pub fn liquidation_price(&self, brackets: &SymbolBrackets) -> Option<f64> {
+        if self.position_amount == 0.0 {
+            return None;
+        }
+        // The bracket depends on the notional at the liquidation price, keep the one that is self-consistent
+        let price = brackets
+            .brackets
+            .iter()
+            .map(|b| (b, self.liquidation_price_with(b)))
+            .find(|(b, lp)| {
+                let notional = self.position_amount.abs() * lp;
+                notional >= b.notional_floor && notional < b.notional_cap
+            })
+            .map(|(_, lp)| lp)
+            .or_else(|| brackets.brackets.first().map(|b| self.liquidation_price_with(b)))?;
+        if price.is_finite() && price > 0.0 {
+            Some(price)
+        } else {
+            None
+        }
+    }
+}
+
+/// Liquidation price recomputed locally next to the one reported by Binance
+#[derive(Debug, Clone)]
+pub struct LiquidationEstimate {
+    pub symbol: String,
+    pub position_side: String,
+    pub params: LiquidationPriceParams,
+    pub estimated_liquidation_price: Option<f64>,
+    pub reported_liquidation_price: f64,
 }
 
 /// Serialize bool as str
@@ -47,7 +154,7 @@ where
 }
 
 /// Serialize opt bool as str
-fn serialize_opt_as_uppercase<S, T>(t: &Option<T>, serializer: S) -> std::result::Result<S::Ok, S::Error>
+pub(crate) fn serialize_opt_as_uppercase<S, T>(t: &Option<T>, serializer: S) -> std::result::Result<S::Ok, S::Error>
 where
     S: Serializer,
     T: ToString,
@@ -58,9 +165,11 @@ where
     }
 }
 
-#[derive(Serialize)]
+/// Order Request
+/// perform an order for the futures account
+#[derive(Default, Debug, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
-struct OrderRequest {
+pub struct OrderRequest {
     pub symbol: String,
     pub side: OrderSide,
     pub position_side: Option<PositionSide>,
@@ -75,11 +184,57 @@ struct OrderRequest {
     pub close_position: Option<bool>,
     pub activation_price: Option<f64>,
     pub callback_rate: Option<f64>,
+    /// Price the stop price is compared to, only for stop and take profit orders. Default CONTRACT_PRICE
     pub working_type: Option<WorkingType>,
+    /// Protect against triggering on an abnormal price, only for stop and take profit orders. Default false
     #[serde(serialize_with = "serialize_opt_as_uppercase")]
     pub price_protect: Option<bool>,
 }
 
+impl OrderRequest {
+    fn valid(&self) -> Result<()> {
+        let is_conditional = matches!(
+            self.order_type,
+            OrderType::Stop
+                | OrderType::StopMarket
+                | OrderType::TakeProfit
+                | OrderType::TakeProfitMarket
+                | OrderType::TrailingStopMarket
+        );
+        if !is_conditional && (self.working_type.is_some() || self.price_protect.is_some()) {
+            return Err(Error::InvalidOrderError {
+                msg: "Working type and price protect can only be set for stop and take profit orders".to_string(),
+            });
+        }
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct PositionMarginRequest {
+    symbol: String,
+    position_side: Option<PositionSide>,
+    amount: f64,
+    #[serde(rename = "type")]
+    adjustment_type: u8,
+}
+
+/// Positions of a symbol are either listed in an array or returned as a single object
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum AdlQuantileResponse {
+    All(Vec<AdlQuantile>),
+    One(AdlQuantile),
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct CountdownCancelAllRequest {
+    symbol: String,
+    countdown_time: u64,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 struct ChangePositionModeRequest {
@@ -88,6 +243,12 @@ struct ChangePositionModeRequest {
 }
 
 impl FuturesAccount {
+    /// Place an order
+    pub async fn place_order(&self, order: OrderRequest) -> Result<Transaction> {
+        order.valid()?;
+        self.post_order(order).await
+    }
+
     async fn post_order(&self, order: OrderRequest) -> Result<Transaction> {
         self.client
             .post_signed_p("/fapi/v1/order", order, self.recv_window)
@@ -218,6 +379,62 @@ impl FuturesAccount {
             .await
     }
 
+    /// Recompute the liquidation price of the open positions of a symbol, see [`LiquidationPriceParams`]
+    /// for the formula. In cross margin mode the maintenance margin and unrealized PnL of the other positions
+    /// are derived from the account totals.
+    pub async fn estimated_liquidation_price<S>(&self, symbol: S) -> Result<Vec<LiquidationEstimate>>
+    where
+        S: Into<String>,
+    {
+        let symbol = symbol.into();
+        let positions = self.position_information(symbol.as_str()).await?;
+        let brackets = self.leverage_brackets(symbol.as_str()).await?;
+        let account = if positions.iter().any(|p| p.margin_type != "isolated" && p.position_amount != 0.0) {
+            Some(self.account_info().await?)
+        } else {
+            None
+        };
+
+        Ok(positions
+            .into_iter()
+            .filter(|p| p.position_amount != 0.0)
+            .map(|p| {
+                let params = match &account {
+                    Some(account) if p.margin_type != "isolated" => {
+                        let own_mm = brackets
+                            .maintenance_margin(p.position_amount * p.mark_price)
+                            .unwrap_or_default();
+                        LiquidationPriceParams {
+                            other_maint_margin: account.total_maint_margin.unwrap_or_default() - own_mm,
+                            other_unrealized_pnl: account.total_cross_unrealized_pnl.unwrap_or_default()
+                                - p.unrealized_profit,
+                            ..LiquidationPriceParams::cross(
+                                p.entry_price,
+                                p.position_amount,
+                                account.total_cross_wallet_balance.unwrap_or(account.total_wallet_balance),
+                            )
+                        }
+                    }
+                    _ => LiquidationPriceParams {
+                        margin_type: MarginType::Isolated,
+                        entry_price: p.entry_price,
+                        position_amount: p.position_amount,
+                        wallet_balance: p.isolated_margin - p.unrealized_profit,
+                        other_maint_margin: 0.0,
+                        other_unrealized_pnl: 0.0,
+                    },
+                };
+                LiquidationEstimate {
+                    estimated_liquidation_price: params.liquidation_price(&brackets),
+                    symbol: p.symbol,
+                    position_side: p.position_side,
+                    params,
+                    reported_liquidation_price: p.liquidation_price,
+                }
+            })
+            .collect())
+    }
+
     pub async fn account_info(&self) -> Result<AccountInfo> {
         let parameters = BTreeMap::new();
         let request = build_signed_request(parameters, self.recv_window)?;
@@ -230,6 +447,140 @@ impl FuturesAccount {
         self.client.get_signed_d("/fapi/v2/balance", request.as_str()).await
     }
 
+    /// Income history within a time range, of all symbols when `symbol` is `None` and of all types when
+    /// `income_type` is `None`, one page at a time. The next page is only fetched once the previous one has
+    /// been consumed. Without `start_time`, Binance only returns the incomes of the last 7 days.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, futures::account::*, config::*};
+    /// use futures::TryStreamExt;
+    /// let account: FuturesAccount = Binance::new_with_env(&Config::testnet());
+    /// tokio_test::block_on(async {
+    ///     let pages = account.income_history_pages(None, Some("FUNDING_FEE"), Some(1_640_995_200_000), None);
+    ///     let mut pages = Box::pin(pages);
+    ///     while let Some(page) = pages.try_next().await.unwrap() {
+    ///         println!("{} incomes", page.len());
+    ///     }
+    /// });
+    /// ```
+    pub fn income_history_pages<'a>(
+        &'a self,
+        symbol: Option<&'a str>,
+        income_type: Option<&'a str>,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> impl Stream<Item = Result<Vec<Income>>> + 'a {
+        // `None` once the last page was fetched, otherwise the start of the next page along with the ids of the
+        // incomes already returned at that time, pages start at the time of the last income of the previous one
+        let first: Option<(Option<u64>, Vec<u64>)> = Some((start_time, vec![]));
+        stream::try_unfold(first, move |next| async move {
+            let (start, seen) = match next {
+                Some(next) => next,
+                None => return Ok(None),
+            };
+            let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+            parameters.insert("limit".into(), INCOME_MAX_LIMIT.to_string());
+            if let Some(symbol) = symbol {
+                parameters.insert("symbol".into(), symbol.to_string());
+            }
+            if let Some(income_type) = income_type {
+                parameters.insert("incomeType".into(), income_type.to_string());
+            }
+            if let Some(start) = start {
+                parameters.insert("startTime".into(), start.to_string());
+            }
+            if let Some(end) = end_time {
+                parameters.insert("endTime".into(), end.to_string());
+            }
+
+            let request = build_signed_request(parameters, self.recv_window)?;
+            let page: Vec<Income> = self.client.get_signed_d("/fapi/v1/income", &request).await?;
+
+            let last_page = page.len() < INCOME_MAX_LIMIT;
+            let next_start = page.last().map(|i| i.time);
+            let seen_at_next: Vec<u64> = page
+                .iter()
+                .filter(|i| Some(i.time) == next_start)
+                .map(|i| i.tran_id)
+                .collect();
+            let incomes: Vec<Income> = page
+                .into_iter()
+                .filter(|i| !(Some(i.time) == start && seen.contains(&i.tran_id)))
+                .collect();
+            // a full page of incomes already returned cannot move the start forward
+            let next = if last_page || incomes.is_empty() {
+                None
+            } else {
+                Some((next_start, seen_at_next))
+            };
+            Ok(Some((incomes, next)))
+        })
+    }
+
+    /// Write the income history within a time range to `writer` as CSV, page by page: the history is never held
+    /// in memory as a whole. `progress` is called with the number of incomes written so far after each page,
+    /// which is flushed. Returns the number of incomes written.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, futures::account::*, config::*};
+    /// let account: FuturesAccount = Binance::new_with_env(&Config::testnet());
+    /// let file = std::fs::File::create("income.csv").unwrap();
+    /// let export = account.export_income_csv(None, None, None, None, file, |n| println!("{} incomes", n));
+    /// let written = tokio_test::block_on(export);
+    /// ```
+    #[cfg(feature = "csv_export")]
+    pub async fn export_income_csv<W, F>(
+        &self,
+        symbol: Option<&str>,
+        income_type: Option<&str>,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+        writer: W,
+        mut progress: F,
+    ) -> Result<usize>
+    where
+        W: std::io::Write,
+        F: FnMut(usize),
+    {
+        let mut writer = csv::Writer::from_writer(writer);
+        let mut written = 0;
+        let mut pages = Box::pin(self.income_history_pages(symbol, income_type, start_time, end_time));
+        while let Some(page) = pages.try_next().await? {
+            for income in &page {
+                writer.serialize(income)?;
+            }
+            writer.flush()?;
+            written += page.len();
+            progress(written);
+        }
+        Ok(written)
+    }
+
+    /// Notional and leverage brackets for a symbol
+    /// https://binance-docs.github.io/apidocs/futures/en/#notional-and-leverage-brackets-user_data
+    pub async fn leverage_brackets<S>(&self, symbol: S) -> Result<SymbolBrackets>
+    where
+        S: Into<String>,
+    {
+        self.client
+            .get_signed_p(
+                "/fapi/v1/leverageBracket",
+                Some(PairAndWindowQuery {
+                    symbol: symbol.into(),
+                    recv_window: self.recv_window,
+                }),
+                self.recv_window,
+            )
+            .await
+    }
+
+    /// Notional and leverage brackets for all symbols
+    pub async fn all_leverage_brackets(&self) -> Result<Vec<SymbolBrackets>> {
+        let parameters = BTreeMap::new();
+        let request = build_signed_request(parameters, self.recv_window)?;
+        self.client.get_signed_d("/fapi/v1/leverageBracket", request.as_str()).await
+    }
+
     pub async fn change_initial_leverage<S>(&self, symbol: S, leverage: u8) -> Result<ChangeLeverageResponse>
     where
         S: Into<String>,
@@ -242,9 +593,60 @@ impl FuturesAccount {
         self.client.post_signed_d("/fapi/v1/leverage", request.as_str()).await
     }
 
+    /// Auto-deleveraging quantile of the positions of a symbol, updated every 30 seconds
+    pub async fn adl_quantile<S>(&self, symbol: S) -> Result<AdlQuantile>
+    where
+        S: Into<String>,
+    {
+        let symbol = symbol.into();
+        let quantiles = self
+            .client
+            .get_signed_p(
+                "/fapi/v1/adlQuantile",
+                Some(PairQuery { symbol: symbol.clone() }),
+                self.recv_window,
+            )
+            .await?;
+        match quantiles {
+            AdlQuantileResponse::One(quantile) => Ok(quantile),
+            AdlQuantileResponse::All(quantiles) => quantiles
+                .into_iter()
+                .find(|q| q.symbol == symbol)
+                .ok_or(Error::UnknownSymbol(symbol)),
+        }
+    }
+
+    /// Auto-deleveraging quantiles of all the open positions
+    pub async fn all_adl_quantiles(&self) -> Result<Vec<AdlQuantile>> {
+        let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
+        self.client.get_signed_d("/fapi/v1/adlQuantile", request.as_str()).await
+    }
+
+    /// Add margin to or remove margin from an isolated position, `position_side` is mandatory in hedge mode
+    pub async fn adjust_position_margin<S>(
+        &self,
+        symbol: S,
+        amount: f64,
+        position_side: Option<PositionSide>,
+        adjustment_type: PositionMarginType,
+    ) -> Result<PositionMarginResponse>
+    where
+        S: Into<String>,
+    {
+        let request = PositionMarginRequest {
+            symbol: symbol.into(),
+            position_side,
+            amount,
+            adjustment_type: adjustment_type as u8,
+        };
+        self.client
+            .post_signed_p("/fapi/v1/positionMargin", request, self.recv_window)
+            .await
+    }
+
     pub async fn change_position_mode(&self, dual_side_position: bool) -> Result<()> {
         self.client
-            .post_signed_p(
+            .post_signed_p::<Success, _>(
                 "/fapi/v1/positionSide/dual",
                 ChangePositionModeRequest { dual_side_position },
                 self.recv_window,
@@ -272,7 +674,7 @@ impl FuturesAccount {
         S: Into<String>,
     {
         self.client
-            .delete_signed_p(
+            .delete_signed_p::<Success, _>(
                 "/fapi/v1/allOpenOrders",
                 PairQuery { symbol: symbol.into() },
                 self.recv_window,
@@ -280,4 +682,209 @@ impl FuturesAccount {
             .await?;
         Ok(())
     }
+
+    /// Arm a timer canceling all the open orders of `symbol` after `countdown_ms`, a countdown of 0 disarms it.
+    ///
+    /// This is a dead man's switch : the timer must be armed again before it runs out, like a heartbeat, for
+    /// as long as the orders should stay open. Binance checks the timers about every 10ms.
+    pub async fn countdown_cancel_all<S>(&self, symbol: S, countdown_ms: u64) -> Result<CountdownCancelAll>
+    where
+        S: Into<String>,
+    {
+        self.client
+            .post_signed_p(
+                "/fapi/v1/countdownCancelAll",
+                CountdownCancelAllRequest {
+                    symbol: symbol.into(),
+                    countdown_time: countdown_ms,
+                },
+                self.recv_window,
+            )
+            .await
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        AdlQuantileResponse, CountdownCancelAllRequest, FuturesAccount, LiquidationPriceParams, OrderRequest,
+        PositionMarginRequest, PositionMarginType, PositionSide, WorkingType, INCOME_MAX_LIMIT,
+    };
+    use crate::client::Client;
+    use crate::futures::rest_model::{CountdownCancelAll, LeverageBracket, OrderType, SymbolBrackets};
+    use crate::util::build_request_p;
+    use crate::test_server::{ok, scripted_server};
+    use futures::TryStreamExt;
+
+    fn incomes(ids: std::ops::Range<u64>, time: impl Fn(u64) -> u64) -> String {
+        let incomes: Vec<String> = ids
+            .map(|id| {
+                format!(
+                    r#"{{"symbol":"BTCUSDT","incomeType":"FUNDING_FEE","income":"-0.1","asset":"USDT","info":"",
+                    "time":{},"tranId":{},"tradeId":""}}"#,
+                    time(id),
+                    id
+                )
+            })
+            .collect();
+        format!("[{}]", incomes.join(","))
+    }
+
+    fn brackets() -> SymbolBrackets {
+        SymbolBrackets {
+            symbol: "BTCUSDT".to_string(),
+            notional_coef: None,
+            brackets: vec![
+                LeverageBracket {
+                    bracket: 1,
+                    initial_leverage: 125,
+                    notional_cap: 50_000.0,
+                    notional_floor: 0.0,
+                    maint_margin_ratio: 0.004,
+                    cum: 0.0,
+                },
+                LeverageBracket {
+                    bracket: 2,
+                    initial_leverage: 100,
+                    notional_cap: 250_000.0,
+                    notional_floor: 50_000.0,
+                    maint_margin_ratio: 0.005,
+                    cum: 50.0,
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn isolated_liquidation_price() {
+        let long = LiquidationPriceParams::isolated(20_000.0, 1.0, 10);
+        let lp = long.liquidation_price(&brackets()).unwrap();
+        assert!((lp - 18_072.289_156_626_5).abs() < 1e-6, "{}", lp);
+
+        let short = LiquidationPriceParams::isolated(20_000.0, -1.0, 10);
+        let lp = short.liquidation_price(&brackets()).unwrap();
+        assert!((lp - 21_912.350_597_609_56).abs() < 1e-6, "{}", lp);
+    }
+
+    #[test]
+    fn cross_liquidation_price_uses_matching_bracket() {
+        let params = LiquidationPriceParams::cross(20_000.0, 3.0, 10_000.0);
+        let lp = params.liquidation_price(&brackets()).unwrap();
+        // 3 * lp is above 50k so the second bracket applies
+        assert!((lp - (60_000.0 - 10_000.0 - 50.0) / (3.0 * 0.995)).abs() < 1e-6, "{}", lp);
+
+        let unliquidable = LiquidationPriceParams::cross(20_000.0, 1.0, 30_000.0);
+        assert_eq!(unliquidable.liquidation_price(&brackets()), None);
+    }
+
+    #[test]
+    fn working_type_only_for_conditional_orders() {
+        let stop = OrderRequest {
+            symbol: "BTCUSDT".to_string(),
+            order_type: OrderType::StopMarket,
+            stop_price: Some(20_000.0),
+            working_type: Some(WorkingType::MarkPrice),
+            price_protect: Some(true),
+            ..OrderRequest::default()
+        };
+        assert!(stop.valid().is_ok());
+        let query = build_request_p(&stop).unwrap();
+        assert!(query.contains("workingType=MARK_PRICE"), "{}", query);
+        assert!(query.contains("priceProtect=TRUE"), "{}", query);
+
+        let limit = OrderRequest {
+            order_type: OrderType::Limit,
+            ..stop
+        };
+        assert!(limit.valid().is_err());
+    }
+
+    #[test]
+    fn adl_quantiles_and_position_margin() {
+        let json = r#"[
+            {"symbol": "ETHUSDT", "adlQuantile": {"LONG": 3, "SHORT": 3, "HEDGE": 0}},
+            {"symbol": "BTCUSDT", "adlQuantile": {"LONG": 1, "SHORT": 2, "BOTH": 0}}
+        ]"#;
+        let quantiles: AdlQuantileResponse = serde_json::from_str(json).unwrap();
+        let quantiles = match quantiles {
+            AdlQuantileResponse::All(quantiles) => quantiles,
+            AdlQuantileResponse::One(_) => panic!("expected all quantiles"),
+        };
+        assert_eq!(quantiles[0].adl_quantile.hedge, Some(0));
+        assert_eq!(quantiles[1].adl_quantile.both, Some(0));
+        assert_eq!(quantiles[1].adl_quantile.highest(), Some(2));
+
+        let request = PositionMarginRequest {
+            symbol: "BTCUSDT".to_string(),
+            position_side: Some(PositionSide::Long),
+            amount: 100.0,
+            adjustment_type: PositionMarginType::Reduce as u8,
+        };
+        assert_eq!(
+            build_request_p(&request).unwrap(),
+            "symbol=BTCUSDT&positionSide=LONG&amount=100&type=2"
+        );
+    }
+
+    #[test]
+    fn countdown_cancel_all() {
+        let request = CountdownCancelAllRequest {
+            symbol: "BTCUSDT".to_string(),
+            countdown_time: 120_000,
+        };
+        assert_eq!(
+            build_request_p(&request).unwrap(),
+            "symbol=BTCUSDT&countdownTime=120000"
+        );
+        let response: CountdownCancelAll =
+            serde_json::from_str(r#"{"symbol": "BTCUSDT", "countdownTime": "100000"}"#).unwrap();
+        assert_eq!(response.countdown_time, 100_000);
+    }
+
+    #[tokio::test]
+    async fn income_pages_resume_at_the_last_income() {
+        let limit = INCOME_MAX_LIMIT as u64;
+        // the last two incomes of the first page share their time, the second page starts with them again
+        let (host, server) = scripted_server(vec![
+            ok(&incomes(0..limit, |id| 1000 + id.min(limit - 2))),
+            ok(&incomes(limit - 2..limit + 3, |id| 1000 + id.max(limit - 1) - 1)),
+        ])
+        .await;
+
+        let account = FuturesAccount {
+            client: Client::new(Some("key".into()), Some("secret".into()), host),
+            recv_window: 5000,
+        };
+        let pages: Vec<_> = account
+            .income_history_pages(Some("BTCUSDT"), None, Some(1000), None)
+            .try_collect()
+            .await
+            .unwrap();
+        let ids: Vec<u64> = pages.iter().flatten().map(|i| i.tran_id).collect();
+        assert_eq!(ids, (0..limit + 3).collect::<Vec<_>>());
+        let requests = server.await.unwrap();
+        assert!(requests[0].contains("startTime=1000&"), "{}", requests[0]);
+        assert!(requests[1].contains(&format!("startTime={}&", 1000 + limit - 2)), "{}", requests[1]);
+    }
+
+    #[cfg(feature = "csv_export")]
+    #[tokio::test]
+    async fn exports_income_as_csv() {
+        let (host, _server) = scripted_server(vec![ok(&incomes(7..9, |id| id))]).await;
+
+        let account = FuturesAccount {
+            client: Client::new(Some("key".into()), Some("secret".into()), host),
+            recv_window: 5000,
+        };
+        let mut csv = Vec::new();
+        let written = account
+            .export_income_csv(None, Some("FUNDING_FEE"), None, None, &mut csv, |_| {})
+            .await
+            .unwrap();
+        assert_eq!(written, 2);
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], "symbol,incomeType,income,asset,info,time,tranId,tradeId");
+        assert_eq!(lines[1], "BTCUSDT,FUNDING_FEE,-0.1,USDT,,7,7,");
+    }
 }
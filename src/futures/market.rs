@@ -1,3 +1,5 @@
+use std::collections::BTreeMap;
+
 use serde_json::Value;
 
 use crate::client::*;
@@ -10,6 +12,9 @@ use crate::util::*;
 //TODO: find out the repartition of kline/candlestick columns in the future kline rows
 //TODO: make limit optional where applicable
 
+/// Depth limits accepted by /fapi/v1/depth
+pub(crate) static DEPTH_LIMITS: [u16; 7] = [5, 10, 20, 50, 100, 500, 1000];
+
 #[derive(Clone)]
 pub struct FuturesMarket {
     pub client: Client,
@@ -27,6 +32,22 @@ impl FuturesMarket {
             .await
     }
 
+    /// Order book with a custom depth limit
+    /// Supported limits are: 5, 10, 20, 50, 100, 500, 1000
+    pub async fn get_custom_depth<S>(&self, symbol: S, limit: u16) -> Result<OrderBook>
+    where
+        S: Into<String>,
+    {
+        if !DEPTH_LIMITS.contains(&limit) {
+            return Err(Error::InvalidLimit(limit));
+        }
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("symbol".into(), symbol.into());
+        parameters.insert("limit".into(), limit.to_string());
+        let request = build_request(&parameters);
+        self.client.get_p("/fapi/v1/depth", &request).await
+    }
+
     /// Get trades for a pair
     pub async fn get_trades<S>(&self, symbol: S) -> Result<Trades>
     where
@@ -45,7 +66,7 @@ impl FuturesMarket {
         S3: Into<u16>,
     {
         self.client
-            .get_signed_p(
+            .get_keyed_d(
                 "/fapi/v1/historicalTrades",
                 Some(HistoryQuery {
                     start_time: None,
@@ -56,7 +77,6 @@ impl FuturesMarket {
                     interval: None,
                     period: None,
                 }),
-                self.recv_window,
             )
             .await
     }
@@ -78,7 +98,7 @@ impl FuturesMarket {
         S5: Into<u16>,
     {
         self.client
-            .get_signed_p(
+            .get_d(
                 "/fapi/v1/aggTrades",
                 Some(HistoryQuery {
                     start_time: start_time.into(),
@@ -89,7 +109,6 @@ impl FuturesMarket {
                     interval: None,
                     period: None,
                 }),
-                self.recv_window,
             )
             .await
     }
@@ -109,7 +128,7 @@ impl FuturesMarket {
         S5: Into<u16>,
     {
         self.client
-            .get_signed_p(
+            .get_d(
                 "/fapi/v1/fundingRate",
                 Some(HistoryQuery {
                     start_time: start_time.into(),
@@ -120,12 +139,23 @@ impl FuturesMarket {
                     interval: None,
                     period: None,
                 }),
-                self.recv_window,
             )
             .await
     }
 
     /// Get open interest history
+    ///
+    /// This and the long/short ratios are market statistics served under `/futures/data` rather than
+    /// `/fapi/v1`, on the same host. `period` is one of [`PERIODS`], see [`StatisticsPeriod`], and only
+    /// the last 30 days are available.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, config::*, futures::market::*, futures::rest_model::*};
+    /// let market: FuturesMarket = Binance::new_with_env(&Config::default());
+    /// let period = StatisticsPeriod::OneHour;
+    /// let history = tokio_test::block_on(market.get_open_interest_history("BTCUSDT", period, None, None, 24u16));
+    /// assert!(history.is_ok(), "{:?}", history);
+    /// ```
     pub async fn get_open_interest_history<S1, S2, S3, S4, S5>(
         &self,
         symbol: S1,
@@ -151,9 +181,7 @@ impl FuturesMarket {
             interval: None,
         };
         query.validate()?;
-        self.client
-            .get_signed_p("/futures/data/openInterestHist", Some(query), self.recv_window)
-            .await
+        self.client.get_d("/futures/data/openInterestHist", Some(query)).await
     }
 
     /// Get Top Trader Account Long/Short Ratio
@@ -183,7 +211,7 @@ impl FuturesMarket {
         };
         query.validate()?;
         self.client
-            .get_signed_p("/futures/data/topLongShortAccountRatio", Some(query), self.recv_window)
+            .get_d("/futures/data/topLongShortAccountRatio", Some(query))
             .await
     }
 
@@ -214,7 +242,7 @@ impl FuturesMarket {
         };
         query.validate()?;
         self.client
-            .get_signed_p("/futures/data/topLongShortPositionRatio", Some(query), self.recv_window)
+            .get_d("/futures/data/topLongShortPositionRatio", Some(query))
             .await
     }
 
@@ -245,11 +273,7 @@ impl FuturesMarket {
         };
         query.validate()?;
         self.client
-            .get_signed_p(
-                "/futures/data/globalLongShortAccountRatio",
-                Some(query),
-                self.recv_window,
-            )
+            .get_d("/futures/data/globalLongShortAccountRatio", Some(query))
             .await
     }
 
@@ -261,7 +285,7 @@ impl FuturesMarket {
         start_time: S3,
         end_time: S4,
         limit: S5,
-    ) -> Result<Vec<LongShortRatio>>
+    ) -> Result<Vec<TakerLongShortRatio>>
     where
         S1: Into<String>,
         S2: Into<String>,
@@ -280,7 +304,7 @@ impl FuturesMarket {
         };
         query.validate()?;
         self.client
-            .get_signed_p("/futures/data/takerlongshortRatio", Some(query), self.recv_window)
+            .get_d("/futures/data/takerlongshortRatio", Some(query))
             .await
     }
 
@@ -537,6 +561,7 @@ impl FuturesMarket {
         self.client.get_p("/fapi/v1/allForceOrders", "").await
     }
 
+    /// Present open interest of a symbol
     pub async fn open_interest<S>(&self, symbol: S) -> Result<OpenInterest>
     where
         S: Into<String>,
@@ -1,6 +1,7 @@
 use serde::{Deserialize, Serialize};
 
-use crate::rest_model::{string_or_float, string_or_float_opt, Asks, Bids, RateLimit, SymbolStatus, TimeInForce};
+use crate::rest_model::{string_or_float, string_or_float_opt, string_or_u64, Asks, Bids, RateLimit, SymbolStatus,
+                        TimeInForce};
 pub use crate::rest_model::{BookTickers, KlineSummaries, KlineSummary, ServerTime, SymbolPrice, Tickers};
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -81,6 +82,23 @@ pub enum OrderType {
     Other,
 }
 
+/// Price used to trigger stop and take profit orders
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum WorkingType {
+    MarkPrice,
+    ContractPrice,
+    #[serde(other)]
+    Other,
+}
+
+/// By default, use market orders
+impl Default for OrderType {
+    fn default() -> Self {
+        Self::Market
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(tag = "filterType")]
 pub enum Filters {
@@ -143,11 +161,12 @@ pub enum Filters {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderBook {
+    /// Used with `pu` (previous final update id) of diff depth events to sync a local order book
     pub last_update_id: u64,
-    // Undocumented
+    /// Message output time
     #[serde(rename = "E")]
     pub event_time: u64,
-    // Undocumented
+    /// Transaction time
     #[serde(rename = "T")]
     pub trade_order_time: u64,
     pub bids: Vec<Bids>,
@@ -276,6 +295,7 @@ pub struct OpenInterest {
     #[serde(with = "string_or_float")]
     pub open_interest: f64,
     pub symbol: String,
+    pub time: u64,
 }
 
 // #[derive(Debug, Deserialize, Clone)]
@@ -329,6 +349,12 @@ pub struct OpenOrder {
     pub avg_price: f64,
     #[serde(with = "string_or_float")]
     pub price: f64,
+    #[serde(default, with = "string_or_float_opt")]
+    pub stop_price: Option<f64>,
+    #[serde(rename = "type")]
+    pub order_type: Option<OrderType>,
+    pub working_type: Option<WorkingType>,
+    pub price_protect: Option<bool>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -363,7 +389,7 @@ pub struct Transaction {
     #[serde(default, with = "string_or_float_opt")]
     pub price_rate: Option<f64>,
     pub update_time: u64,
-    pub working_type: String,
+    pub working_type: WorkingType,
     pub price_protect: bool,
 }
 
@@ -399,8 +425,8 @@ pub struct CanceledOrder {
     #[serde(default, with = "string_or_float_opt")]
     pub price_rate: Option<f64>,
     pub update_time: u64,
-    pub working_type: String,
-    price_protect: bool,
+    pub working_type: WorkingType,
+    pub price_protect: bool,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -447,6 +473,24 @@ pub struct AccountBalance {
     pub update_time: u64,
 }
 
+/// Entry of the income history: realized PnL, funding fees, commissions, transfers...
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Income {
+    /// Empty for incomes which are not bound to a symbol, such as transfers
+    pub symbol: String,
+    /// `REALIZED_PNL`, `FUNDING_FEE`, `COMMISSION`, `TRANSFER`...
+    pub income_type: String,
+    #[serde(with = "string_or_float")]
+    pub income: f64,
+    pub asset: String,
+    pub info: String,
+    pub time: u64,
+    pub tran_id: u64,
+    /// Empty for incomes which are not bound to a trade
+    pub trade_id: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountInfoBalance {
@@ -464,6 +508,12 @@ pub struct AccountInfo {
     pub total_unrealized_profit: f64,
     #[serde(with = "string_or_float")]
     pub total_margin_balance: f64,
+    #[serde(with = "string_or_float_opt", default)]
+    pub total_maint_margin: Option<f64>,
+    #[serde(with = "string_or_float_opt", default)]
+    pub total_cross_wallet_balance: Option<f64>,
+    #[serde(with = "string_or_float_opt", default, rename = "totalCrossUnPnl")]
+    pub total_cross_unrealized_pnl: Option<f64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -475,6 +525,53 @@ pub struct ChangeLeverageResponse {
     pub symbol: String,
 }
 
+/// Auto-deleveraging quantile of the positions of a symbol
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AdlQuantile {
+    pub symbol: String,
+    pub adl_quantile: AdlQuantiles,
+}
+
+/// ADL quantiles per position side, from 0 to 4, the higher the closer the position is to deleveraging.
+///
+/// `LONG` and `SHORT` are always present. `BOTH` is set in one-way mode and for isolated positions in
+/// hedge mode, it is replaced by `HEDGE`, a flag without meaning, for cross positions in hedge mode.
+#[derive(Debug, Serialize, Deserialize, Clone, Default, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub struct AdlQuantiles {
+    pub long: Option<u8>,
+    pub short: Option<u8>,
+    pub both: Option<u8>,
+    pub hedge: Option<u8>,
+}
+
+impl AdlQuantiles {
+    /// Highest quantile of the positions, `HEDGE` excluded
+    pub fn highest(&self) -> Option<u8> { [self.long, self.short, self.both].into_iter().flatten().max() }
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionMarginResponse {
+    pub amount: f64,
+    pub code: i64,
+    pub msg: String,
+    /// 1 for an addition, 2 for a reduction
+    #[serde(rename = "type")]
+    pub adjustment_type: u8,
+}
+
+/// Auto-cancel timer of the open orders of a symbol
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct CountdownCancelAll {
+    pub symbol: String,
+    /// Countdown in ms, 0 once disarmed
+    #[serde(with = "string_or_u64")]
+    pub countdown_time: u64,
+}
+
 #[derive(Serialize)]
 #[serde(rename_all = "camelCase")]
 pub(crate) struct HistoryQuery {
@@ -509,7 +606,42 @@ pub struct FundingRate {
 
 pub static PERIODS: &[&str] = &["5m", "15m", "30m", "1h", "2h", "4h", "6h", "12h", "1d"];
 
-#[derive(Deserialize)]
+/// Period of the market statistics under `/futures/data`, one of [`PERIODS`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatisticsPeriod {
+    FiveMinutes,
+    FifteenMinutes,
+    ThirtyMinutes,
+    OneHour,
+    TwoHours,
+    FourHours,
+    SixHours,
+    TwelveHours,
+    OneDay,
+}
+
+impl StatisticsPeriod {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StatisticsPeriod::FiveMinutes => "5m",
+            StatisticsPeriod::FifteenMinutes => "15m",
+            StatisticsPeriod::ThirtyMinutes => "30m",
+            StatisticsPeriod::OneHour => "1h",
+            StatisticsPeriod::TwoHours => "2h",
+            StatisticsPeriod::FourHours => "4h",
+            StatisticsPeriod::SixHours => "6h",
+            StatisticsPeriod::TwelveHours => "12h",
+            StatisticsPeriod::OneDay => "1d",
+        }
+    }
+}
+
+/// Lets the statistics queries take a [`StatisticsPeriod`] as well as a string
+impl From<StatisticsPeriod> for String {
+    fn from(period: StatisticsPeriod) -> Self { period.as_str().to_string() }
+}
+
+#[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OpenInterestHistory {
     pub symbol: String,
@@ -520,7 +652,8 @@ pub struct OpenInterestHistory {
     pub timestamp: u64,
 }
 
-#[derive(Deserialize)]
+/// Long/short ratio of accounts, or of positions for the top trader position ratio
+#[derive(Debug, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LongShortRatio {
     pub symbol: String,
@@ -533,24 +666,55 @@ pub struct LongShortRatio {
     pub timestamp: u64,
 }
 
-#[derive(Deserialize)]
+/// Taker buy and sell volumes over a period
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct TakerLongShortRatio {
+    #[serde(with = "string_or_float")]
+    pub buy_sell_ratio: f64,
+    #[serde(with = "string_or_float")]
+    pub buy_vol: f64,
+    #[serde(with = "string_or_float")]
+    pub sell_vol: f64,
+    pub timestamp: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct LeverageBracket {
     pub bracket: u8,
     pub initial_leverage: u8,
-    pub notional_cap: u64,
-    pub notional_floor: u64,
+    /// Upper bound (exclusive) of the position notional for this bracket
+    pub notional_cap: f64,
+    /// Lower bound (inclusive) of the position notional for this bracket
+    pub notional_floor: f64,
     pub maint_margin_ratio: f64,
-    pub cum: u64,
+    /// Maintenance amount, used for fast calculation of the maintenance margin
+    pub cum: f64,
 }
 
-#[derive(Deserialize)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SymbolBrackets {
     pub symbol: String,
+    /// Ratio of the user's bracket compared to the default one, only present for some accounts
+    pub notional_coef: Option<f64>,
     pub brackets: Vec<LeverageBracket>,
 }
 
+impl SymbolBrackets {
+    /// The bracket a position of the given notional value falls into
+    pub fn bracket_for_notional(&self, notional: f64) -> Option<&LeverageBracket> {
+        let notional = notional.abs();
+        self.brackets.iter().find(|b| notional >= b.notional_floor && notional < b.notional_cap)
+    }
+
+    /// Maintenance margin required for a position of the given notional value
+    pub fn maintenance_margin(&self, notional: f64) -> Option<f64> {
+        self.bracket_for_notional(notional).map(|b| notional.abs() * b.maint_margin_ratio - b.cum)
+    }
+}
+
 pub(crate) mod string_or_bool {
     use std::fmt;
 
@@ -1,8 +1,7 @@
-use serde_json::from_str;
-
 use crate::client::*;
 use crate::errors::*;
 use crate::rest_model::*;
+use crate::userstream::ListenKeyEndpoint;
 
 static USER_DATA_STREAM: &str = "/fapi/v1/listenKey";
 
@@ -23,8 +22,7 @@ impl FuturesUserStream {
     /// assert!(start.unwrap().listen_key.len() > 0)
     /// ```
     pub async fn start(&self) -> Result<UserDataStream> {
-        let data = self.client.post(USER_DATA_STREAM, None).await?;
-        let user_data_stream: UserDataStream = from_str(data.as_str())?;
+        let user_data_stream: UserDataStream = self.client.post_d(USER_DATA_STREAM, None).await?;
 
         Ok(user_data_stream)
     }
@@ -40,9 +38,7 @@ impl FuturesUserStream {
     /// assert!(keep_alive.is_ok())
     /// ```
     pub async fn keep_alive(&self, listen_key: &str) -> Result<Success> {
-        let data = self.client.put(USER_DATA_STREAM, listen_key, None).await?;
-
-        let success: Success = from_str(data.as_str())?;
+        let success: Success = self.client.put_d(USER_DATA_STREAM, listen_key, None).await?;
 
         Ok(success)
     }
@@ -58,10 +54,13 @@ impl FuturesUserStream {
     /// assert!(close.is_ok())
     /// ```
     pub async fn close(&self, listen_key: &str) -> Result<Success> {
-        let data = self.client.delete(USER_DATA_STREAM, listen_key, None).await?;
-
-        let success: Success = from_str(data.as_str())?;
+        let success: Success = self.client.delete_d(USER_DATA_STREAM, listen_key, None).await?;
 
         Ok(success)
     }
+
+    /// Endpoint of the futures listen keys, to start a [`crate::userstream::ManagedListenKey`]
+    pub fn listen_key_endpoint(&self) -> ListenKeyEndpoint {
+        ListenKeyEndpoint::new(self.client.clone(), USER_DATA_STREAM, None)
+    }
 }
@@ -25,7 +25,7 @@
 //! # use std::io;
 //! use binance::general::General;
 //! use binance::api::Binance;
-//! use binance::errors::Error as BinanceLibError;
+//! use binance::errors::{BinanceErrorCode, Error as BinanceLibError};
 //!
 //! #[tokio::main]
 //! async fn main() -> std::io::Result<()> {
@@ -35,8 +35,8 @@
 //!         Ok(answer) => println!("{:?}", answer),
 //!         Err(err) => {
 //!             match err {
-//!                 BinanceLibError::BinanceError { response } => match response.code {
-//!                     -1000_i16 => println!("An unknown error occured while processing the request"),
+//!                 BinanceLibError::BinanceError { response } => match response.error_code() {
+//!                     BinanceErrorCode::Unknown => println!("An unknown error occured while processing the request"),
 //!                     _ => println!("Unknown code {}: {}", response.code, response.msg),
 //!                 },
 //!                 _ => println!("Other errors: {}.", err),
@@ -52,15 +52,17 @@
 //! # Details
 //!
 //! - Credentials are not enforced, you will get authentication errors if you don't provide
-//! credentials and they are required by an endpoint
+//!   credentials and they are required by an endpoint
 //!
 //! - Error codes are handled on a best effort basis as some are inconsistent and not even
-//! documented on Binance's side
+//!   documented on Binance's side
 //!
 //! - Errors are implemented using [![thiserror]](https://docs.rs/thiserror/1.0.25/thiserror/)
 //!
 
 #![deny(unstable_features, unused_must_use, unused_mut, unused_imports, unused_import_braces)]
+// `Error::Tungstenite` holds the tungstenite error as is, boxing it would change the public variant
+#![allow(clippy::result_large_err)]
 
 #[macro_use]
 extern crate lazy_static;
@@ -71,21 +73,40 @@ extern crate serde_qs as qs;
 pub use util::{bool_to_string, bool_to_string_some};
 
 mod client;
+mod clock;
+mod trace;
+#[cfg(test)]
+mod test_server;
 pub mod errors;
 pub mod util;
 
 pub mod account;
 pub mod api;
+#[cfg(feature = "broker_api")]
+pub mod broker;
+pub mod client_order_id;
+#[cfg(feature = "coin_futures_api")]
+pub mod coin_futures;
 pub mod config;
+pub mod exchange_info_cache;
 #[cfg(feature = "futures_api")]
 pub mod futures;
 pub mod general;
 #[cfg(feature = "margin_api")]
 pub mod margin;
 pub mod market;
+pub mod open_orders;
+pub mod order_governor;
+#[cfg(feature = "options_api")]
+pub mod options;
+#[cfg(feature = "portfolio_margin_api")]
+pub mod portfolio_margin;
 pub mod rest_model;
 #[cfg(feature = "savings_api")]
 pub mod savings;
+pub mod signing;
 pub mod userstream;
 pub mod websockets;
+pub mod ws_api;
 pub mod ws_model;
+mod ws_transport;
@@ -1,9 +1,8 @@
-use serde_json::from_str;
-
 use crate::client::*;
 use crate::errors::*;
 use crate::rest_model::*;
-use crate::util::bool_to_string;
+use crate::userstream::ListenKeyEndpoint;
+use crate::util::{bool_to_string, validate_client_order_ids};
 
 static SAPI_V1_MARGIN_TRANSFER: &str = "/sapi/v1/margin/transfer";
 static SAPI_V1_MARGIN_ISOLATED_TRANSFER: &str = "/sapi/v1/margin/isolated/transfer";
@@ -213,6 +212,7 @@ impl Margin {
     /// assert!(transaction_id.is_ok(), "{:?}", transaction_id);
     /// ```
     pub async fn trade(&self, margin_order: MarginOrder) -> Result<MarginOrderResult> {
+        validate_client_order_ids([margin_order.new_client_order_id.as_deref()])?;
         self.client
             .post_signed_p(SAPI_V1_MARGIN_ORDER, margin_order, self.recv_window)
             .await
@@ -262,6 +262,11 @@ impl Margin {
     /// assert!(transaction_id.is_ok(), "{:?}", transaction_id);
     /// ```
     pub async fn new_oco_order(&self, margin_order: MarginOCOOrder) -> Result<MarginOCOOrderResult> {
+        validate_client_order_ids([
+            margin_order.list_client_order_id.as_deref(),
+            margin_order.limit_client_order_id.as_deref(),
+            margin_order.stop_client_order_id.as_deref(),
+        ])?;
         self.client
             .post_signed_p(SAPI_V1_MARGIN_OCO_ORDER, margin_order, self.recv_window)
             .await
@@ -287,6 +292,7 @@ impl Margin {
         S: Into<String>,
         F: Into<u64>,
     {
+        validate_client_order_ids(non_empty([&orig_client_order_id, &new_client_order_id]))?;
         let margin_order_cancellation: MarginOrderCancellation = MarginOrderCancellation {
             symbol: symbol.into(),
             order_id: order_id.into(),
@@ -319,6 +325,7 @@ impl Margin {
         S: Into<String>,
         F: Into<u64>,
     {
+        validate_client_order_ids(non_empty([&list_client_order_id, &new_client_order_id]))?;
         let margin_order_cancellation: MarginOCOOrderCancellation = MarginOCOOrderCancellation {
             symbol: symbol.into(),
             order_list_id: order_list_id.into(),
@@ -604,6 +611,9 @@ impl Margin {
     }
 
     /// Get price index
+    ///
+    /// Margin liquidations are triggered by this index rather than by the last trade price. This is a
+    /// market data endpoint, the request carries the API key but is not signed.
     /// # Examples
     /// ```rust,no_run
     /// use binance::{api::*, margin::*, config::*, rest_model::*};
@@ -616,11 +626,7 @@ impl Margin {
         S: Into<String>,
     {
         self.client
-            .get_signed_p(
-                SAPI_V1_MARGIN_PRICE_INDEX,
-                Some(PairQuery { symbol: symbol.into() }),
-                self.recv_window,
-            )
+            .get_keyed_d(SAPI_V1_MARGIN_PRICE_INDEX, Some(PairQuery { symbol: symbol.into() }))
             .await
     }
 
@@ -905,8 +911,7 @@ impl Margin {
     /// assert!(start.unwrap().listen_key.len() > 0)
     /// ```
     pub async fn start(&self) -> Result<UserDataStream> {
-        let data = self.client.post(SAPI_USER_DATA_STREAM, None).await?;
-        let user_data_stream: UserDataStream = from_str(data.as_str())?;
+        let user_data_stream: UserDataStream = self.client.post_d(SAPI_USER_DATA_STREAM, None).await?;
 
         Ok(user_data_stream)
     }
@@ -922,9 +927,7 @@ impl Margin {
     /// assert!(keep_alive.is_ok())
     /// ```
     pub async fn keep_alive(&self, listen_key: &str) -> Result<Success> {
-        let data = self.client.put(SAPI_USER_DATA_STREAM, listen_key, None).await?;
-
-        let success: Success = from_str(data.as_str())?;
+        let success: Success = self.client.put_d(SAPI_USER_DATA_STREAM, listen_key, None).await?;
 
         Ok(success)
     }
@@ -940,9 +943,7 @@ impl Margin {
     /// assert!(close.is_ok())
     /// ```
     pub async fn close(&self, listen_key: &str) -> Result<Success> {
-        let data = self.client.delete(SAPI_USER_DATA_STREAM, listen_key, None).await?;
-
-        let success: Success = from_str(data.as_str())?;
+        let success: Success = self.client.delete_d(SAPI_USER_DATA_STREAM, listen_key, None).await?;
 
         Ok(success)
     }
@@ -957,8 +958,7 @@ impl Margin {
     /// assert!(start.unwrap().listen_key.len() > 0)
     /// ```
     pub async fn start_isolated(&self, symbol: &str) -> Result<UserDataStream> {
-        let data = self.client.post(SAPI_USER_DATA_STREAM_ISOLATED, Some(symbol)).await?;
-        let user_data_stream: UserDataStream = from_str(data.as_str())?;
+        let user_data_stream: UserDataStream = self.client.post_d(SAPI_USER_DATA_STREAM_ISOLATED, Some(symbol)).await?;
 
         Ok(user_data_stream)
     }
@@ -974,12 +974,7 @@ impl Margin {
     /// assert!(keep_alive.is_ok())
     /// ```
     pub async fn keep_alive_isolated(&self, listen_key: &str, symbol: &str) -> Result<Success> {
-        let data = self
-            .client
-            .put(SAPI_USER_DATA_STREAM_ISOLATED, listen_key, Some(symbol))
-            .await?;
-
-        let success: Success = from_str(data.as_str())?;
+        let success: Success = self.client.put_d(SAPI_USER_DATA_STREAM_ISOLATED, listen_key, Some(symbol)).await?;
 
         Ok(success)
     }
@@ -995,16 +990,21 @@ impl Margin {
     /// assert!(close.is_ok())
     /// ```
     pub async fn close_isolated(&self, listen_key: &str, symbol: &str) -> Result<Success> {
-        let data = self
-            .client
-            .delete(SAPI_USER_DATA_STREAM_ISOLATED, listen_key, Some(symbol))
-            .await?;
-
-        let success: Success = from_str(data.as_str())?;
+        let success: Success = self.client.delete_d(SAPI_USER_DATA_STREAM_ISOLATED, listen_key, Some(symbol)).await?;
 
         Ok(success)
     }
 
+    /// Endpoint of the cross margin listen keys, to start a [`crate::userstream::ManagedListenKey`]
+    pub fn listen_key_endpoint(&self) -> ListenKeyEndpoint {
+        ListenKeyEndpoint::new(self.client.clone(), SAPI_USER_DATA_STREAM, None)
+    }
+
+    /// Endpoint of the isolated margin listen keys of `symbol`
+    pub fn isolated_listen_key_endpoint(&self, symbol: &str) -> ListenKeyEndpoint {
+        ListenKeyEndpoint::new(self.client.clone(), SAPI_USER_DATA_STREAM_ISOLATED, Some(symbol.to_string()))
+    }
+
     pub async fn isolated_account_limit(&self) -> Result<IsolatedAccountLimit> {
         let q: Option<PairQuery> = None;
 
@@ -1015,3 +1015,66 @@ impl Margin {
         Ok(data)
     }
 }
+
+/// Client ids of cancellations are plain strings, left empty when unset
+fn non_empty<const N: usize>(ids: [&String; N]) -> impl Iterator<Item = Option<&str>> {
+    ids.into_iter().map(|id| Some(id.as_str()).filter(|id| !id.is_empty()))
+}
+
+#[cfg(test)]
+mod test {
+    use super::Margin;
+    use crate::client::Client;
+    use crate::rest_model::{MarginOrder, OrderResponse, OrderSide, OrderType, SideEffectType};
+    use crate::test_server::{ok, scripted_server};
+
+    #[test]
+    fn side_effect_types() {
+        for (side_effect, name) in [
+            (SideEffectType::NoSideEffect, "NO_SIDE_EFFECT"),
+            (SideEffectType::MarginBuy, "MARGIN_BUY"),
+            (SideEffectType::AutoRepay, "AUTO_REPAY"),
+            (SideEffectType::AutoBorrowRepay, "AUTO_BORROW_REPAY"),
+        ] {
+            assert_eq!(serde_json::to_value(side_effect).unwrap(), name);
+        }
+    }
+
+    #[tokio::test]
+    async fn margin_buy_with_auto_borrow() {
+        let (host, server) = scripted_server(vec![ok(
+            r#"{"symbol":"BTCUSDT","orderId":28,"clientOrderId":"6gCrw2kRUAF9CvJDGP16IP",
+            "transactTime":1507725176595,"price":"0.00000000","origQty":"0.01000000","executedQty":"0.01000000",
+            "cummulativeQuoteQty":"300.00000000","status":"FILLED","timeInForce":"GTC","type":"MARKET",
+            "side":"BUY","marginBuyBorrowAmount":"300","marginBuyBorrowAsset":"USDT","isIsolated":false,
+            "fills":[]}"#,
+        )])
+        .await;
+
+        let margin = Margin {
+            client: Client::new(Some("key".into()), Some("secret".into()), host),
+            recv_window: 5000,
+        };
+        let result = margin
+            .trade(MarginOrder {
+                symbol: "BTCUSDT".to_string(),
+                side: OrderSide::Buy,
+                order_type: OrderType::Market,
+                quantity: Some(0.01),
+                quote_order_qty: None,
+                price: None,
+                stop_price: None,
+                new_client_order_id: None,
+                iceberg_qty: None,
+                new_order_resp_type: OrderResponse::Full,
+                time_in_force: None,
+                is_isolated: None,
+                side_effect_type: SideEffectType::AutoBorrowRepay,
+            })
+            .await
+            .unwrap();
+        assert_eq!(result.margin_buy_borrow_amount, Some(300.0));
+        let request = server.await.unwrap().remove(0);
+        assert!(request.contains("sideEffectType=AUTO_BORROW_REPAY"), "{}", request);
+    }
+}
@@ -1,8 +1,9 @@
 use crate::client::*;
 use crate::errors::*;
 use crate::rest_model::*;
+use crate::trace;
 use crate::util::*;
-use serde_json::{from_str, Value};
+use serde_json::Value;
 use std::collections::BTreeMap;
 
 static API_V3_DEPTH: &str = "/api/v3/depth";
@@ -12,6 +13,12 @@ static API_V3_BOOK_TICKER: &str = "/api/v3/ticker/bookTicker";
 static API_V3_24H_TICKER: &str = "/api/v3/ticker/24hr";
 static API_V3_AGG_TRADES: &str = "/api/v3/aggTrades";
 static API_V3_KLINES: &str = "/api/v3/klines";
+static API_V3_UI_KLINES: &str = "/api/v3/uiKlines";
+
+/// Depth fetched to simulate market orders, deeper books cost a much higher request weight
+static SIMULATION_DEPTH_LIMIT: u16 = 1000;
+/// Depth limits supported by the order book endpoint, Binance rejects the others with -1100
+const DEPTH_LIMITS: [u16; 8] = [5, 10, 20, 50, 100, 500, 1000, 5000];
 
 #[derive(Clone)]
 pub struct Market {
@@ -44,36 +51,52 @@ impl Market {
         S: Into<String>,
     {
         let request = self.symbol_request(symbol);
-        let data = self.client.get(API_V3_DEPTH, &request).await?;
-        let order_book: OrderBook = from_str(data.as_str())?;
-
-        Ok(order_book)
+        self.client.get_p(API_V3_DEPTH, &request).await
     }
 
     /// Order book with a custom depth limit
-    /// Supported limits are: 5, 10, 20, 50, 100, 500, 1000, 5000
+    /// Supported limits are: 5, 10, 20, 50, 100, 500, 1000, 5000, other limits are replaced by the nearest
+    /// supported one, see [`depth_limit`]. The request weight depends on the limit, see [`depth_weight`], and the
+    /// weight used reported by Binance is kept in `market.client.last_rate_limits()`.
     /// # Examples
     /// ```rust
     /// use binance::{api::*, market::*, config::*};
     /// let market: Market = Binance::new_with_env(&Config::default());
     /// let orderbook = tokio_test::block_on(market.get_custom_depth("BTCUSDT".to_string(), 50));
     /// assert!(orderbook.is_ok(), "{:?}", orderbook);
-    /// let bids_len = orderbook.unwrap().bids.len();
-    /// assert_eq!(bids_len, 50);
+    /// assert_eq!(orderbook.unwrap().bids.len(), 50);
     /// ```
     pub async fn get_custom_depth<S>(&self, symbol: S, limit: u16) -> Result<OrderBook>
     where
         S: Into<String>,
     {
+        let limit = supported_depth_limit(limit);
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
         parameters.insert("symbol".into(), symbol.into());
         parameters.insert("limit".into(), limit.to_string());
 
         let request = build_request(&parameters);
-        let data = self.client.get(API_V3_DEPTH, &request).await?;
-        let order_book: OrderBook = from_str(data.as_str())?;
+        self.client.get_p(API_V3_DEPTH, &request).await
+    }
 
-        Ok(order_book)
+    /// Estimate the average fill price and slippage of a market order from the current order book.
+    ///
+    /// This is a one-shot estimate against a snapshot of the 1000 best levels of each side, the book moves and
+    /// actual fills may differ. When the levels fetched cannot absorb `quantity`, the remainder is reported in
+    /// `unfilled_qty`.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, market::*, config::*, rest_model::OrderSide};
+    /// let market: Market = Binance::new_with_env(&Config::default());
+    /// let fill = tokio_test::block_on(market.simulate_market_order("BTCUSDT", OrderSide::Buy, 2.0)).unwrap();
+    /// println!("{:?} {:?}", fill.average_price, fill.slippage);
+    /// ```
+    pub async fn simulate_market_order<S>(&self, symbol: S, side: OrderSide, quantity: f64) -> Result<SimulatedFill>
+    where
+        S: Into<String>,
+    {
+        let order_book = self.get_custom_depth(symbol, SIMULATION_DEPTH_LIMIT).await?;
+        Ok(order_book.simulate_market_order(side, quantity))
     }
 
     /// Latest price for ALL symbols.
@@ -85,11 +108,7 @@ impl Market {
     /// assert!(prices.is_ok(), "{:?}", prices);
     /// ```
     pub async fn get_all_prices(&self) -> Result<Prices> {
-        let data = self.client.get(API_V3_TICKER_PRICE, "").await?;
-
-        let prices: Prices = from_str(data.as_str())?;
-
-        Ok(prices)
+        self.client.get_p(API_V3_TICKER_PRICE, "").await
     }
 
     /// Latest price for ONE symbol.
@@ -105,10 +124,7 @@ impl Market {
         S: Into<String>,
     {
         let request = self.symbol_request(symbol);
-        let data = self.client.get(API_V3_TICKER_PRICE, &request).await?;
-        let symbol_price: SymbolPrice = from_str(data.as_str())?;
-
-        Ok(symbol_price)
+        self.client.get_p(API_V3_TICKER_PRICE, &request).await
     }
 
     /// Average price for ONE symbol.
@@ -124,10 +140,7 @@ impl Market {
         S: Into<String>,
     {
         let request = self.symbol_request(symbol);
-        let data = self.client.get(API_V3_AVG_PRICE, &request).await?;
-        let average_price: AveragePrice = from_str(data.as_str())?;
-
-        Ok(average_price)
+        self.client.get_p(API_V3_AVG_PRICE, &request).await
     }
 
     /// Symbols order book ticker
@@ -140,11 +153,7 @@ impl Market {
     /// assert!(tickers.is_ok(), "{:?}", tickers);
     /// ```
     pub async fn get_all_book_tickers(&self) -> Result<BookTickers> {
-        let data = self.client.get(API_V3_BOOK_TICKER, "").await?;
-
-        let book_tickers: BookTickers = from_str(data.as_str())?;
-
-        Ok(book_tickers)
+        self.client.get_p(API_V3_BOOK_TICKER, "").await
     }
 
     /// -> Best price/qty on the order book for ONE symbol
@@ -160,10 +169,7 @@ impl Market {
         S: Into<String>,
     {
         let request = self.symbol_request(symbol);
-        let data = self.client.get(API_V3_BOOK_TICKER, &request).await?;
-        let ticker: Tickers = from_str(data.as_str())?;
-
-        Ok(ticker)
+        self.client.get_p(API_V3_BOOK_TICKER, &request).await
     }
 
     /// 24hr ticker price change statistics
@@ -179,11 +185,7 @@ impl Market {
         S: Into<String>,
     {
         let request = self.symbol_request(symbol);
-        let data = self.client.get(API_V3_24H_TICKER, &request).await?;
-
-        let stats: PriceStats = from_str(data.as_str())?;
-
-        Ok(stats)
+        self.client.get_p(API_V3_24H_TICKER, &request).await
     }
 
     /// Get aggregated historical trades.
@@ -258,45 +260,132 @@ impl Market {
         S4: Into<Option<u64>>,
         S5: Into<Option<u64>>,
     {
+        self.klines(API_V3_KLINES, symbol.into(), interval.into(), limit.into(), start_time.into(), end_time.into())
+            .await
+    }
+
+    /// Same as [`Self::get_klines`], with candles optimized for presentation in charts
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, market::*, config::*, rest_model::KlineInterval};
+    /// let market: Market = Binance::new_with_env(&Config::default());
+    /// let klines = tokio_test::block_on(market.get_ui_klines("BTCUSDT", KlineInterval::OneMinute, None, None, None));
+    /// assert!(klines.is_ok(), "{:?}", klines);
+    /// ```
+    pub async fn get_ui_klines<S1, S2, S3, S4, S5>(
+        &self,
+        symbol: S1,
+        interval: S2,
+        limit: S3,
+        start_time: S4,
+        end_time: S5,
+    ) -> Result<KlineSummaries>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u16>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u64>>,
+    {
+        self.klines(API_V3_UI_KLINES, symbol.into(), interval.into(), limit.into(), start_time.into(), end_time.into())
+            .await
+    }
+
+    async fn klines(
+        &self,
+        endpoint: &str,
+        symbol: String,
+        interval: String,
+        limit: Option<u16>,
+        start_time: Option<u64>,
+        end_time: Option<u64>,
+    ) -> Result<KlineSummaries> {
         let mut parameters: BTreeMap<String, String> = BTreeMap::new();
 
-        parameters.insert("symbol".into(), symbol.into());
-        parameters.insert("interval".into(), interval.into());
+        parameters.insert("symbol".into(), symbol);
+        parameters.insert("interval".into(), interval);
 
         // Add three optional parameters
-        if let Some(lt) = limit.into() {
+        if let Some(lt) = limit {
             parameters.insert("limit".into(), format!("{}", lt));
         }
-        if let Some(st) = start_time.into() {
+        if let Some(st) = start_time {
             parameters.insert("startTime".into(), format!("{}", st));
         }
-        if let Some(et) = end_time.into() {
+        if let Some(et) = end_time {
             parameters.insert("endTime".into(), format!("{}", et));
         }
 
         let request = build_request(&parameters);
 
-        let data = self.client.get(API_V3_KLINES, &request).await?;
-        let parsed_data: Vec<Vec<Value>> = from_str(data.as_str())?;
-
-        let klines = KlineSummaries::AllKlineSummaries(
-            parsed_data
-                .iter()
-                .map(|row| KlineSummary {
-                    open_time: to_i64(&row[0]),
-                    open: to_f64(&row[1]),
-                    high: to_f64(&row[2]),
-                    low: to_f64(&row[3]),
-                    close: to_f64(&row[4]),
-                    volume: to_f64(&row[5]),
-                    close_time: to_i64(&row[6]),
-                    quote_asset_volume: to_f64(&row[7]),
-                    number_of_trades: to_i64(&row[8]),
-                    taker_buy_base_asset_volume: to_f64(&row[9]),
-                    taker_buy_quote_asset_volume: to_f64(&row[10]),
-                })
-                .collect(),
-        );
-        Ok(klines)
+        let parsed_data: Vec<Vec<Value>> = self.client.get_p(endpoint, &request).await?;
+
+        Ok(kline_summaries(&parsed_data))
+    }
+}
+
+/// Request weight of a spot order book of `limit` levels: 5 up to 100 levels, 25 up to 500, 50 up to 1000 and
+/// 250 up to 5000
+pub fn depth_weight(limit: u16) -> u16 {
+    match limit {
+        0..=100 => 5,
+        101..=500 => 25,
+        501..=1000 => 50,
+        _ => 250,
+    }
+}
+
+/// Supported depth limit nearest to `limit`, the smaller one when `limit` is halfway between two
+pub fn depth_limit(limit: u16) -> u16 {
+    DEPTH_LIMITS
+        .iter()
+        .copied()
+        .min_by_key(|supported| supported.abs_diff(limit))
+        .unwrap_or(limit)
+}
+
+/// [`depth_limit`], with a warning when it differs from `limit`, shared with the WebSocket API
+pub(crate) fn supported_depth_limit(limit: u16) -> u16 {
+    let supported = depth_limit(limit);
+    if supported != limit {
+        trace::depth_limit_replaced(limit, supported);
+    }
+    supported
+}
+
+/// Klines from the rows of arrays returned by Binance, shared with the WebSocket API
+pub(crate) fn kline_summaries(rows: &[Vec<Value>]) -> KlineSummaries {
+    KlineSummaries::AllKlineSummaries(
+        rows.iter()
+            .map(|row| KlineSummary {
+                open_time: to_i64(&row[0]),
+                open: to_f64(&row[1]),
+                high: to_f64(&row[2]),
+                low: to_f64(&row[3]),
+                close: to_f64(&row[4]),
+                volume: to_f64(&row[5]),
+                close_time: to_i64(&row[6]),
+                quote_asset_volume: to_f64(&row[7]),
+                number_of_trades: to_i64(&row[8]),
+                taker_buy_base_asset_volume: to_f64(&row[9]),
+                taker_buy_quote_asset_volume: to_f64(&row[10]),
+            })
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn depth_limits_and_weights() {
+        assert_eq!(depth_limit(5), 5);
+        assert_eq!(depth_limit(0), 5);
+        assert_eq!(depth_limit(15), 10);
+        assert_eq!(depth_limit(120), 100);
+        assert_eq!(depth_limit(1001), 1000);
+        assert_eq!(depth_limit(50_000), 5000);
+        assert_eq!(DEPTH_LIMITS.map(depth_weight), [5, 5, 5, 5, 5, 25, 50, 250]);
     }
 }
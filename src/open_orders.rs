@@ -0,0 +1,296 @@
+//! In-memory registry of the open orders of the account, kept in sync with the user stream.
+//!
+//! The book is seeded with a snapshot from `openOrders` and then follows `executionReport` events,
+//! orders are dropped as soon as they reach a terminal status. Snapshots and events can arrive in any
+//! order : events are ordered by their transaction time, and orders closed by the stream are remembered
+//! so that a snapshot fetched before their closure cannot bring them back.
+
+use std::collections::HashMap;
+
+use tokio::sync::broadcast;
+
+use crate::account::Account;
+use crate::errors::*;
+use crate::rest_model::{Order, OrderStatus};
+use crate::util::get_timestamp;
+use crate::ws_model::{OrderUpdate, WebsocketEvent};
+
+/// Capacity of the change notification channel, slow receivers lag beyond it
+static CHANGES_CAPACITY: usize = 1024;
+
+/// How long (in ms) closed orders are remembered without a snapshot, no snapshot takes longer to arrive
+static CLOSED_RETENTION_MS: u64 = 10 * 60 * 1000;
+
+/// A change of the open orders
+#[derive(Debug, Clone)]
+pub enum OpenOrderChange {
+    /// An order appeared, through the stream or a snapshot
+    Opened(Order),
+    /// An open order was (partially) filled or amended
+    Updated(Order),
+    /// An order reached a terminal status and left the book
+    Closed(Order),
+}
+
+/// Live map of the open orders of the account
+/// # Examples
+/// ```rust,no_run
+/// use binance::{api::*, account::*, config::*, open_orders::*};
+/// let account: Account = Binance::new_with_env(&Config::testnet());
+/// let mut book = OpenOrderBook::new();
+/// let mut changes = book.subscribe();
+/// // start the user stream first, and feed every event to `book.apply(&event)`
+/// tokio_test::block_on(book.sync(&account)).unwrap();
+/// println!("{:?}", book.all());
+/// ```
+pub struct OpenOrderBook {
+    orders: HashMap<u64, Order>,
+    client_ids: HashMap<String, u64>,
+    /// Orders closed by the stream, by order id, with the transaction time of their closure.
+    /// Pruned by every snapshot, and by every closure of the ones older than `CLOSED_RETENTION_MS`.
+    closed: HashMap<u64, u64>,
+    changes: broadcast::Sender<OpenOrderChange>,
+}
+
+impl Default for OpenOrderBook {
+    fn default() -> Self { Self::new() }
+}
+
+impl OpenOrderBook {
+    pub fn new() -> Self {
+        let (changes, _) = broadcast::channel(CHANGES_CAPACITY);
+        Self {
+            orders: HashMap::new(),
+            client_ids: HashMap::new(),
+            closed: HashMap::new(),
+            changes,
+        }
+    }
+
+    /// Receive every change made to the book from now on
+    pub fn subscribe(&self) -> broadcast::Receiver<OpenOrderChange> { self.changes.subscribe() }
+
+    pub fn get(&self, order_id: u64) -> Option<&Order> { self.orders.get(&order_id) }
+
+    pub fn by_client_id(&self, client_order_id: &str) -> Option<&Order> {
+        self.client_ids
+            .get(client_order_id)
+            .and_then(|order_id| self.orders.get(order_id))
+    }
+
+    pub fn all(&self) -> Vec<&Order> { self.orders.values().collect() }
+
+    pub fn len(&self) -> usize { self.orders.len() }
+
+    pub fn is_empty(&self) -> bool { self.orders.is_empty() }
+
+    /// Fetch all open orders and merge them into the book.
+    ///
+    /// The user stream should already be connected so that no event is missed between the snapshot and
+    /// the stream.
+    pub async fn sync(&mut self, account: &Account) -> Result<()> {
+        let snapshot_time = get_timestamp()?;
+        let orders = account.get_all_open_orders().await?;
+        self.seed(orders, snapshot_time);
+        Ok(())
+    }
+
+    /// Merge a snapshot of the open orders, requested at `snapshot_time` (in ms).
+    ///
+    /// Orders known to the book but missing from the snapshot are dropped if they were last updated
+    /// before the snapshot was requested, orders closed by the stream are never restored.
+    pub fn seed(&mut self, orders: Vec<Order>, snapshot_time: u64) {
+        let snapshot: HashMap<u64, Order> = orders
+            .into_iter()
+            .filter(|o| !self.closed.contains_key(&o.order_id))
+            .map(|o| (o.order_id, o))
+            .collect();
+        let gone: Vec<u64> = self
+            .orders
+            .values()
+            .filter(|o| !snapshot.contains_key(&o.order_id) && o.update_time < snapshot_time)
+            .map(|o| o.order_id)
+            .collect();
+        for order_id in gone {
+            if let Some(order) = self.remove(order_id) {
+                self.notify(OpenOrderChange::Closed(order));
+            }
+        }
+        for order in snapshot.into_values() {
+            match self.orders.get(&order.order_id) {
+                Some(known) if known.update_time >= order.update_time => {}
+                Some(_) => self.upsert(order, false),
+                None => self.upsert(order, true),
+            }
+        }
+        // A closure older than the snapshot is already reflected by it
+        self.closed.retain(|_, closed_at| *closed_at >= snapshot_time);
+    }
+
+    /// Apply a user stream event, events other than execution reports are ignored
+    pub fn apply(&mut self, event: &WebsocketEvent) {
+        if let WebsocketEvent::OrderUpdate(update) = event {
+            self.apply_update(update);
+        }
+    }
+
+    /// Apply an execution report
+    pub fn apply_update(&mut self, update: &OrderUpdate) {
+        if self.closed.contains_key(&update.order_id) {
+            return;
+        }
+        let known = self.orders.get(&update.order_id);
+        if known.is_some_and(|o| o.update_time > update.trade_order_time) {
+            return;
+        }
+        let opened = known.is_none();
+        let order = to_order(update, known);
+        if is_terminal(&order.status) {
+            let retained_since = order.update_time.saturating_sub(CLOSED_RETENTION_MS);
+            self.closed.retain(|_, closed_at| *closed_at >= retained_since);
+            self.closed.insert(order.order_id, order.update_time);
+            self.remove(order.order_id);
+            self.notify(OpenOrderChange::Closed(order));
+        } else {
+            self.upsert(order, opened);
+        }
+    }
+
+    fn upsert(&mut self, order: Order, opened: bool) {
+        if let Some(previous) = self.orders.get(&order.order_id) {
+            if previous.client_order_id != order.client_order_id {
+                self.client_ids.remove(&previous.client_order_id);
+            }
+        }
+        self.client_ids.insert(order.client_order_id.clone(), order.order_id);
+        self.orders.insert(order.order_id, order.clone());
+        self.notify(if opened {
+            OpenOrderChange::Opened(order)
+        } else {
+            OpenOrderChange::Updated(order)
+        });
+    }
+
+    fn remove(&mut self, order_id: u64) -> Option<Order> {
+        let order = self.orders.remove(&order_id)?;
+        self.client_ids.remove(&order.client_order_id);
+        Some(order)
+    }
+
+    /// Sending only fails when nobody listens
+    fn notify(&self, change: OpenOrderChange) { let _ = self.changes.send(change); }
+}
+
+fn is_terminal(status: &OrderStatus) -> bool {
+    matches!(
+        status,
+        OrderStatus::Filled
+            | OrderStatus::Canceled
+            | OrderStatus::Rejected
+            | OrderStatus::Expired
+            | OrderStatus::ExpiredInMatch
+    )
+}
+
+fn to_order(update: &OrderUpdate, known: Option<&Order>) -> Order {
+    // Cancel reports carry the client id of the cancel request, the order keeps its original one
+    let client_order_id = match (&update.current_order_status, &update.origin_client_id) {
+        (OrderStatus::Canceled, Some(origin)) if !origin.is_empty() => origin.clone(),
+        _ => update.client_order_id.clone(),
+    };
+    Order {
+        symbol: update.symbol.clone(),
+        order_id: update.order_id,
+        order_list_id: update.order_list_id as i32,
+        client_order_id,
+        price: update.price,
+        orig_qty: update.qty,
+        executed_qty: update.cumulative_filled_qty,
+        cummulative_quote_qty: update.cumulative_quote_asset_transacted_qty,
+        status: update.current_order_status.clone(),
+        time_in_force: update.time_in_force.clone(),
+        order_type: update.order_type.clone(),
+        side: update.side.clone(),
+        stop_price: update.stop_price,
+        iceberg_qty: update.iceberg_qty,
+        time: known.map_or(update.order_creation_time, |o| o.time),
+        update_time: update.trade_order_time,
+        is_working: update.is_order_on_the_book,
+        orig_quote_order_qty: update.quote_order_qty,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn order(order_id: u64, update_time: u64) -> Order {
+        serde_json::from_value(serde_json::json!({
+            "symbol": "BTCUSDT", "orderId": order_id, "orderListId": -1, "clientOrderId": format!("c{}", order_id),
+            "price": "20000.0", "origQty": "1.0", "executedQty": "0.0", "cummulativeQuoteQty": "0.0",
+            "status": "NEW", "timeInForce": "GTC", "type": "LIMIT", "side": "BUY", "stopPrice": "0.0",
+            "icebergQty": "0.0", "time": update_time, "updateTime": update_time, "isWorking": true,
+            "origQuoteOrderQty": "0.0"
+        }))
+        .unwrap()
+    }
+
+    fn update(order_id: u64, status: &str, time: u64) -> OrderUpdate {
+        serde_json::from_value(serde_json::json!({
+            "e": "executionReport", "E": time, "s": "BTCUSDT", "c": format!("c{}", order_id), "S": "BUY",
+            "o": "LIMIT", "f": "GTC", "q": "1.0", "p": "20000.0", "P": "0.0", "F": "0.0", "g": -1,
+            "C": "", "x": status, "X": status, "r": "NONE", "i": order_id, "l": "0.5", "z": "0.5",
+            "L": "20000.0", "n": "0", "N": null, "T": time, "t": 1, "I": 0, "w": true, "m": false,
+            "M": false, "O": 1, "Z": "10000.0", "Y": "10000.0", "Q": "0.0"
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn stream_updates_and_closes_orders() {
+        let mut book = OpenOrderBook::new();
+        let mut changes = book.subscribe();
+        book.seed(vec![order(1, 10), order(2, 10)], 20);
+        book.apply_update(&update(1, "PARTIALLY_FILLED", 30));
+        book.apply_update(&update(2, "FILLED", 30));
+        assert_eq!(book.len(), 1);
+        assert_eq!(book.by_client_id("c1").unwrap().executed_qty, 0.5);
+        assert!(book.get(2).is_none());
+        // stale event
+        book.apply_update(&update(1, "NEW", 25));
+        assert_eq!(book.get(1).unwrap().status, OrderStatus::PartiallyFilled);
+        let mut count = 0;
+        while changes.try_recv().is_ok() {
+            count += 1;
+        }
+        assert_eq!(count, 4);
+    }
+
+    #[test]
+    fn late_snapshot_does_not_restore_closed_orders() {
+        let mut book = OpenOrderBook::new();
+        book.apply_update(&update(1, "NEW", 30));
+        book.apply_update(&update(2, "CANCELED", 40));
+        book.apply_update(&update(3, "PARTIALLY_FILLED", 50));
+        // requested at 20, before every event
+        book.seed(vec![order(2, 10), order(3, 10), order(4, 10)], 20);
+        assert!(book.get(1).is_some());
+        assert!(book.get(2).is_none());
+        assert_eq!(book.get(3).unwrap().status, OrderStatus::PartiallyFilled);
+        assert!(book.get(4).is_some());
+        // a later snapshot drops the orders it no longer lists
+        book.seed(vec![order(3, 50)], 60);
+        assert_eq!(book.len(), 1);
+    }
+
+    #[test]
+    fn closed_orders_are_forgotten_after_the_retention() {
+        let mut book = OpenOrderBook::new();
+        book.apply_update(&update(1, "CANCELED", 10));
+        book.apply_update(&update(2, "FILLED", 20));
+        assert_eq!(book.closed.len(), 2);
+        book.apply_update(&update(3, "CANCELED", 15 + CLOSED_RETENTION_MS));
+        assert_eq!(book.closed.len(), 2);
+        assert!(!book.closed.contains_key(&1));
+    }
+}
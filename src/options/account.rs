@@ -0,0 +1,239 @@
+use std::collections::BTreeMap;
+
+use crate::client::Client;
+use crate::errors::*;
+use crate::options::rest_model::*;
+use crate::rest_model::{string_or_float_opt, OrderSide, TimeInForce};
+use crate::util::*;
+
+/// Maximum number of orders accepted by a single batch call
+static MAX_BATCH_ORDERS: usize = 10;
+
+/// Options account and trading
+#[derive(Clone)]
+pub struct OptionsAccount {
+    pub client: Client,
+    pub recv_window: u64,
+}
+
+/// Options response types, options do not support FULL
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderResponse {
+    Ack,
+    Result,
+}
+
+/// Order Request for options, options orders are always LIMIT orders
+#[derive(Default, Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderRequest {
+    pub symbol: String,
+    pub side: OrderSide,
+    #[serde(rename = "type")]
+    order_type: LimitOrderType,
+    #[serde(with = "string_or_float_opt")]
+    pub quantity: Option<f64>,
+    #[serde(with = "string_or_float_opt")]
+    pub price: Option<f64>,
+    /// Default GTC
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub time_in_force: Option<TimeInForce>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reduce_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub post_only: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub new_order_resp_type: Option<OrderResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_order_id: Option<String>,
+    /// Market maker protection order
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_mmp: Option<bool>,
+}
+
+#[derive(Default, Debug, Serialize, Clone, Copy)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum LimitOrderType {
+    #[default]
+    Limit,
+}
+
+impl OrderRequest {
+    /// A limit order of `quantity` contracts at `price`
+    pub fn limit<S: Into<String>>(symbol: S, side: OrderSide, quantity: f64, price: f64) -> Self {
+        OrderRequest {
+            symbol: symbol.into(),
+            side,
+            quantity: Some(quantity),
+            price: Some(price),
+            ..OrderRequest::default()
+        }
+    }
+}
+
+/// Identifies an order by order id or client order id
+#[derive(Default, Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderIdRequest {
+    pub symbol: String,
+    pub order_id: Option<u64>,
+    pub client_order_id: Option<String>,
+}
+
+/// Query on the orders, trades or records of an option
+#[derive(Default, Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryQuery {
+    pub symbol: Option<String>,
+    /// Orders with an id greater than or equal to this one, only for orders
+    pub order_id: Option<u64>,
+    /// Trades with an id greater than or equal to this one, only for trades
+    pub from_id: Option<u64>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    /// Default 100 max 1000
+    pub limit: Option<u16>,
+}
+
+#[derive(Default, Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct BillQuery {
+    pub currency: String,
+    /// Records with an id greater than this one
+    pub record_id: Option<u64>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    /// Default 100 max 1000
+    pub limit: Option<u16>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchOrdersRequest {
+    orders: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BatchCancelRequest {
+    symbol: String,
+    order_ids: Option<String>,
+    client_order_ids: Option<String>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SymbolQuery {
+    symbol: Option<String>,
+}
+
+impl OptionsAccount {
+    /// Place an order
+    pub async fn place_order(&self, order: OrderRequest) -> Result<Order> {
+        self.client
+            .post_signed_p("/eapi/v1/order", order, self.recv_window)
+            .await
+    }
+
+    /// Place up to 10 orders at once, each order succeeds or fails individually
+    pub async fn place_batch_orders(&self, orders: Vec<OrderRequest>) -> Result<Vec<BatchOrderResult>> {
+        if orders.len() > MAX_BATCH_ORDERS {
+            return Err(Error::InvalidOrderError {
+                msg: format!("At most {} orders can be sent in a batch", MAX_BATCH_ORDERS),
+            });
+        }
+        let request = BatchOrdersRequest {
+            orders: to_batch_json(&orders)?,
+        };
+        self.client
+            .post_signed_p("/eapi/v1/batchOrders", request, self.recv_window)
+            .await
+    }
+
+    /// Cancel an order by order id or client order id
+    pub async fn cancel_order(&self, o: OrderIdRequest) -> Result<Order> {
+        self.client
+            .delete_signed_p("/eapi/v1/order", o, self.recv_window)
+            .await
+    }
+
+    /// Cancel up to 10 orders of an option, by order id or by client order id
+    pub async fn cancel_batch_orders<S>(
+        &self,
+        symbol: S,
+        order_ids: Vec<u64>,
+        client_order_ids: Vec<String>,
+    ) -> Result<Vec<BatchOrderResult>>
+    where
+        S: Into<String>,
+    {
+        let request = BatchCancelRequest {
+            symbol: symbol.into(),
+            order_ids: (!order_ids.is_empty())
+                .then(|| serde_json::to_string(&order_ids))
+                .transpose()?,
+            client_order_ids: (!client_order_ids.is_empty())
+                .then(|| serde_json::to_string(&client_order_ids))
+                .transpose()?,
+        };
+        self.client
+            .delete_signed_p("/eapi/v1/batchOrders", request, self.recv_window)
+            .await
+    }
+
+    /// Query an order by order id or client order id
+    pub async fn get_order(&self, o: OrderIdRequest) -> Result<Order> {
+        self.client
+            .get_signed_p("/eapi/v1/order", Some(o), self.recv_window)
+            .await
+    }
+
+    /// Open orders, of all options if the query has no symbol
+    pub async fn get_open_orders(&self, query: HistoryQuery) -> Result<Vec<Order>> {
+        self.client
+            .get_signed_p("/eapi/v1/openOrders", Some(query), self.recv_window)
+            .await
+    }
+
+    /// Finished orders of an option, the symbol is mandatory
+    pub async fn get_history_orders(&self, query: HistoryQuery) -> Result<Vec<Order>> {
+        self.client
+            .get_signed_p("/eapi/v1/historyOrders", Some(query), self.recv_window)
+            .await
+    }
+
+    /// Account information: asset balances and greeks per underlying
+    pub async fn account_info(&self) -> Result<AccountInformation> {
+        let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
+        self.client.get_signed_d("/eapi/v1/account", request.as_str()).await
+    }
+
+    /// Positions of an option, or of all options if `symbol` is `None`
+    pub async fn positions(&self, symbol: Option<String>) -> Result<Vec<Position>> {
+        self.client
+            .get_signed_p("/eapi/v1/position", Some(SymbolQuery { symbol }), self.recv_window)
+            .await
+    }
+
+    /// Trades of an option, or of all options if the query has no symbol
+    pub async fn user_trades(&self, query: HistoryQuery) -> Result<Vec<UserTrade>> {
+        self.client
+            .get_signed_p("/eapi/v1/userTrades", Some(query), self.recv_window)
+            .await
+    }
+
+    /// Exercise records of expired options
+    pub async fn exercise_records(&self, query: HistoryQuery) -> Result<Vec<ExerciseRecord>> {
+        self.client
+            .get_signed_p("/eapi/v1/exerciseRecord", Some(query), self.recv_window)
+            .await
+    }
+
+    /// Funding flows of the options account for a currency
+    pub async fn bills(&self, query: BillQuery) -> Result<Vec<Bill>> {
+        self.client
+            .get_signed_p("/eapi/v1/bill", Some(query), self.recv_window)
+            .await
+    }
+}
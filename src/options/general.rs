@@ -0,0 +1,40 @@
+use crate::client::*;
+use crate::errors::*;
+use crate::options::rest_model::*;
+
+#[derive(Clone)]
+pub struct OptionsGeneral {
+    pub client: Client,
+}
+
+impl OptionsGeneral {
+    // Test connectivity
+    pub async fn ping(&self) -> Result<String> {
+        self.client.get("/eapi/v1/ping", "").await?;
+        Ok("pong".into())
+    }
+
+    // Check server time
+    pub async fn get_server_time(&self) -> Result<ServerTime> { self.client.get_p("/eapi/v1/time", "").await }
+
+    // Obtain exchange information
+    // - Option contracts, assets and symbols with their strike and expiry
+    pub async fn exchange_info(&self) -> Result<ExchangeInformation> {
+        self.client.get_p("/eapi/v1/exchangeInfo", "").await
+    }
+
+    // Get Symbol information
+    pub async fn get_symbol_info<S>(&self, symbol: S) -> Result<OptionSymbol>
+    where
+        S: Into<String>,
+    {
+        let symbol_string = symbol.into();
+        let upper_symbol = symbol_string.to_uppercase();
+        self.exchange_info()
+            .await?
+            .option_symbols
+            .into_iter()
+            .find(|item| item.symbol == upper_symbol)
+            .ok_or(Error::UnknownSymbol(symbol_string))
+    }
+}
@@ -0,0 +1,128 @@
+use crate::client::*;
+use crate::errors::*;
+use crate::options::rest_model::*;
+
+/// Depth limits accepted by /eapi/v1/depth
+static DEPTH_LIMITS: [u16; 6] = [10, 20, 50, 100, 500, 1000];
+
+/// Options market data, all endpoints are public
+#[derive(Clone)]
+pub struct OptionsMarket {
+    pub client: Client,
+    pub recv_window: u64,
+}
+
+impl OptionsMarket {
+    /// Order book (Default 100; max 1000)
+    pub async fn get_depth<S>(&self, symbol: S) -> Result<OrderBook>
+    where
+        S: Into<String>,
+    {
+        let query = MarketQuery {
+            symbol: Some(symbol.into()),
+            ..MarketQuery::default()
+        };
+        self.client.get_d("/eapi/v1/depth", Some(query)).await
+    }
+
+    /// Order book with a custom depth limit
+    /// Supported limits are: 10, 20, 50, 100, 500, 1000
+    pub async fn get_custom_depth<S>(&self, symbol: S, limit: u16) -> Result<OrderBook>
+    where
+        S: Into<String>,
+    {
+        if !DEPTH_LIMITS.contains(&limit) {
+            return Err(Error::InvalidLimit(limit));
+        }
+        let query = MarketQuery {
+            symbol: Some(symbol.into()),
+            limit: Some(limit),
+            ..MarketQuery::default()
+        };
+        self.client.get_d("/eapi/v1/depth", Some(query)).await
+    }
+
+    /// Recent trades of an option (Default 100; max 500)
+    pub async fn get_trades<S>(&self, symbol: S, limit: Option<u16>) -> Result<Vec<Trade>>
+    where
+        S: Into<String>,
+    {
+        let query = MarketQuery {
+            symbol: Some(symbol.into()),
+            limit,
+            ..MarketQuery::default()
+        };
+        self.client.get_d("/eapi/v1/trades", Some(query)).await
+    }
+
+    /// Returns up to 'limit' klines for given symbol and interval ("1m", "5m", ...)
+    pub async fn get_klines<S1, S2, S3, S4, S5>(
+        &self,
+        symbol: S1,
+        interval: S2,
+        limit: S3,
+        start_time: S4,
+        end_time: S5,
+    ) -> Result<Vec<Kline>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u16>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u64>>,
+    {
+        let query = MarketQuery {
+            symbol: Some(symbol.into()),
+            interval: Some(interval.into()),
+            limit: limit.into(),
+            start_time: start_time.into(),
+            end_time: end_time.into(),
+            ..MarketQuery::default()
+        };
+        self.client.get_d("/eapi/v1/klines", Some(query)).await
+    }
+
+    /// Mark price, greeks and implied volatilities of an option, or of all options if `symbol` is `None`
+    pub async fn get_mark_prices(&self, symbol: Option<String>) -> Result<Vec<MarkPrice>> {
+        let query = MarketQuery {
+            symbol,
+            ..MarketQuery::default()
+        };
+        self.client.get_d("/eapi/v1/mark", Some(query)).await
+    }
+
+    /// 24hr ticker of an option, or of all options if `symbol` is `None`
+    pub async fn get_tickers(&self, symbol: Option<String>) -> Result<Vec<Ticker>> {
+        let query = MarketQuery {
+            symbol,
+            ..MarketQuery::default()
+        };
+        self.client.get_d("/eapi/v1/ticker", Some(query)).await
+    }
+
+    /// Spot index price of an underlying (e.g. BTCUSDT)
+    pub async fn get_index_price<S>(&self, underlying: S) -> Result<IndexPrice>
+    where
+        S: Into<String>,
+    {
+        let query = MarketQuery {
+            underlying: Some(underlying.into()),
+            ..MarketQuery::default()
+        };
+        self.client.get_d("/eapi/v1/index", Some(query)).await
+    }
+
+    /// Open interest of all the options of an underlying asset (e.g. BTC) for an expiration date (YYMMDD)
+    pub async fn open_interest<S1, S2>(&self, underlying_asset: S1, expiration: S2) -> Result<Vec<OpenInterest>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+    {
+        let query = MarketQuery {
+            underlying_asset: Some(underlying_asset.into()),
+            expiration: Some(expiration.into()),
+            ..MarketQuery::default()
+        };
+        self.client.get_d("/eapi/v1/openInterest", Some(query)).await
+    }
+}
@@ -0,0 +1,8 @@
+/// European options API modules
+/// # Examples
+/// See examples/binance_options.rs
+pub mod account;
+pub mod general;
+pub mod market;
+pub mod rest_model;
+pub mod userstream;
@@ -0,0 +1,492 @@
+use serde::{Deserialize, Serialize};
+
+use crate::errors::BinanceContentError;
+use crate::rest_model::{string_or_float, string_or_float_opt, Asks, Bids, OrderSide, RateLimit, TimeInForce};
+pub use crate::rest_model::ServerTime;
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExchangeInformation {
+    pub timezone: String,
+    pub server_time: u64,
+    pub option_contracts: Vec<OptionContract>,
+    pub option_assets: Vec<OptionAsset>,
+    pub option_symbols: Vec<OptionSymbol>,
+    pub rate_limits: Vec<RateLimit>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionContract {
+    pub id: u64,
+    pub base_asset: String,
+    pub quote_asset: String,
+    pub underlying: String,
+    pub settle_asset: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionAsset {
+    pub id: u64,
+    pub name: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OptionSide {
+    Call,
+    Put,
+}
+
+/// An option symbol such as BTC-240628-70000-C
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OptionSymbol {
+    pub id: u64,
+    pub contract_id: u64,
+    pub symbol: String,
+    pub underlying: String,
+    pub side: OptionSide,
+    #[serde(with = "string_or_float")]
+    pub strike_price: f64,
+    pub expiry_date: u64,
+    /// Number of underlying per contract
+    pub unit: u64,
+    pub quote_asset: String,
+    #[serde(with = "string_or_float")]
+    pub maker_fee_rate: f64,
+    #[serde(with = "string_or_float")]
+    pub taker_fee_rate: f64,
+    #[serde(with = "string_or_float")]
+    pub min_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub max_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub initial_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub maintenance_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub min_initial_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub min_maintenance_margin: f64,
+    pub price_scale: u16,
+    pub quantity_scale: u16,
+    pub filters: Vec<Filters>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "filterType")]
+pub enum Filters {
+    #[serde(rename = "PRICE_FILTER")]
+    #[serde(rename_all = "camelCase")]
+    PriceFilter {
+        #[serde(with = "string_or_float")]
+        min_price: f64,
+        #[serde(with = "string_or_float")]
+        max_price: f64,
+        #[serde(with = "string_or_float")]
+        tick_size: f64,
+    },
+    #[serde(rename = "LOT_SIZE")]
+    #[serde(rename_all = "camelCase")]
+    LotSize {
+        #[serde(with = "string_or_float")]
+        min_qty: f64,
+        #[serde(with = "string_or_float")]
+        max_qty: f64,
+        #[serde(with = "string_or_float")]
+        step_size: f64,
+    },
+    #[serde(other)]
+    Others,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct OrderBook {
+    /// Transaction time
+    #[serde(rename = "T")]
+    pub transaction_time: u64,
+    /// Update id
+    #[serde(rename = "u")]
+    pub update_id: u64,
+    pub bids: Vec<Bids>,
+    pub asks: Vec<Asks>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Trade {
+    pub id: u64,
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    #[serde(with = "string_or_float")]
+    pub qty: f64,
+    #[serde(with = "string_or_float")]
+    pub quote_qty: f64,
+    /// 1 when the taker bought, -1 when the taker sold
+    pub side: i8,
+    pub time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Kline {
+    pub open_time: u64,
+    pub close_time: u64,
+    pub interval: String,
+    #[serde(with = "string_or_float")]
+    pub open: f64,
+    #[serde(with = "string_or_float")]
+    pub high: f64,
+    #[serde(with = "string_or_float")]
+    pub low: f64,
+    #[serde(with = "string_or_float")]
+    pub close: f64,
+    /// Volume in contracts
+    #[serde(with = "string_or_float")]
+    pub volume: f64,
+    /// Volume in quote asset
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    pub trade_count: u64,
+    #[serde(with = "string_or_float")]
+    pub taker_volume: f64,
+    #[serde(with = "string_or_float")]
+    pub taker_amount: f64,
+}
+
+/// Mark price, greeks and implied volatilities of an option
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarkPrice {
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub mark_price: f64,
+    /// Implied volatility of the best bid
+    #[serde(rename = "bidIV", default, with = "string_or_float_opt")]
+    pub bid_iv: Option<f64>,
+    /// Implied volatility of the best ask
+    #[serde(rename = "askIV", default, with = "string_or_float_opt")]
+    pub ask_iv: Option<f64>,
+    /// Implied volatility of the mark price
+    #[serde(rename = "markIV", with = "string_or_float")]
+    pub mark_iv: f64,
+    #[serde(with = "string_or_float")]
+    pub delta: f64,
+    #[serde(with = "string_or_float")]
+    pub theta: f64,
+    #[serde(with = "string_or_float")]
+    pub gamma: f64,
+    #[serde(with = "string_or_float")]
+    pub vega: f64,
+    #[serde(with = "string_or_float")]
+    pub high_price_limit: f64,
+    #[serde(with = "string_or_float")]
+    pub low_price_limit: f64,
+    #[serde(default, with = "string_or_float_opt")]
+    pub risk_free_interest: Option<f64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Ticker {
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub price_change: f64,
+    #[serde(with = "string_or_float")]
+    pub price_change_percent: f64,
+    #[serde(with = "string_or_float")]
+    pub last_price: f64,
+    #[serde(with = "string_or_float")]
+    pub last_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub open: f64,
+    #[serde(with = "string_or_float")]
+    pub high: f64,
+    #[serde(with = "string_or_float")]
+    pub low: f64,
+    #[serde(with = "string_or_float")]
+    pub volume: f64,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    #[serde(with = "string_or_float")]
+    pub bid_price: f64,
+    #[serde(with = "string_or_float")]
+    pub ask_price: f64,
+    pub open_time: u64,
+    pub close_time: u64,
+    pub first_trade_id: u64,
+    pub trade_count: u64,
+    #[serde(with = "string_or_float")]
+    pub strike_price: f64,
+    /// Estimated settlement price one hour before exercise, index price at other times
+    #[serde(with = "string_or_float")]
+    pub exercise_price: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexPrice {
+    pub time: u64,
+    #[serde(with = "string_or_float")]
+    pub index_price: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenInterest {
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub sum_open_interest: f64,
+    #[serde(with = "string_or_float")]
+    pub sum_open_interest_usd: f64,
+    #[serde(with = "string_or_u64")]
+    pub timestamp: u64,
+}
+
+#[derive(Serialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct MarketQuery {
+    pub symbol: Option<String>,
+    pub underlying: Option<String>,
+    pub underlying_asset: Option<String>,
+    pub expiration: Option<String>,
+    pub interval: Option<String>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub limit: Option<u16>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum OrderStatus {
+    Accepted,
+    Rejected,
+    PartiallyFilled,
+    Filled,
+    Cancelled,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Order {
+    pub order_id: u64,
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    #[serde(with = "string_or_float")]
+    pub quantity: f64,
+    #[serde(with = "string_or_float")]
+    pub executed_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub fee: f64,
+    pub side: OrderSide,
+    #[serde(rename = "type")]
+    pub order_type: String,
+    pub time_in_force: TimeInForce,
+    pub reduce_only: bool,
+    pub post_only: bool,
+    pub create_time: u64,
+    pub update_time: u64,
+    pub status: OrderStatus,
+    #[serde(with = "string_or_float")]
+    pub avg_price: f64,
+    pub client_order_id: String,
+    pub price_scale: u16,
+    pub quantity_scale: u16,
+    pub option_side: OptionSide,
+    pub quote_asset: String,
+    /// Whether the order is a market maker protection order
+    #[serde(default)]
+    pub mmp: bool,
+}
+
+/// Result of one order of a batch, failed orders are reported individually
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum BatchOrderResult {
+    Order(Box<Order>),
+    Error(BinanceContentError),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInformation {
+    pub asset: Vec<AccountAsset>,
+    pub greek: Vec<AccountGreek>,
+    pub time: u64,
+    /// NORMAL, MEDIUM, HIGH
+    pub risk_level: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountAsset {
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub margin_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub equity: f64,
+    #[serde(with = "string_or_float")]
+    pub available: f64,
+    #[serde(with = "string_or_float")]
+    pub locked: f64,
+    #[serde(rename = "unrealizedPNL", with = "string_or_float")]
+    pub unrealized_pnl: f64,
+}
+
+/// Greeks of the whole account for an underlying
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountGreek {
+    pub underlying: String,
+    #[serde(with = "string_or_float")]
+    pub delta: f64,
+    #[serde(with = "string_or_float")]
+    pub gamma: f64,
+    #[serde(with = "string_or_float")]
+    pub theta: f64,
+    #[serde(with = "string_or_float")]
+    pub vega: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum PositionSide {
+    Long,
+    Short,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Position {
+    pub symbol: String,
+    pub side: PositionSide,
+    #[serde(with = "string_or_float")]
+    pub entry_price: f64,
+    #[serde(with = "string_or_float")]
+    pub quantity: f64,
+    #[serde(with = "string_or_float")]
+    pub reducible_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub mark_value: f64,
+    /// Rate of return
+    #[serde(with = "string_or_float")]
+    pub ror: f64,
+    #[serde(rename = "unrealizedPNL", with = "string_or_float")]
+    pub unrealized_pnl: f64,
+    #[serde(with = "string_or_float")]
+    pub mark_price: f64,
+    #[serde(with = "string_or_float")]
+    pub strike_price: f64,
+    #[serde(with = "string_or_float")]
+    pub position_cost: f64,
+    pub expiry_date: u64,
+    pub price_scale: u16,
+    pub quantity_scale: u16,
+    pub option_side: OptionSide,
+    pub quote_asset: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UserTrade {
+    pub id: u64,
+    pub trade_id: u64,
+    pub order_id: u64,
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    #[serde(with = "string_or_float")]
+    pub quantity: f64,
+    #[serde(with = "string_or_float")]
+    pub fee: f64,
+    #[serde(with = "string_or_float")]
+    pub realized_profit: f64,
+    pub side: OrderSide,
+    #[serde(rename = "type")]
+    pub order_type: String,
+    /// Implied volatility of the trade
+    #[serde(default, with = "string_or_float_opt")]
+    pub volatility: Option<f64>,
+    /// TAKER or MAKER
+    pub liquidity: String,
+    pub quote_asset: String,
+    pub time: u64,
+    pub price_scale: u16,
+    pub quantity_scale: u16,
+    pub option_side: OptionSide,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ExerciseRecord {
+    pub id: String,
+    pub currency: String,
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub exercise_price: f64,
+    #[serde(with = "string_or_float")]
+    pub mark_price: f64,
+    #[serde(with = "string_or_float")]
+    pub quantity: f64,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    #[serde(with = "string_or_float")]
+    pub fee: f64,
+    pub create_date: u64,
+    pub price_scale: u16,
+    pub quantity_scale: u16,
+    pub option_side: OptionSide,
+    pub position_side: PositionSide,
+    pub quote_asset: String,
+}
+
+/// Funding flow of the options account
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Bill {
+    pub id: u64,
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    /// FEE, CONTRACT, TRANSFER
+    #[serde(rename = "type")]
+    pub bill_type: String,
+    pub create_date: u64,
+}
+
+mod string_or_u64 {
+    use std::fmt;
+
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        T: fmt::Display,
+        S: Serializer,
+    {
+        serializer.collect_str(value)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StringOrU64 {
+            String(String),
+            U64(u64),
+        }
+
+        match StringOrU64::deserialize(deserializer)? {
+            StringOrU64::String(s) => s.parse().map_err(de::Error::custom),
+            StringOrU64::U64(i) => Ok(i),
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use crate::client::*;
+use crate::errors::*;
+use crate::rest_model::*;
+use crate::userstream::ListenKeyEndpoint;
+
+static USER_DATA_STREAM: &str = "/eapi/v1/listenKey";
+
+#[derive(Clone)]
+pub struct OptionsUserStream {
+    pub client: Client,
+    pub recv_window: u64,
+}
+
+impl OptionsUserStream {
+    /// Get a listen key for the stream
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, options::userstream::*, config::*};
+    /// let userstream: OptionsUserStream = Binance::new_with_env(&Config::testnet());
+    /// let start = tokio_test::block_on(userstream.start());
+    /// assert!(start.is_ok(), "{:?}", start);
+    /// assert!(start.unwrap().listen_key.len() > 0)
+    /// ```
+    pub async fn start(&self) -> Result<UserDataStream> {
+        let user_data_stream: UserDataStream = self.client.post_d(USER_DATA_STREAM, None).await?;
+
+        Ok(user_data_stream)
+    }
+
+    /// Keep the connection alive, as the listen key becomes invalid after 60mn
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, options::userstream::*, config::*};
+    /// let userstream: OptionsUserStream = Binance::new_with_env(&Config::testnet());
+    /// let start = tokio_test::block_on(userstream.start());
+    /// assert!(start.is_ok(), "{:?}", start);
+    /// let keep_alive = tokio_test::block_on(userstream.keep_alive(&start.unwrap().listen_key));
+    /// assert!(keep_alive.is_ok())
+    /// ```
+    pub async fn keep_alive(&self, listen_key: &str) -> Result<Success> {
+        let success: Success = self.client.put_d(USER_DATA_STREAM, listen_key, None).await?;
+
+        Ok(success)
+    }
+
+    /// Invalidate the listen key
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, options::userstream::*, config::*};
+    /// let userstream: OptionsUserStream = Binance::new_with_env(&Config::testnet());
+    /// let start = tokio_test::block_on(userstream.start());
+    /// assert!(start.is_ok(), "{:?}", start);
+    /// let close = tokio_test::block_on(userstream.close(&start.unwrap().listen_key));
+    /// assert!(close.is_ok())
+    /// ```
+    pub async fn close(&self, listen_key: &str) -> Result<Success> {
+        let success: Success = self.client.delete_d(USER_DATA_STREAM, listen_key, None).await?;
+
+        Ok(success)
+    }
+
+    /// Endpoint of the options listen keys, to start a [`crate::userstream::ManagedListenKey`]
+    pub fn listen_key_endpoint(&self) -> ListenKeyEndpoint {
+        ListenKeyEndpoint::new(self.client.clone(), USER_DATA_STREAM, None)
+    }
+}
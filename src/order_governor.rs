@@ -0,0 +1,181 @@
+//! Client side accounting of the order rate limits, to hold orders back before Binance rejects them with
+//! -1015 "Too many new orders".
+//!
+//! The order count limits are separate from the request weight. Binance counts orders in fixed windows
+//! (e.g. 10 seconds, 1 day) aligned on the clock, the governor learns the limits and the current usage from
+//! [`crate::account::Account::order_count_usage`] and counts the orders placed since, correcting its counts with
+//! the `X-MBX-ORDER-COUNT-*` headers of the order responses.
+
+use std::collections::BTreeMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::errors::*;
+use crate::rest_model::{OrderCountUsage, RateLimitInterval, RateLimitType};
+use crate::util::get_timestamp;
+
+/// Order count limits of an account, shared by the clones of the governor
+/// # Examples
+/// ```rust,no_run
+/// use binance::{api::*, account::*, config::*, order_governor::*};
+/// let account: Account = Binance::new_with_env(&Config::testnet());
+/// let account = account.set_order_governor(OrderGovernor::new());
+/// // learn the limits and what was already used
+/// tokio_test::block_on(account.order_count_usage()).unwrap();
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct OrderGovernor {
+    windows: Arc<Mutex<Vec<OrderWindow>>>,
+}
+
+/// Governors are equal when they share their counts
+impl PartialEq for OrderGovernor {
+    fn eq(&self, other: &Self) -> bool { Arc::ptr_eq(&self.windows, &other.windows) }
+}
+
+#[derive(Debug, Clone)]
+struct OrderWindow {
+    usage: OrderCountUsage,
+    length_ms: u64,
+    /// Number of the window the count belongs to, since the epoch
+    window: u64,
+}
+
+impl OrderGovernor {
+    /// Governor without any known limit, which lets every order through until updated
+    pub fn new() -> Self { Self::default() }
+
+    /// Replace the known limits and usage with the ones reported by Binance
+    pub fn update(&self, usage: &[OrderCountUsage]) {
+        let now = get_timestamp().unwrap_or_default();
+        *self.lock() = usage
+            .iter()
+            .filter(|u| matches!(u.rate_limit_type, RateLimitType::Orders))
+            .map(|u| {
+                let length_ms = interval_ms(&u.interval, u.interval_num);
+                OrderWindow {
+                    usage: u.clone(),
+                    length_ms,
+                    window: now / length_ms,
+                }
+            })
+            .collect();
+    }
+
+    /// Count an order about to be placed, fails without counting it when a window is full
+    pub fn acquire(&self) -> Result<()> {
+        let now = get_timestamp()?;
+        let mut windows = self.lock();
+        windows.iter_mut().for_each(|w| w.roll(now));
+        if let Some(full) = windows.iter().find(|w| w.usage.count >= w.usage.limit) {
+            return Err(Error::OrderRateLimited {
+                limit: full.usage.limit,
+                interval: Duration::from_millis(full.length_ms),
+            });
+        }
+        windows.iter_mut().for_each(|w| w.usage.count += 1);
+        Ok(())
+    }
+
+    /// Replace the local counts with the ones reported by Binance, by interval as in the
+    /// `X-MBX-ORDER-COUNT-*` headers, e.g. `10s` or `1d`. The limits must be known already.
+    pub fn update_counts(&self, counts: &BTreeMap<String, u32>) {
+        let now = get_timestamp().unwrap_or_default();
+        let mut windows = self.lock();
+        for (interval, count) in counts {
+            let length_ms = match header_interval_ms(interval) {
+                Some(length_ms) => length_ms,
+                None => continue,
+            };
+            for window in windows.iter_mut().filter(|w| w.length_ms == length_ms) {
+                window.roll(now);
+                window.usage.count = *count as i32;
+            }
+        }
+    }
+
+    /// Limits and usage as counted locally
+    pub fn usage(&self) -> Vec<OrderCountUsage> {
+        let now = get_timestamp().unwrap_or_default();
+        let mut windows = self.lock();
+        windows.iter_mut().for_each(|w| w.roll(now));
+        windows.iter().map(|w| w.usage.clone()).collect()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<OrderWindow>> {
+        self.windows.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+impl OrderWindow {
+    /// Start counting again once the window is over
+    fn roll(&mut self, now: u64) {
+        let window = now / self.length_ms;
+        if window != self.window {
+            self.window = window;
+            self.usage.count = 0;
+        }
+    }
+}
+
+fn interval_ms(interval: &RateLimitInterval, interval_num: i32) -> u64 {
+    let unit = match interval {
+        RateLimitInterval::Second => 1000,
+        RateLimitInterval::Minute => 60 * 1000,
+        RateLimitInterval::Day => 24 * 3600 * 1000,
+    };
+    unit * interval_num.max(1) as u64
+}
+
+/// Length of an interval as written in the headers, e.g. `10s`
+fn header_interval_ms(interval: &str) -> Option<u64> {
+    let unit = match interval.chars().last()?.to_ascii_lowercase() {
+        's' => 1000,
+        'm' => 60 * 1000,
+        'h' => 3600 * 1000,
+        'd' => 24 * 3600 * 1000,
+        _ => return None,
+    };
+    let number: u64 = interval[..interval.len() - 1].parse().ok()?;
+    Some(unit * number.max(1))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::clock::{set_clock, MockClock};
+
+    fn usage(interval: &str, interval_num: i32, limit: i32, count: i32) -> OrderCountUsage {
+        serde_json::from_value(serde_json::json!({
+            "rateLimitType": "ORDERS", "interval": interval, "intervalNum": interval_num, "limit": limit,
+            "count": count
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn holds_orders_back_until_the_window_is_over() {
+        let clock = MockClock::at_millis(10_000);
+        let _guard = set_clock(clock.clone());
+        let governor = OrderGovernor::new();
+        assert!(governor.acquire().is_ok());
+
+        governor.update(&[usage("SECOND", 10, 3, 1), usage("DAY", 1, 100, 50)]);
+        assert!(governor.acquire().is_ok());
+        assert!(governor.acquire().is_ok());
+        clock.advance(Duration::from_millis(9_999));
+        match governor.acquire() {
+            Err(e @ Error::OrderRateLimited { .. }) => assert!(e.is_rate_limited()),
+            other => panic!("unexpected {:?}", other),
+        }
+        // a new 10 seconds window, the daily count goes on
+        clock.advance(Duration::from_millis(1));
+        assert!(governor.acquire().is_ok());
+        assert_eq!(governor.usage()[1].count, 53);
+
+        // the counts of Binance win over the local ones
+        governor.update_counts(&BTreeMap::from([("10s".to_string(), 3), ("1d".to_string(), 60)]));
+        assert!(governor.acquire().is_err());
+        assert_eq!(governor.usage()[1].count, 60);
+    }
+}
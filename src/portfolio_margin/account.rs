@@ -0,0 +1,257 @@
+use std::collections::BTreeMap;
+
+use crate::client::Client;
+use crate::errors::*;
+use crate::futures::rest_model::{ChangeLeverageResponse, OpenOrder, OrderType};
+use crate::portfolio_margin::rest_model::*;
+use crate::rest_model::{Loan, MarginOCOOrder, MarginOCOOrderResult, MarginOrder, MarginOrderCancellationResult,
+                        MarginOrderResult, Success, TransactionId};
+use crate::util::*;
+
+static PAPI_V1_ACCOUNT: &str = "/papi/v1/account";
+static PAPI_V1_BALANCE: &str = "/papi/v1/balance";
+static PAPI_V1_UM_ORDER: &str = "/papi/v1/um/order";
+static PAPI_V1_UM_OPEN_ORDERS: &str = "/papi/v1/um/openOrders";
+static PAPI_V1_UM_ALL_ORDERS: &str = "/papi/v1/um/allOrders";
+static PAPI_V1_UM_USER_TRADES: &str = "/papi/v1/um/userTrades";
+static PAPI_V1_UM_POSITION_RISK: &str = "/papi/v1/um/positionRisk";
+static PAPI_V1_UM_LEVERAGE: &str = "/papi/v1/um/leverage";
+static PAPI_V1_UM_CONDITIONAL_ORDER: &str = "/papi/v1/um/conditional/order";
+static PAPI_V1_UM_CONDITIONAL_OPEN_ORDERS: &str = "/papi/v1/um/conditional/openOrders";
+static PAPI_V1_MARGIN_ORDER: &str = "/papi/v1/margin/order";
+static PAPI_V1_MARGIN_OCO_ORDER: &str = "/papi/v1/margin/order/oco";
+static PAPI_V1_MARGIN_LOAN: &str = "/papi/v1/marginLoan";
+static PAPI_V1_REPAY_LOAN: &str = "/papi/v1/repayLoan";
+
+/// Portfolio margin account
+#[derive(Clone)]
+pub struct PortfolioMarginAccount {
+    pub client: Client,
+    pub recv_window: u64,
+}
+
+impl PortfolioMarginAccount {
+    /// Account equity, margins and status
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, portfolio_margin::account::*, config::*};
+    /// let account: PortfolioMarginAccount = Binance::new_with_env(&Config::default());
+    /// let info = tokio_test::block_on(account.account_info());
+    /// assert!(info.is_ok(), "{:?}", info);
+    /// ```
+    pub async fn account_info(&self) -> Result<AccountInformation> {
+        let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
+        self.client.get_signed_d(PAPI_V1_ACCOUNT, request.as_str()).await
+    }
+
+    /// Unified maintenance margin ratio of the account
+    pub async fn uni_mmr(&self) -> Result<f64> { Ok(self.account_info().await?.uni_mmr) }
+
+    /// Balances of every asset
+    pub async fn balances(&self) -> Result<Vec<Balance>> {
+        let request = build_signed_request(BTreeMap::new(), self.recv_window)?;
+        self.client.get_signed_d(PAPI_V1_BALANCE, request.as_str()).await
+    }
+
+    /// Balance of a single asset
+    pub async fn balance<S>(&self, asset: S) -> Result<Balance>
+    where
+        S: Into<String>,
+    {
+        let mut params: BTreeMap<String, String> = BTreeMap::new();
+        params.insert("asset".into(), asset.into());
+        let request = build_signed_request(params, self.recv_window)?;
+        self.client.get_signed_d(PAPI_V1_BALANCE, request.as_str()).await
+    }
+
+    /// Collect the assets of the futures wallets back into the margin wallet
+    pub async fn auto_collection(&self) -> Result<Success> {
+        self.client
+            .post_signed_p("/papi/v1/auto-collection", BTreeMap::<String, String>::new(), self.recv_window)
+            .await
+    }
+
+    /// Collect a single asset of the futures wallets back into the margin wallet
+    pub async fn asset_collection<S>(&self, asset: S) -> Result<Success>
+    where
+        S: Into<String>,
+    {
+        let mut params: BTreeMap<String, String> = BTreeMap::new();
+        params.insert("asset".into(), asset.into());
+        self.client
+            .post_signed_p("/papi/v1/asset-collection", params, self.recv_window)
+            .await
+    }
+
+    /// Place a UM futures LIMIT or MARKET order
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, portfolio_margin::{account::*, rest_model::*}, config::*, rest_model::OrderSide};
+    /// let account: PortfolioMarginAccount = Binance::new_with_env(&Config::default());
+    /// let order = UmOrderRequest::limit("BTCUSDT", OrderSide::Buy, 0.001, 20000.0);
+    /// let order = tokio_test::block_on(account.place_um_order(order));
+    /// assert!(order.is_ok(), "{:?}", order);
+    /// ```
+    pub async fn place_um_order(&self, order: UmOrderRequest) -> Result<UmOrder> {
+        if !matches!(order.order_type, OrderType::Limit | OrderType::Market) {
+            return Err(Error::InvalidOrderError {
+                msg: "Only LIMIT and MARKET orders are accepted, use place_um_conditional_order instead".to_string(),
+            });
+        }
+        self.client
+            .post_signed_p(PAPI_V1_UM_ORDER, order, self.recv_window)
+            .await
+    }
+
+    /// Cancel a UM futures order by order id or client order id
+    pub async fn cancel_um_order(&self, o: OrderIdQuery) -> Result<UmOrder> {
+        self.client
+            .delete_signed_p(PAPI_V1_UM_ORDER, o, self.recv_window)
+            .await
+    }
+
+    /// Query a UM futures order by order id or client order id
+    pub async fn get_um_order(&self, o: OrderIdQuery) -> Result<OpenOrder> {
+        self.client
+            .get_signed_p(PAPI_V1_UM_ORDER, Some(o), self.recv_window)
+            .await
+    }
+
+    /// Open UM futures orders, of all symbols if `symbol` is `None`
+    pub async fn um_open_orders(&self, symbol: Option<String>) -> Result<Vec<OpenOrder>> {
+        let mut parameters = BTreeMap::new();
+        if let Some(symbol) = symbol {
+            parameters.insert("symbol".into(), symbol);
+        }
+        let request = build_signed_request(parameters, self.recv_window)?;
+        self.client.get_signed_d(PAPI_V1_UM_OPEN_ORDERS, request.as_str()).await
+    }
+
+    /// All UM futures orders of a symbol
+    pub async fn um_all_orders(&self, query: HistoryQuery) -> Result<Vec<OpenOrder>> {
+        self.client
+            .get_signed_p(PAPI_V1_UM_ALL_ORDERS, Some(query), self.recv_window)
+            .await
+    }
+
+    /// UM futures trades of a symbol
+    pub async fn um_user_trades(&self, query: HistoryQuery) -> Result<Vec<UmTrade>> {
+        self.client
+            .get_signed_p(PAPI_V1_UM_USER_TRADES, Some(query), self.recv_window)
+            .await
+    }
+
+    /// UM futures positions, of all symbols if `symbol` is `None`
+    pub async fn um_position_risk(&self, symbol: Option<String>) -> Result<Vec<UmPosition>> {
+        let mut parameters = BTreeMap::new();
+        if let Some(symbol) = symbol {
+            parameters.insert("symbol".into(), symbol);
+        }
+        let request = build_signed_request(parameters, self.recv_window)?;
+        self.client.get_signed_d(PAPI_V1_UM_POSITION_RISK, request.as_str()).await
+    }
+
+    pub async fn change_um_initial_leverage<S>(&self, symbol: S, leverage: u8) -> Result<ChangeLeverageResponse>
+    where
+        S: Into<String>,
+    {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("symbol".into(), symbol.into());
+        parameters.insert("leverage".into(), leverage.to_string());
+        self.client
+            .post_signed_p(PAPI_V1_UM_LEVERAGE, parameters, self.recv_window)
+            .await
+    }
+
+    /// Place a UM futures stop, take profit or trailing stop order
+    pub async fn place_um_conditional_order(&self, order: UmConditionalOrderRequest) -> Result<UmConditionalOrder> {
+        self.client
+            .post_signed_p(PAPI_V1_UM_CONDITIONAL_ORDER, order, self.recv_window)
+            .await
+    }
+
+    pub async fn cancel_um_conditional_order<S>(&self, symbol: S, strategy_id: u64) -> Result<UmConditionalOrder>
+    where
+        S: Into<String>,
+    {
+        let mut parameters: BTreeMap<String, String> = BTreeMap::new();
+        parameters.insert("symbol".into(), symbol.into());
+        parameters.insert("strategyId".into(), strategy_id.to_string());
+        self.client
+            .delete_signed_p(PAPI_V1_UM_CONDITIONAL_ORDER, parameters, self.recv_window)
+            .await
+    }
+
+    /// Open UM futures conditional orders, of all symbols if `symbol` is `None`
+    pub async fn um_open_conditional_orders(&self, symbol: Option<String>) -> Result<Vec<UmConditionalOrder>> {
+        let mut parameters = BTreeMap::new();
+        if let Some(symbol) = symbol {
+            parameters.insert("symbol".into(), symbol);
+        }
+        let request = build_signed_request(parameters, self.recv_window)?;
+        self.client
+            .get_signed_d(PAPI_V1_UM_CONDITIONAL_OPEN_ORDERS, request.as_str())
+            .await
+    }
+
+    /// Place a cross margin order, portfolio margin has no isolated margin
+    pub async fn place_margin_order(&self, order: MarginOrder) -> Result<MarginOrderResult> {
+        if order.is_isolated.is_some() {
+            return Err(Error::InvalidOrderError {
+                msg: "Isolated margin is not available on portfolio margin accounts".to_string(),
+            });
+        }
+        self.client
+            .post_signed_p(PAPI_V1_MARGIN_ORDER, order, self.recv_window)
+            .await
+    }
+
+    /// Cancel a cross margin order by order id or client order id
+    pub async fn cancel_margin_order(&self, o: OrderIdQuery) -> Result<MarginOrderCancellationResult> {
+        self.client
+            .delete_signed_p(PAPI_V1_MARGIN_ORDER, o, self.recv_window)
+            .await
+    }
+
+    pub async fn place_margin_oco_order(&self, order: MarginOCOOrder) -> Result<MarginOCOOrderResult> {
+        if order.is_isolated.is_some() {
+            return Err(Error::InvalidOrderError {
+                msg: "Isolated margin is not available on portfolio margin accounts".to_string(),
+            });
+        }
+        self.client
+            .post_signed_p(PAPI_V1_MARGIN_OCO_ORDER, order, self.recv_window)
+            .await
+    }
+
+    /// Borrow an asset in the cross margin account
+    pub async fn margin_loan<S, F>(&self, asset: S, amount: F) -> Result<TransactionId>
+    where
+        S: Into<String>,
+        F: Into<f64>,
+    {
+        self.client
+            .post_signed_p(PAPI_V1_MARGIN_LOAN, margin_loan(asset, amount), self.recv_window)
+            .await
+    }
+
+    /// Repay a cross margin loan
+    pub async fn repay_loan<S, F>(&self, asset: S, amount: F) -> Result<TransactionId>
+    where
+        S: Into<String>,
+        F: Into<f64>,
+    {
+        self.client
+            .post_signed_p(PAPI_V1_REPAY_LOAN, margin_loan(asset, amount), self.recv_window)
+            .await
+    }
+}
+
+fn margin_loan<S: Into<String>, F: Into<f64>>(asset: S, amount: F) -> Loan {
+    Loan {
+        asset: asset.into(),
+        amount: amount.into(),
+        is_isolated: None,
+        symbol: None,
+    }
+}
@@ -0,0 +1,5 @@
+/// Portfolio margin API modules, a unified account trading margin, USD-M and COIN-M futures
+/// # Examples
+/// See examples/binance_portfolio_margin.rs
+pub mod account;
+pub mod rest_model;
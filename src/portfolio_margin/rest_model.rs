@@ -0,0 +1,311 @@
+use crate::futures::account::{serialize_opt_as_uppercase, PositionSide};
+use crate::futures::rest_model::{OrderType, WorkingType};
+use crate::rest_model::{string_or_float, string_or_float_opt, OrderSide, OrderStatus, TimeInForce};
+
+/// Status of a portfolio margin account, from the least to the most restricted
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccountStatus {
+    Normal,
+    MarginCall,
+    SupplyMargin,
+    ReduceOnly,
+    ActiveLiquidation,
+    ForceLiquidation,
+    Bankrupted,
+    #[serde(other)]
+    Other,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountInformation {
+    /// Unified maintenance margin ratio, liquidation happens when it drops to 1.05
+    #[serde(rename = "uniMMR", with = "string_or_float")]
+    pub uni_mmr: f64,
+    /// Account equity in USD
+    #[serde(with = "string_or_float")]
+    pub account_equity: f64,
+    /// Account equity in USD without collateral rates
+    #[serde(with = "string_or_float")]
+    pub actual_equity: f64,
+    #[serde(with = "string_or_float")]
+    pub account_initial_margin: f64,
+    #[serde(with = "string_or_float")]
+    pub account_maint_margin: f64,
+    pub account_status: AccountStatus,
+    #[serde(with = "string_or_float")]
+    pub virtual_max_withdraw_amount: f64,
+    #[serde(with = "string_or_float")]
+    pub total_available_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub total_margin_open_loss: f64,
+    pub update_time: u64,
+}
+
+/// Balance of an asset across cross margin and the futures wallets
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct Balance {
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub total_wallet_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub cross_margin_asset: f64,
+    #[serde(with = "string_or_float")]
+    pub cross_margin_borrowed: f64,
+    #[serde(with = "string_or_float")]
+    pub cross_margin_free: f64,
+    #[serde(with = "string_or_float")]
+    pub cross_margin_interest: f64,
+    #[serde(with = "string_or_float")]
+    pub cross_margin_locked: f64,
+    #[serde(with = "string_or_float")]
+    pub um_wallet_balance: f64,
+    #[serde(rename = "umUnrealizedPNL", with = "string_or_float")]
+    pub um_unrealized_pnl: f64,
+    #[serde(with = "string_or_float")]
+    pub cm_wallet_balance: f64,
+    #[serde(rename = "cmUnrealizedPNL", with = "string_or_float")]
+    pub cm_unrealized_pnl: f64,
+    #[serde(default, with = "string_or_float")]
+    pub negative_balance: f64,
+    pub update_time: u64,
+}
+
+/// UM futures order, papi only accepts LIMIT and MARKET orders here, conditional orders are placed
+/// with [`UmConditionalOrderRequest`]
+#[derive(Default, Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UmOrderRequest {
+    pub symbol: String,
+    pub side: OrderSide,
+    pub position_side: Option<PositionSide>,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    pub time_in_force: Option<TimeInForce>,
+    pub quantity: Option<f64>,
+    pub reduce_only: Option<bool>,
+    pub price: Option<f64>,
+    pub new_client_order_id: Option<String>,
+}
+
+impl UmOrderRequest {
+    /// A GTC limit order
+    pub fn limit<S: Into<String>>(symbol: S, side: OrderSide, quantity: f64, price: f64) -> Self {
+        UmOrderRequest {
+            symbol: symbol.into(),
+            side,
+            order_type: OrderType::Limit,
+            time_in_force: Some(TimeInForce::GTC),
+            quantity: Some(quantity),
+            price: Some(price),
+            ..UmOrderRequest::default()
+        }
+    }
+
+    pub fn market<S: Into<String>>(symbol: S, side: OrderSide, quantity: f64) -> Self {
+        UmOrderRequest {
+            symbol: symbol.into(),
+            side,
+            order_type: OrderType::Market,
+            quantity: Some(quantity),
+            ..UmOrderRequest::default()
+        }
+    }
+}
+
+/// Strategy of a conditional UM order
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum StrategyType {
+    Stop,
+    StopMarket,
+    TakeProfit,
+    TakeProfitMarket,
+    TrailingStopMarket,
+    #[serde(other)]
+    Other,
+}
+
+/// Conditional UM futures order, triggered on the stop price
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UmConditionalOrderRequest {
+    pub symbol: String,
+    pub side: OrderSide,
+    pub position_side: Option<PositionSide>,
+    pub strategy_type: StrategyType,
+    pub time_in_force: Option<TimeInForce>,
+    pub quantity: Option<f64>,
+    pub reduce_only: Option<bool>,
+    /// Only for STOP and TAKE_PROFIT
+    pub price: Option<f64>,
+    /// Trigger price, for every strategy but TRAILING_STOP_MARKET
+    pub stop_price: Option<f64>,
+    /// Only for TRAILING_STOP_MARKET
+    pub activation_price: Option<f64>,
+    /// Only for TRAILING_STOP_MARKET
+    pub callback_rate: Option<f64>,
+    /// Default CONTRACT_PRICE
+    pub working_type: Option<WorkingType>,
+    #[serde(serialize_with = "serialize_opt_as_uppercase")]
+    pub price_protect: Option<bool>,
+    pub new_client_strategy_id: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UmOrder {
+    pub client_order_id: String,
+    #[serde(with = "string_or_float")]
+    pub cum_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub cum_quote: f64,
+    #[serde(with = "string_or_float")]
+    pub executed_qty: f64,
+    pub order_id: u64,
+    #[serde(with = "string_or_float")]
+    pub avg_price: f64,
+    #[serde(with = "string_or_float")]
+    pub orig_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    pub reduce_only: bool,
+    pub side: OrderSide,
+    pub position_side: PositionSide,
+    pub status: OrderStatus,
+    pub symbol: String,
+    pub time_in_force: TimeInForce,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UmConditionalOrder {
+    pub new_client_strategy_id: String,
+    pub strategy_id: u64,
+    /// NEW, CANCELED, EXPIRED or TRIGGERED
+    pub strategy_status: String,
+    pub strategy_type: StrategyType,
+    #[serde(with = "string_or_float")]
+    pub orig_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    pub reduce_only: bool,
+    pub side: OrderSide,
+    pub position_side: PositionSide,
+    #[serde(default, with = "string_or_float_opt")]
+    pub stop_price: Option<f64>,
+    pub symbol: String,
+    pub time_in_force: TimeInForce,
+    #[serde(default, with = "string_or_float_opt")]
+    pub activate_price: Option<f64>,
+    #[serde(default, with = "string_or_float_opt")]
+    pub price_rate: Option<f64>,
+    pub book_time: u64,
+    pub update_time: u64,
+    pub working_type: WorkingType,
+    pub price_protect: bool,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UmTrade {
+    pub symbol: String,
+    pub id: u64,
+    pub order_id: u64,
+    pub side: OrderSide,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    #[serde(with = "string_or_float")]
+    pub qty: f64,
+    #[serde(with = "string_or_float")]
+    pub realized_pnl: f64,
+    pub margin_asset: String,
+    #[serde(with = "string_or_float")]
+    pub quote_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub commission: f64,
+    pub commission_asset: String,
+    pub time: u64,
+    pub buyer: bool,
+    pub maker: bool,
+    pub position_side: PositionSide,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct UmPosition {
+    pub symbol: String,
+    #[serde(with = "string_or_float", rename = "positionAmt")]
+    pub position_amount: f64,
+    #[serde(with = "string_or_float")]
+    pub entry_price: f64,
+    #[serde(with = "string_or_float")]
+    pub mark_price: f64,
+    #[serde(with = "string_or_float", rename = "unRealizedProfit")]
+    pub unrealized_profit: f64,
+    #[serde(with = "string_or_float")]
+    pub liquidation_price: f64,
+    #[serde(with = "string_or_float")]
+    pub leverage: f64,
+    #[serde(with = "string_or_float")]
+    pub max_notional_value: f64,
+    pub position_side: PositionSide,
+    #[serde(with = "string_or_float")]
+    pub notional: f64,
+    pub update_time: u64,
+}
+
+/// Identifies an order by order id or client order id
+#[derive(Default, Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderIdQuery {
+    pub symbol: String,
+    pub order_id: Option<u64>,
+    pub orig_client_order_id: Option<String>,
+}
+
+/// Query on the orders or trades of a symbol
+#[derive(Default, Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct HistoryQuery {
+    pub symbol: String,
+    /// Orders with an id greater than or equal to this one, only for orders
+    pub order_id: Option<u64>,
+    /// Trades with an id greater than or equal to this one, only for trades
+    pub from_id: Option<u64>,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    pub limit: Option<u16>,
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn account_information() {
+        let account: AccountInformation = serde_json::from_str(
+            r#"{"uniMMR":"5167.92171923","accountEquity":"122607.35137903","actualEquity":"73.47428058",
+            "accountInitialMargin":"23.72469206","accountMaintMargin":"23.72469206","accountStatus":"NORMAL",
+            "virtualMaxWithdrawAmount":"1627523.32459208","totalAvailableBalance":"1627523.32459208",
+            "totalMarginOpenLoss":"0.00000000","updateTime":1657707212154}"#,
+        )
+        .unwrap();
+        assert_eq!(account.uni_mmr, 5167.92171923);
+        assert_eq!(account.account_status, AccountStatus::Normal);
+    }
+
+    #[test]
+    fn um_order_request_query() {
+        let order = UmOrderRequest::limit("BTCUSDT", OrderSide::Buy, 0.001, 20000.0);
+        assert_eq!(
+            crate::util::build_request_p(order).unwrap(),
+            "symbol=BTCUSDT&side=BUY&type=LIMIT&timeInForce=GTC&quantity=0.001&price=20000"
+        );
+    }
+}
@@ -14,11 +14,24 @@ pub struct ExchangeInformation {
     pub exchange_filters: Vec<Filters>,
 }
 
+impl ExchangeInformation {
+    /// Symbol named `symbol`
+    pub fn symbol(&self, symbol: &str) -> Option<&Symbol> { self.symbols.iter().find(|s| s.symbol == symbol) }
+
+    /// Symbols accepting orders, the other ones are halted, in a break or outside of trading hours
+    pub fn tradable_symbols(&self) -> impl Iterator<Item = &Symbol> { self.symbols.iter().filter(|s| s.is_trading()) }
+
+    /// Base and quote assets of `symbol`, e.g. `("BTC", "USDT")` for `"BTCUSDT"`, `None` for unknown symbols
+    pub fn split_symbol(&self, symbol: &str) -> Option<(String, String)> {
+        self.symbol(symbol).map(|s| (s.base_asset.clone(), s.quote_asset.clone()))
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Symbol {
     pub symbol: String,
-    pub status: String,
+    pub status: SymbolStatus,
     pub base_asset: String,
     pub base_asset_precision: u64,
     pub quote_asset: String,
@@ -33,10 +46,24 @@ pub struct Symbol {
     pub is_spot_trading_allowed: bool,
     pub is_margin_trading_allowed: bool,
     pub filters: Vec<Filters>,
+    /// Empty when the permissions are given by `permission_sets`
+    #[serde(default)]
     pub permissions: Vec<SymbolPermission>,
+    /// An account may trade the symbol when it has every permission of one of the sets
+    #[serde(default)]
+    pub permission_sets: Vec<Vec<SymbolPermission>>,
 }
 
 impl Symbol {
+    /// Orders are accepted, they are rejected with -2010 in any other status
+    pub fn is_trading(&self) -> bool { self.status == SymbolStatus::Trading }
+
+    /// Asset bought and sold
+    pub fn base_asset(&self) -> &str { &self.base_asset }
+
+    /// Asset the prices are expressed in
+    pub fn quote_asset(&self) -> &str { &self.quote_asset }
+
     pub fn lot_size(&self) -> Option<Filters> {
         self.filters
             .clone()
@@ -146,6 +173,46 @@ pub struct AccountInformation {
     pub update_time: i64,
 }
 
+impl AccountInformation {
+    /// Balances by asset
+    pub fn balance_map(&self) -> std::collections::HashMap<String, Balance> {
+        self.balances.iter().map(|b| (b.asset.clone(), b.clone())).collect()
+    }
+
+    /// Balances of `assets` by asset, zero for the assets the account does not list
+    pub fn balances_of(&self, assets: &[&str]) -> std::collections::HashMap<String, Balance> {
+        assets
+            .iter()
+            .map(|&asset| {
+                let balance = self.balance(asset).cloned().unwrap_or(Balance {
+                    asset: asset.to_string(),
+                    free: 0.0,
+                    locked: 0.0,
+                });
+                (asset.to_string(), balance)
+            })
+            .collect()
+    }
+
+    /// Balances by asset, without the assets holding nothing
+    pub fn non_zero_balance_map(&self) -> std::collections::HashMap<String, Balance> {
+        self.non_zero_balances().map(|b| (b.asset.clone(), b.clone())).collect()
+    }
+
+    /// Balances of the assets actually held, accounts list every asset they ever touched
+    pub fn non_zero_balances(&self) -> impl Iterator<Item = &Balance> { self.balances.iter().filter(|b| !b.is_zero()) }
+
+    /// Balance of an asset, looked up by a scan of the balances like [`Self::free`] and [`Self::locked`]: use
+    /// [`Self::balance_map`] for many lookups in the same account
+    pub fn balance(&self, asset: &str) -> Option<&Balance> { self.balances.iter().find(|b| b.asset == asset) }
+
+    /// Free amount of an asset, 0 if the account does not list it
+    pub fn free(&self, asset: &str) -> f64 { self.balance(asset).map_or(0.0, |b| b.free) }
+
+    /// Locked amount of an asset, 0 if the account does not list it
+    pub fn locked(&self, asset: &str) -> f64 { self.balance(asset).map_or(0.0, |b| b.locked) }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum MarketPermission {
@@ -176,6 +243,12 @@ pub struct Balance {
     pub locked: f64,
 }
 
+impl Balance {
+    pub fn total(&self) -> f64 { self.free + self.locked }
+
+    pub fn is_zero(&self) -> bool { self.free == 0.0 && self.locked == 0.0 }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct Order {
@@ -214,6 +287,52 @@ pub struct OrderCanceled {
     pub orig_client_order_id: String,
     pub order_id: u64,
     pub client_order_id: String,
+    #[serde(with = "string_or_float")]
+    pub orig_qty: f64,
+    /// Quantity filled before the order was canceled
+    #[serde(with = "string_or_float")]
+    pub executed_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub cummulative_quote_qty: f64,
+    pub status: OrderStatus,
+}
+
+impl OrderCanceled {
+    /// Part of the order was filled before it was canceled
+    pub fn was_partially_filled(&self) -> bool { self.executed_qty > 0.0 }
+}
+
+/// Response to an order amendment (endpoint /api/v3/order/amend/keepPriority)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderAmended {
+    pub transact_time: u64,
+    pub execution_id: u64,
+    pub amended_order: AmendedOrder,
+}
+
+/// Order after its amendment, `qty` is its new quantity
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AmendedOrder {
+    pub symbol: String,
+    pub order_id: u64,
+    pub order_list_id: i32,
+    pub orig_client_order_id: String,
+    pub client_order_id: String,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    #[serde(with = "string_or_float")]
+    pub qty: f64,
+    #[serde(with = "string_or_float")]
+    pub executed_qty: f64,
+    #[serde(with = "string_or_float")]
+    pub cumulative_quote_qty: f64,
+    pub status: OrderStatus,
+    pub time_in_force: TimeInForce,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    pub side: OrderSide,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -248,9 +367,54 @@ pub struct Transaction {
     #[serde(rename = "type")]
     pub order_type: OrderType,
     pub side: OrderSide,
+    /// Empty unless the response type is FULL
+    #[serde(default)]
     pub fills: Vec<Fill>,
 }
 
+/// Response to a new order with the ACK response type
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderAck {
+    pub symbol: String,
+    pub order_id: u64,
+    pub order_list_id: i64,
+    pub client_order_id: String,
+    pub transact_time: u64,
+}
+
+/// Response to a new order, whichever response type Binance answered with
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(untagged)]
+pub enum NewOrderResponse {
+    /// RESULT or FULL, only FULL has fills
+    Transaction(Box<Transaction>),
+    Ack(OrderAck),
+}
+
+impl NewOrderResponse {
+    pub fn order_id(&self) -> u64 {
+        match self {
+            NewOrderResponse::Transaction(transaction) => transaction.order_id,
+            NewOrderResponse::Ack(ack) => ack.order_id,
+        }
+    }
+
+    pub fn client_order_id(&self) -> &str {
+        match self {
+            NewOrderResponse::Transaction(transaction) => &transaction.client_order_id,
+            NewOrderResponse::Ack(ack) => &ack.client_order_id,
+        }
+    }
+
+    pub fn transaction(&self) -> Option<&Transaction> {
+        match self {
+            NewOrderResponse::Transaction(transaction) => Some(transaction),
+            NewOrderResponse::Ack(_) => None,
+        }
+    }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TransactionId {
@@ -259,11 +423,52 @@ pub struct TransactionId {
 
 /// Response to a test order (endpoint /api/v3/order/test).
 ///
-/// Currently, the API responds {} on a successfull test transaction,
-/// hence this struct has no fields.
+/// The API responds {} on a successfull test transaction, unless commission rates were requested
+/// with `computeCommissionRates`, which fills every field.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TestResponse {
+    pub standard_commission_for_order: Option<CommissionRates>,
+    pub tax_commission_for_order: Option<CommissionRates>,
+    pub discount: Option<CommissionDiscount>,
+}
+
+/// Commission rates of the account for a symbol (endpoint /api/v3/account/commission)
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountCommission {
+    pub symbol: String,
+    pub standard_commission: CommissionRates,
+    pub tax_commission: CommissionRates,
+    pub discount: CommissionDiscount,
+}
+
+/// Maker and taker commission rates, as fractions (0.001 is 0.1%)
+///
+/// The buyer and seller rates are only given with the commission rates of the account, they are 0 otherwise.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct CommissionRates {
+    #[serde(with = "string_or_float")]
+    pub maker: f64,
+    #[serde(with = "string_or_float")]
+    pub taker: f64,
+    #[serde(default, with = "string_or_float")]
+    pub buyer: f64,
+    #[serde(default, with = "string_or_float")]
+    pub seller: f64,
+}
+
+/// Discount on the standard commission when paying fees with another asset (BNB)
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
-pub struct TestResponse {}
+pub struct CommissionDiscount {
+    pub enabled_for_account: bool,
+    pub enabled_for_symbol: bool,
+    pub discount_asset: String,
+    #[serde(with = "string_or_float")]
+    pub discount: f64,
+}
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -298,6 +503,310 @@ pub struct EnableFuturesOnSubAccountResponse {
     pub is_futures_enabled: bool,
 }
 
+/// Holding of an asset in a managed sub account
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedSubAccountAsset {
+    pub coin: String,
+    pub name: String,
+    #[serde(with = "string_or_float")]
+    pub total_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub available_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub in_order: f64,
+    #[serde(with = "string_or_float")]
+    pub btc_value: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum AccountSnapshotType {
+    Spot,
+    Margin,
+    Futures,
+}
+
+/// Daily snapshots of a managed sub account, of one type of account
+#[derive(Debug, Serialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ManagedSubAccountSnapshotQuery {
+    pub email: String,
+    #[serde(rename = "type")]
+    pub snapshot_type: AccountSnapshotType,
+    pub start_time: Option<u64>,
+    pub end_time: Option<u64>,
+    /// Default 7, min 7 max 30
+    pub limit: Option<u16>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSnapshotResponse {
+    pub code: i32,
+    pub msg: String,
+    pub snapshot_vos: Vec<AccountSnapshot>,
+}
+
+/// State of an account at the end of a day
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AccountSnapshot {
+    #[serde(flatten)]
+    pub data: AccountSnapshotData,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(tag = "type", content = "data", rename_all = "lowercase")]
+pub enum AccountSnapshotData {
+    Spot(SpotSnapshot),
+    Margin(MarginSnapshot),
+    Futures(FuturesSnapshot),
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotSnapshot {
+    pub balances: Vec<Balance>,
+    #[serde(with = "string_or_float")]
+    pub total_asset_of_btc: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginSnapshot {
+    #[serde(with = "string_or_float")]
+    pub margin_level: f64,
+    #[serde(with = "string_or_float")]
+    pub total_asset_of_btc: f64,
+    #[serde(with = "string_or_float")]
+    pub total_liability_of_btc: f64,
+    #[serde(with = "string_or_float")]
+    pub total_net_asset_of_btc: f64,
+    pub user_assets: Vec<MarginSnapshotAsset>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct MarginSnapshotAsset {
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub borrowed: f64,
+    #[serde(with = "string_or_float")]
+    pub free: f64,
+    #[serde(with = "string_or_float")]
+    pub interest: f64,
+    #[serde(with = "string_or_float")]
+    pub locked: f64,
+    #[serde(with = "string_or_float")]
+    pub net_asset: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesSnapshot {
+    pub assets: Vec<FuturesSnapshotAsset>,
+    pub position: Vec<FuturesSnapshotPosition>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesSnapshotAsset {
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub margin_balance: f64,
+    #[serde(with = "string_or_float")]
+    pub wallet_balance: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct FuturesSnapshotPosition {
+    pub symbol: String,
+    #[serde(with = "string_or_float")]
+    pub entry_price: f64,
+    #[serde(with = "string_or_float")]
+    pub mark_price: f64,
+    #[serde(with = "string_or_float")]
+    pub position_amt: f64,
+    #[serde(with = "string_or_float")]
+    pub un_realized_profit: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTradingStatusResponse {
+    pub data: ApiTradingStatus,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiTradingStatus {
+    pub is_locked: bool,
+    /// Time (ms) the lock is lifted, 0 when not locked
+    pub planned_recover_time: u64,
+    /// Trigger value of each indicator
+    pub trigger_condition: std::collections::HashMap<String, f64>,
+    /// Indicators of the symbols getting close to their trigger, by symbol
+    #[serde(default)]
+    pub indicators: std::collections::HashMap<String, Vec<TradingIndicator>>,
+    pub update_time: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct TradingIndicator {
+    #[serde(rename = "i")]
+    pub indicator: String,
+    /// Number of orders the indicator was computed on
+    #[serde(rename = "c")]
+    pub count: u64,
+    #[serde(rename = "v")]
+    pub current_value: f64,
+    #[serde(rename = "t")]
+    pub trigger_value: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PreventedMatch {
+    pub symbol: String,
+    pub prevented_match_id: u64,
+    pub taker_order_id: u64,
+    pub maker_order_id: u64,
+    pub trade_group_id: i64,
+    /// Self trade prevention mode of the taker order, e.g. EXPIRE_MAKER
+    pub self_trade_prevention_mode: String,
+    #[serde(with = "string_or_float")]
+    pub price: f64,
+    #[serde(with = "string_or_float")]
+    pub maker_prevented_quantity: f64,
+    pub transact_time: u64,
+}
+
+/// Usage of an order count limit, see [`crate::account::Account::order_count_usage`]
+pub type OrderCountUsage = OrderRateLimitUsage;
+
+/// Usage of the rate limits, as reported by the `X-MBX-USED-WEIGHT-*` and `X-MBX-ORDER-COUNT-*` headers of
+/// the REST responses
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RateLimits {
+    /// Request weight used in the current windows, by interval, e.g. `1m`
+    pub used_weight: std::collections::BTreeMap<String, u32>,
+    /// Orders placed in the current windows, by interval, e.g. `10s` or `1d`. Only order requests report
+    /// them, these are the counts of the last one.
+    pub order_count: std::collections::BTreeMap<String, u32>,
+    /// Time of the server when it answered, in ms
+    pub server_time: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct OrderRateLimitUsage {
+    pub rate_limit_type: RateLimitType,
+    pub interval: RateLimitInterval,
+    pub interval_num: i32,
+    pub limit: i32,
+    pub count: i32,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ApiKeyPermissions {
+    pub ip_restrict: bool,
+    pub create_time: u64,
+    pub enable_reading: bool,
+    pub enable_spot_and_margin_trading: bool,
+    pub enable_withdrawals: bool,
+    #[serde(default)]
+    pub enable_internal_transfer: bool,
+    #[serde(default)]
+    pub permits_universal_transfer: bool,
+    #[serde(default)]
+    pub enable_margin: bool,
+    #[serde(default)]
+    pub enable_futures: bool,
+    #[serde(default)]
+    pub enable_vanilla_options: bool,
+    #[serde(default)]
+    pub enable_portfolio_margin_trading: bool,
+    /// Expiration time (ms) of the trading authority, only set for keys without IP restriction
+    pub trading_authority_expiration_time: Option<u64>,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DividendRecords {
+    pub rows: Vec<DividendRecord>,
+    /// Number of records matching the query, across all pages
+    pub total: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DividendRecord {
+    pub id: u64,
+    #[serde(with = "string_or_float")]
+    pub amount: f64,
+    pub asset: String,
+    pub div_time: u64,
+    /// Description of the distribution
+    pub en_info: String,
+    pub tran_id: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DustableAssets {
+    pub details: Vec<DustableAsset>,
+    #[serde(with = "string_or_float")]
+    pub total_transfer_btc: f64,
+    #[serde(rename = "totalTransferBNB", with = "string_or_float")]
+    pub total_transfer_bnb: f64,
+    /// Service charge of the conversion, as a fraction
+    #[serde(with = "string_or_float")]
+    pub dribblet_percentage: f64,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct DustableAsset {
+    pub asset: String,
+    pub asset_full_name: String,
+    #[serde(with = "string_or_float")]
+    pub amount_free: f64,
+    #[serde(rename = "toBTC", with = "string_or_float")]
+    pub to_btc: f64,
+    /// BNB received for the asset, after the service charge
+    #[serde(rename = "toBNB", with = "string_or_float")]
+    pub to_bnb: f64,
+    #[serde(rename = "toBNBOffExchange", with = "string_or_float")]
+    pub to_bnb_off_exchange: f64,
+    #[serde(with = "string_or_float")]
+    pub exchange: f64,
+}
+
+/// Balance of an asset in the spot wallet, including the amounts frozen or being withdrawn, not to be
+/// confused with the [`UserAsset`] of a margin account
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SpotUserAsset {
+    pub asset: String,
+    #[serde(with = "string_or_float")]
+    pub free: f64,
+    #[serde(with = "string_or_float")]
+    pub locked: f64,
+    #[serde(with = "string_or_float")]
+    pub freeze: f64,
+    #[serde(with = "string_or_float")]
+    pub withdrawing: f64,
+    #[serde(with = "string_or_float")]
+    pub ipoable: f64,
+    /// Zero unless the valuation was asked for
+    #[serde(default, with = "string_or_float")]
+    pub btc_valuation: f64,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct SubAccountCreationReq {
@@ -328,6 +837,70 @@ pub struct Asks {
     pub qty: f64,
 }
 
+impl OrderBook {
+    /// Estimate the fill of a market order of `quantity` by walking the book, buys take the asks and sells take
+    /// the bids. Levels are expected best first, as the depth endpoint returns them.
+    pub fn simulate_market_order(&self, side: OrderSide, quantity: f64) -> SimulatedFill {
+        let levels: Vec<(f64, f64)> = match side {
+            OrderSide::Buy => self.asks.iter().map(|a| (a.price, a.qty)).collect(),
+            OrderSide::Sell => self.bids.iter().map(|b| (b.price, b.qty)).collect(),
+        };
+        let mut remaining = quantity;
+        let mut quote_qty = 0.0;
+        let mut worst_price = None;
+        for (price, qty) in levels.iter().copied() {
+            if remaining <= 0.0 {
+                break;
+            }
+            let taken = qty.min(remaining);
+            quote_qty += taken * price;
+            remaining -= taken;
+            worst_price = Some(price);
+        }
+        let filled_qty = quantity - remaining.max(0.0);
+        let best_price = levels.first().map(|(price, _)| *price);
+        let average_price = (filled_qty > 0.0).then(|| quote_qty / filled_qty);
+        let slippage = match (average_price, best_price) {
+            (Some(average), Some(best)) => Some((average - best).abs() / best),
+            _ => None,
+        };
+        SimulatedFill {
+            side,
+            requested_qty: quantity,
+            filled_qty,
+            unfilled_qty: remaining.max(0.0),
+            quote_qty,
+            best_price,
+            worst_price,
+            average_price,
+            slippage,
+        }
+    }
+}
+
+/// Expected outcome of a market order against a snapshot of the book
+#[derive(Debug, Clone)]
+pub struct SimulatedFill {
+    pub side: OrderSide,
+    pub requested_qty: f64,
+    pub filled_qty: f64,
+    /// Quantity the book was too thin to fill
+    pub unfilled_qty: f64,
+    /// Quote asset spent on a buy, or received on a sell
+    pub quote_qty: f64,
+    /// Best price of the side taken, `None` if it is empty
+    pub best_price: Option<f64>,
+    /// Price of the last level reached
+    pub worst_price: Option<f64>,
+    pub average_price: Option<f64>,
+    /// Relative distance between the average price and the best price, `0.01` is 1%
+    pub slippage: Option<f64>,
+}
+
+impl SimulatedFill {
+    pub fn is_complete(&self) -> bool { self.unfilled_qty <= 0.0 }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct UserDataStream {
@@ -365,6 +938,75 @@ pub enum BookTickers {
     AllBookTickers(Vec<Tickers>),
 }
 
+/// Interval of klines, for the REST queries and the kline streams
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KlineInterval {
+    #[serde(rename = "1s")]
+    OneSecond,
+    #[serde(rename = "1m")]
+    OneMinute,
+    #[serde(rename = "3m")]
+    ThreeMinutes,
+    #[serde(rename = "5m")]
+    FiveMinutes,
+    #[serde(rename = "15m")]
+    FifteenMinutes,
+    #[serde(rename = "30m")]
+    ThirtyMinutes,
+    #[serde(rename = "1h")]
+    OneHour,
+    #[serde(rename = "2h")]
+    TwoHours,
+    #[serde(rename = "4h")]
+    FourHours,
+    #[serde(rename = "6h")]
+    SixHours,
+    #[serde(rename = "8h")]
+    EightHours,
+    #[serde(rename = "12h")]
+    TwelveHours,
+    #[serde(rename = "1d")]
+    OneDay,
+    #[serde(rename = "3d")]
+    ThreeDays,
+    #[serde(rename = "1w")]
+    OneWeek,
+    #[serde(rename = "1M")]
+    OneMonth,
+}
+
+impl KlineInterval {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            KlineInterval::OneSecond => "1s",
+            KlineInterval::OneMinute => "1m",
+            KlineInterval::ThreeMinutes => "3m",
+            KlineInterval::FiveMinutes => "5m",
+            KlineInterval::FifteenMinutes => "15m",
+            KlineInterval::ThirtyMinutes => "30m",
+            KlineInterval::OneHour => "1h",
+            KlineInterval::TwoHours => "2h",
+            KlineInterval::FourHours => "4h",
+            KlineInterval::SixHours => "6h",
+            KlineInterval::EightHours => "8h",
+            KlineInterval::TwelveHours => "12h",
+            KlineInterval::OneDay => "1d",
+            KlineInterval::ThreeDays => "3d",
+            KlineInterval::OneWeek => "1w",
+            KlineInterval::OneMonth => "1M",
+        }
+    }
+}
+
+impl std::fmt::Display for KlineInterval {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { f.write_str(self.as_str()) }
+}
+
+/// Lets the klines queries take a [`KlineInterval`] as well as a string
+impl From<KlineInterval> for String {
+    fn from(interval: KlineInterval) -> Self { interval.as_str().to_string() }
+}
+
 #[derive(Debug, Clone)]
 pub enum KlineSummaries {
     AllKlineSummaries(Vec<KlineSummary>),
@@ -387,11 +1029,15 @@ pub struct Tickers {
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TradeHistory {
+    pub symbol: String,
     pub id: u64,
+    pub order_id: u64,
     #[serde(with = "string_or_float")]
     pub price: f64,
     #[serde(with = "string_or_float")]
     pub qty: f64,
+    #[serde(with = "string_or_float")]
+    pub quote_qty: f64,
     pub commission: String,
     pub commission_asset: String,
     pub time: u64,
@@ -505,7 +1151,7 @@ pub enum TimeInForce {
     Other,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderResponse {
     Ack,
@@ -519,8 +1165,12 @@ pub enum OrderResponse {
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SideEffectType {
     NoSideEffect,
+    /// Borrow what the order needs
     MarginBuy,
+    /// Repay the debt with what the order brings
     AutoRepay,
+    /// Borrow what the order needs, and repay the debt with what a later order brings
+    AutoBorrowRepay,
     #[serde(other)]
     Other,
 }
@@ -707,7 +1357,6 @@ pub struct OCOOrderReport {
     pub time_in_force: TimeInForce,
     #[serde(rename = "type")]
     pub order_type: OrderType,
-    #[serde(rename = "type")]
     pub side: OrderSide,
     #[serde(with = "string_or_float")]
     pub stop_price: f64,
@@ -1204,7 +1853,7 @@ pub struct MaxTransferableAmount {
     pub amount: f64,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SymbolStatus {
     PreTrading,
@@ -1219,7 +1868,7 @@ pub enum SymbolStatus {
     Other,
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum SymbolPermission {
     Spot,
@@ -1248,6 +1897,8 @@ pub enum OrderStatus {
     Expired,
     /// Part of the order or all of the order's quantity has filled.
     Trade,
+    /// The order was expired by self trade prevention.
+    ExpiredInMatch,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -1441,7 +2092,7 @@ pub mod string_or_float {
 pub(crate) mod string_or_float_opt {
     use std::fmt;
 
-    use serde::{Deserialize, Deserializer, Serializer};
+    use serde::{de, Deserialize, Deserializer, Serializer};
 
     pub fn serialize<T, S>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -1465,7 +2116,41 @@ pub(crate) mod string_or_float_opt {
             Float(f64),
         }
 
-        Ok(Some(crate::rest_model::string_or_float::deserialize(deserializer)?))
+        // Some endpoints send an empty string in place of an absent value
+        match StringOrFloat::deserialize(deserializer)? {
+            StringOrFloat::String(s) if s.is_empty() => Ok(None),
+            StringOrFloat::String(s) => s.parse().map(Some).map_err(de::Error::custom),
+            StringOrFloat::Float(i) => Ok(Some(i)),
+        }
+    }
+}
+
+/// Integers that some payloads send as strings, e.g. the event time of the futures `listenKeyExpired`
+pub(crate) mod string_or_u64 {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S>(value: &u64, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_u64(*value)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<u64, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        #[serde(untagged)]
+        enum StringOrU64 {
+            String(String),
+            U64(u64),
+        }
+
+        match StringOrU64::deserialize(deserializer)? {
+            StringOrU64::String(s) => s.parse().map_err(de::Error::custom),
+            StringOrU64::U64(i) => Ok(i),
+        }
     }
 }
 
@@ -1473,7 +2158,8 @@ pub(crate) mod string_or_float_opt {
 mod test {
     use std::path::PathBuf;
 
-    use crate::rest_model::ExchangeInformation;
+    use crate::rest_model::{AccountInformation, AccountSnapshotData, AccountSnapshotResponse, ExchangeInformation,
+                            OrderBook, OrderCanceled, OrderSide, OrderStatus, Symbol, SymbolPermission, SymbolStatus};
 
     #[test]
     fn exchange_info_serde() {
@@ -1482,5 +2168,106 @@ mod test {
         let fc = std::fs::read_to_string(d).unwrap();
         let result = serde_json::from_str::<ExchangeInformation>(&fc);
         assert!(result.is_ok(), "{:?}", result);
+        let info = result.unwrap();
+        assert_eq!(info.split_symbol("ETHBTC"), Some(("ETH".to_string(), "BTC".to_string())));
+        assert_eq!(info.split_symbol("NOTASYMBOL"), None);
+        assert_eq!(info.symbol("LTCBTC").unwrap().base_asset(), "LTC");
+        assert_eq!(info.tradable_symbols().count(), 592);
+        assert!(info.symbols.iter().any(|s| s.status == SymbolStatus::Break));
+
+        let symbol: Symbol = serde_json::from_str(
+            r#"{"symbol":"BTCUSDT","status":"HALT","baseAsset":"BTC","baseAssetPrecision":8,"quoteAsset":"USDT",
+            "quotePrecision":8,"quoteAssetPrecision":8,"baseCommissionPrecision":8,"quoteCommissionPrecision":8,
+            "orderTypes":["LIMIT"],"icebergAllowed":true,"ocoAllowed":true,"quoteOrderQtyMarketAllowed":true,
+            "isSpotTradingAllowed":true,"isMarginTradingAllowed":false,"filters":[],"permissions":[],
+            "permissionSets":[["SPOT","TRD_GRP_004"]]}"#,
+        )
+        .unwrap();
+        assert!(!symbol.is_trading());
+        assert_eq!(symbol.permission_sets, vec![vec![SymbolPermission::Spot, SymbolPermission::Other]]);
+    }
+
+    #[test]
+    fn account_balances_lookup() {
+        let account: AccountInformation = serde_json::from_value(serde_json::json!({
+            "makerCommission": 15, "takerCommission": 15, "buyerCommission": 0, "sellerCommission": 0,
+            "canTrade": true, "canWithdraw": true, "canDeposit": true, "accountType": "SPOT",
+            "balances": [
+                {"asset": "BTC", "free": "4723846.89208129", "locked": "0.00000000"},
+                {"asset": "LTC", "free": "0.00000000", "locked": "0.00000000"},
+                {"asset": "ETH", "free": "1.00000000", "locked": "0.50000000"}
+            ],
+            "permissions": ["SPOT"], "updateTime": 123456789
+        }))
+        .unwrap();
+        assert_eq!(account.balance_map().len(), 3);
+        let non_zero = account.non_zero_balance_map();
+        assert_eq!(non_zero.len(), 2);
+        assert!(!non_zero.contains_key("LTC"));
+        assert_eq!(account.free("BTC"), 4723846.89208129);
+        assert_eq!(account.locked("ETH"), 0.5);
+        assert_eq!(account.free("DOGE"), 0.0);
+        assert_eq!(account.balance("ETH").unwrap().total(), 1.5);
+        let balances = account.balances_of(&["ETH", "DOGE"]);
+        assert_eq!(balances.len(), 2);
+        assert_eq!(balances["ETH"].free, 1.0);
+        assert!(balances["DOGE"].is_zero());
+        assert_eq!(balances["DOGE"].asset, "DOGE");
+    }
+
+    #[test]
+    fn account_snapshots() {
+        let response: AccountSnapshotResponse = serde_json::from_str(
+            r#"{"code":200,"msg":"","snapshotVos":[
+                {"data":{"balances":[{"asset":"BTC","free":"0.09905021","locked":"0.00000000"}],"totalAssetOfBtc":"0.09942700"},"type":"spot","updateTime":1576281599000},
+                {"data":{"marginLevel":"2748.02909813","totalAssetOfBtc":"0.00274803","totalLiabilityOfBtc":"0.00000100","totalNetAssetOfBtc":"0.00274750","userAssets":[{"asset":"XRP","borrowed":"0.00000000","free":"1.00000000","interest":"0.00000000","locked":"0.00000000","netAsset":"1.00000000"}]},"type":"margin","updateTime":1576281599000},
+                {"data":{"assets":[{"asset":"USDT","marginBalance":"118.99782335","walletBalance":"120.23811389"}],"position":[{"entryPrice":"7130.41000000","markPrice":"7257.66239673","positionAmt":"0.01000000","symbol":"BTCUSDT","unRealizedProfit":"1.24029054"}]},"type":"futures","updateTime":1576281599000}
+            ]}"#,
+        )
+        .unwrap();
+        assert_eq!(response.snapshot_vos[0].update_time, 1576281599000);
+        match &response.snapshot_vos[0].data {
+            AccountSnapshotData::Spot(spot) => assert_eq!(spot.balances[0].free, 0.09905021),
+            other => panic!("unexpected snapshot {:?}", other),
+        }
+        assert!(matches!(&response.snapshot_vos[1].data, AccountSnapshotData::Margin(m) if m.user_assets.len() == 1));
+        match &response.snapshot_vos[2].data {
+            AccountSnapshotData::Futures(futures) => assert_eq!(futures.position[0].position_amt, 0.01),
+            other => panic!("unexpected snapshot {:?}", other),
+        }
+    }
+
+    #[test]
+    fn partially_filled_cancellation() {
+        let canceled: OrderCanceled = serde_json::from_str(
+            r#"{"symbol":"LTCBTC","origClientOrderId":"myOrder1","orderId":4,"orderListId":-1,
+            "clientOrderId":"cancelMyOrder1","transactTime":1684804350068,"price":"2.00000000",
+            "origQty":"1.00000000","executedQty":"0.25000000","cummulativeQuoteQty":"0.50000000",
+            "status":"CANCELED","timeInForce":"GTC","type":"LIMIT","side":"BUY","selfTradePreventionMode":"NONE"}"#,
+        )
+        .unwrap();
+        assert!(canceled.was_partially_filled());
+        assert_eq!(canceled.executed_qty, 0.25);
+        assert_eq!(canceled.cummulative_quote_qty, 0.5);
+        assert!(matches!(canceled.status, OrderStatus::Canceled));
+    }
+
+    #[test]
+    fn simulated_market_order_walks_the_book() {
+        let book: OrderBook = serde_json::from_str(
+            r#"{"lastUpdateId":1,"bids":[["99.0","1.0"],["98.0","2.0"]],"asks":[["100.0","1.0"],["102.0","1.0"]]}"#,
+        )
+        .unwrap();
+        let buy = book.simulate_market_order(OrderSide::Buy, 1.5);
+        assert!(buy.is_complete());
+        assert_eq!(buy.quote_qty, 151.0);
+        assert_eq!(buy.worst_price, Some(102.0));
+        assert!((buy.average_price.unwrap() - 100.0 * 151.0 / 150.0).abs() < 1e-9);
+        assert!((buy.slippage.unwrap() - 1.0 / 150.0).abs() < 1e-9);
+        // the bids hold 3.0, the rest cannot be filled
+        let sell = book.simulate_market_order(OrderSide::Sell, 5.0);
+        assert_eq!(sell.filled_qty, 3.0);
+        assert_eq!(sell.unfilled_qty, 2.0);
+        assert_eq!(sell.average_price, Some(295.0 / 3.0));
     }
 }
@@ -2,8 +2,22 @@ use crate::client::*;
 use crate::errors::*;
 use crate::rest_model::*;
 use crate::util::*;
+use reqwest::StatusCode;
+use serde::de::DeserializeOwned;
 use std::collections::BTreeMap;
 
+static SAPI_V1_SIMPLE_EARN_FLEXIBLE_LIST: &str = "/sapi/v1/simple-earn/flexible/list";
+static SAPI_V1_SIMPLE_EARN_LOCKED_LIST: &str = "/sapi/v1/simple-earn/locked/list";
+static SAPI_V1_SIMPLE_EARN_FLEXIBLE_POSITION: &str = "/sapi/v1/simple-earn/flexible/position";
+static SAPI_V1_SIMPLE_EARN_LOCKED_POSITION: &str = "/sapi/v1/simple-earn/locked/position";
+static SAPI_V1_LENDING_DAILY_PRODUCT_LIST: &str = "/sapi/v1/lending/daily/product/list";
+static SAPI_V1_LENDING_PROJECT_LIST: &str = "/sapi/v1/lending/project/list";
+static SAPI_V1_LENDING_DAILY_TOKEN_POSITION: &str = "/sapi/v1/lending/daily/token/position";
+static SAPI_V1_LENDING_PROJECT_POSITION_LIST: &str = "/sapi/v1/lending/project/position/list";
+
+/// Largest page of the simple earn and lending product lists
+const EARN_PAGE_SIZE: usize = 100;
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct CoinInfo {
@@ -83,6 +97,289 @@ pub struct DepositAddress {
     pub url: String,
 }
 
+/// Savings products, flexible ones can be redeemed at any time, locked ones (fixed in the legacy lending
+/// API) at the end of their duration
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum EarnProductType {
+    Flexible,
+    Locked,
+}
+
+/// Savings product of the simple earn API, or of the legacy lending API on accounts which were not migrated
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EarnProduct {
+    pub product_type: EarnProductType,
+    /// Product id of flexible products, project id of locked ones
+    pub product_id: String,
+    pub asset: String,
+    /// Latest annual rate of flexible products, fixed annual rate of locked ones
+    pub annual_percentage_rate: f64,
+    /// Days until locked products can be redeemed
+    pub duration: Option<u64>,
+    pub min_purchase_amount: f64,
+    pub can_purchase: bool,
+}
+
+/// Holding in a savings product, of the simple earn API or of the legacy lending API
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct EarnPosition {
+    pub product_type: EarnProductType,
+    /// Product id of flexible products, project id of locked ones
+    pub product_id: String,
+    /// Id of locked positions, a product can be subscribed several times
+    pub position_id: Option<u64>,
+    pub asset: String,
+    pub amount: f64,
+    pub annual_percentage_rate: Option<f64>,
+    pub duration: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct SimpleEarnRows<T> {
+    rows: Vec<T>,
+    total: usize,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimpleEarnFlexibleProduct {
+    asset: String,
+    #[serde(with = "string_or_float")]
+    latest_annual_percentage_rate: f64,
+    can_purchase: bool,
+    #[serde(with = "string_or_float")]
+    min_purchase_amount: f64,
+    product_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimpleEarnLockedProduct {
+    project_id: String,
+    detail: SimpleEarnLockedDetail,
+    quota: SimpleEarnLockedQuota,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimpleEarnLockedDetail {
+    asset: String,
+    duration: u64,
+    #[serde(with = "string_or_float")]
+    apr: f64,
+    is_sold_out: bool,
+}
+
+#[derive(Deserialize)]
+struct SimpleEarnLockedQuota {
+    #[serde(with = "string_or_float")]
+    minimum: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimpleEarnFlexiblePosition {
+    asset: String,
+    product_id: String,
+    #[serde(with = "string_or_float")]
+    total_amount: f64,
+    #[serde(with = "string_or_float")]
+    latest_annual_percentage_rate: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SimpleEarnLockedPosition {
+    #[serde(with = "string_or_u64")]
+    position_id: u64,
+    project_id: String,
+    asset: String,
+    #[serde(with = "string_or_float")]
+    amount: f64,
+    #[serde(with = "string_or_u64")]
+    duration: u64,
+    #[serde(rename = "APY", with = "string_or_float")]
+    apy: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LendingFlexibleProduct {
+    asset: String,
+    #[serde(with = "string_or_float")]
+    avg_annual_interest_rate: f64,
+    can_purchase: bool,
+    #[serde(with = "string_or_float")]
+    min_purchase_amount: f64,
+    product_id: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LendingFixedProject {
+    asset: String,
+    duration: u64,
+    #[serde(with = "string_or_float")]
+    interest_rate: f64,
+    #[serde(with = "string_or_float")]
+    lot_size: f64,
+    project_id: String,
+    status: String,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LendingFlexiblePosition {
+    asset: String,
+    product_id: String,
+    #[serde(with = "string_or_float")]
+    total_amount: f64,
+    #[serde(with = "string_or_float")]
+    annual_interest_rate: f64,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct LendingFixedPosition {
+    asset: String,
+    duration: u64,
+    #[serde(with = "string_or_float")]
+    interest_rate: f64,
+    position_id: u64,
+    #[serde(with = "string_or_float")]
+    principal: f64,
+    project_id: String,
+}
+
+impl From<SimpleEarnFlexibleProduct> for EarnProduct {
+    fn from(p: SimpleEarnFlexibleProduct) -> Self {
+        EarnProduct {
+            product_type: EarnProductType::Flexible,
+            product_id: p.product_id,
+            asset: p.asset,
+            annual_percentage_rate: p.latest_annual_percentage_rate,
+            duration: None,
+            min_purchase_amount: p.min_purchase_amount,
+            can_purchase: p.can_purchase,
+        }
+    }
+}
+
+impl From<SimpleEarnLockedProduct> for EarnProduct {
+    fn from(p: SimpleEarnLockedProduct) -> Self {
+        EarnProduct {
+            product_type: EarnProductType::Locked,
+            product_id: p.project_id,
+            asset: p.detail.asset,
+            annual_percentage_rate: p.detail.apr,
+            duration: Some(p.detail.duration),
+            min_purchase_amount: p.quota.minimum,
+            can_purchase: !p.detail.is_sold_out,
+        }
+    }
+}
+
+impl From<LendingFlexibleProduct> for EarnProduct {
+    fn from(p: LendingFlexibleProduct) -> Self {
+        EarnProduct {
+            product_type: EarnProductType::Flexible,
+            product_id: p.product_id,
+            asset: p.asset,
+            annual_percentage_rate: p.avg_annual_interest_rate,
+            duration: None,
+            min_purchase_amount: p.min_purchase_amount,
+            can_purchase: p.can_purchase,
+        }
+    }
+}
+
+impl From<LendingFixedProject> for EarnProduct {
+    fn from(p: LendingFixedProject) -> Self {
+        EarnProduct {
+            product_type: EarnProductType::Locked,
+            product_id: p.project_id,
+            asset: p.asset,
+            annual_percentage_rate: p.interest_rate,
+            duration: Some(p.duration),
+            // fixed projects are bought by lots
+            min_purchase_amount: p.lot_size,
+            can_purchase: p.status == "PURCHASING",
+        }
+    }
+}
+
+impl From<SimpleEarnFlexiblePosition> for EarnPosition {
+    fn from(p: SimpleEarnFlexiblePosition) -> Self {
+        EarnPosition {
+            product_type: EarnProductType::Flexible,
+            product_id: p.product_id,
+            position_id: None,
+            asset: p.asset,
+            amount: p.total_amount,
+            annual_percentage_rate: Some(p.latest_annual_percentage_rate),
+            duration: None,
+        }
+    }
+}
+
+impl From<SimpleEarnLockedPosition> for EarnPosition {
+    fn from(p: SimpleEarnLockedPosition) -> Self {
+        EarnPosition {
+            product_type: EarnProductType::Locked,
+            product_id: p.project_id,
+            position_id: Some(p.position_id),
+            asset: p.asset,
+            amount: p.amount,
+            annual_percentage_rate: Some(p.apy),
+            duration: Some(p.duration),
+        }
+    }
+}
+
+impl From<LendingFlexiblePosition> for EarnPosition {
+    fn from(p: LendingFlexiblePosition) -> Self {
+        EarnPosition {
+            product_type: EarnProductType::Flexible,
+            product_id: p.product_id,
+            position_id: None,
+            asset: p.asset,
+            amount: p.total_amount,
+            annual_percentage_rate: Some(p.annual_interest_rate),
+            duration: None,
+        }
+    }
+}
+
+impl From<LendingFixedPosition> for EarnPosition {
+    fn from(p: LendingFixedPosition) -> Self {
+        EarnPosition {
+            product_type: EarnProductType::Locked,
+            product_id: p.project_id,
+            position_id: Some(p.position_id),
+            asset: p.asset,
+            amount: p.principal,
+            annual_percentage_rate: Some(p.interest_rate),
+            duration: Some(p.duration),
+        }
+    }
+}
+
+/// The endpoint was retired, or is not available yet, for this account
+fn is_unavailable_endpoint(error: &Error) -> bool {
+    match error {
+        Error::BinanceError { response } => {
+            matches!(response.status(), Some(StatusCode::NOT_FOUND | StatusCode::GONE))
+                || response.error_code() == BinanceErrorCode::UnsupportedOperation
+                || response.msg.to_lowercase().contains("deprecated")
+        }
+        Error::NonJsonResponse { status, .. } => *status == StatusCode::NOT_FOUND || *status == StatusCode::GONE,
+        _ => false,
+    }
+}
+
 #[derive(Clone)]
 pub struct Savings {
     pub client: Client,
@@ -149,4 +446,223 @@ impl Savings {
             .get_signed_d("/sapi/v1/capital/deposit/address", request.as_str())
             .await
     }
+
+    /// Savings products, from the simple earn API or from the legacy lending API when simple earn is not
+    /// available to the account
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, savings::*, config::*};
+    /// let savings: Savings = Binance::new_with_env(&Config::default());
+    /// let products = tokio_test::block_on(savings.earn_products(EarnProductType::Flexible));
+    /// assert!(products.is_ok(), "{:?}", products)
+    /// ```
+    pub async fn earn_products(&self, product_type: EarnProductType) -> Result<Vec<EarnProduct>> {
+        let products = match product_type {
+            EarnProductType::Flexible => self
+                .simple_earn_rows::<SimpleEarnFlexibleProduct>(SAPI_V1_SIMPLE_EARN_FLEXIBLE_LIST, BTreeMap::new())
+                .await
+                .map(|rows| rows.into_iter().map(EarnProduct::from).collect()),
+            EarnProductType::Locked => self
+                .simple_earn_rows::<SimpleEarnLockedProduct>(SAPI_V1_SIMPLE_EARN_LOCKED_LIST, BTreeMap::new())
+                .await
+                .map(|rows| rows.into_iter().map(EarnProduct::from).collect()),
+        };
+        match products {
+            Err(e) if is_unavailable_endpoint(&e) => self.lending_products(product_type).await,
+            products => products,
+        }
+    }
+
+    /// Positions in savings products, optionally of a single asset, from the simple earn API or from the
+    /// legacy lending API when simple earn is not available to the account
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{api::*, savings::*, config::*};
+    /// let savings: Savings = Binance::new_with_env(&Config::default());
+    /// let positions = tokio_test::block_on(savings.earn_positions(EarnProductType::Locked, Some("USDT".into())));
+    /// assert!(positions.is_ok(), "{:?}", positions)
+    /// ```
+    pub async fn earn_positions(
+        &self,
+        product_type: EarnProductType,
+        asset: Option<String>,
+    ) -> Result<Vec<EarnPosition>> {
+        let mut parameters = BTreeMap::new();
+        if let Some(asset) = &asset {
+            parameters.insert("asset".to_string(), asset.clone());
+        }
+        let positions = match product_type {
+            EarnProductType::Flexible => self
+                .simple_earn_rows::<SimpleEarnFlexiblePosition>(SAPI_V1_SIMPLE_EARN_FLEXIBLE_POSITION, parameters)
+                .await
+                .map(|rows| rows.into_iter().map(EarnPosition::from).collect()),
+            EarnProductType::Locked => self
+                .simple_earn_rows::<SimpleEarnLockedPosition>(SAPI_V1_SIMPLE_EARN_LOCKED_POSITION, parameters)
+                .await
+                .map(|rows| rows.into_iter().map(EarnPosition::from).collect()),
+        };
+        match positions {
+            Err(e) if is_unavailable_endpoint(&e) => self.lending_positions(product_type, asset).await,
+            positions => positions,
+        }
+    }
+
+    /// Every row of a paginated simple earn endpoint
+    async fn simple_earn_rows<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        parameters: BTreeMap<String, String>,
+    ) -> Result<Vec<T>> {
+        let mut rows = Vec::new();
+        for current in 1.. {
+            let mut parameters = parameters.clone();
+            parameters.insert("current".to_string(), current.to_string());
+            parameters.insert("size".to_string(), EARN_PAGE_SIZE.to_string());
+            let request = build_signed_request(parameters, self.recv_window)?;
+            let page: SimpleEarnRows<T> = self.client.get_signed_d(endpoint, &request).await?;
+            let last_page = page.rows.len() < EARN_PAGE_SIZE;
+            rows.extend(page.rows);
+            if last_page || rows.len() >= page.total {
+                break;
+            }
+        }
+        Ok(rows)
+    }
+
+    /// Every item of a paginated legacy lending endpoint
+    async fn lending_pages<T: DeserializeOwned>(
+        &self,
+        endpoint: &str,
+        parameters: BTreeMap<String, String>,
+    ) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        for current in 1.. {
+            let mut parameters = parameters.clone();
+            parameters.insert("current".to_string(), current.to_string());
+            parameters.insert("size".to_string(), EARN_PAGE_SIZE.to_string());
+            let request = build_signed_request(parameters, self.recv_window)?;
+            let page: Vec<T> = self.client.get_signed_d(endpoint, &request).await?;
+            let last_page = page.len() < EARN_PAGE_SIZE;
+            items.extend(page);
+            if last_page {
+                break;
+            }
+        }
+        Ok(items)
+    }
+
+    async fn lending_products(&self, product_type: EarnProductType) -> Result<Vec<EarnProduct>> {
+        match product_type {
+            EarnProductType::Flexible => {
+                let products: Vec<LendingFlexibleProduct> =
+                    self.lending_pages(SAPI_V1_LENDING_DAILY_PRODUCT_LIST, BTreeMap::new()).await?;
+                Ok(products.into_iter().map(EarnProduct::from).collect())
+            }
+            EarnProductType::Locked => {
+                let mut parameters = BTreeMap::new();
+                parameters.insert("type".to_string(), "CUSTOMIZED_FIXED".to_string());
+                let projects: Vec<LendingFixedProject> =
+                    self.lending_pages(SAPI_V1_LENDING_PROJECT_LIST, parameters).await?;
+                Ok(projects.into_iter().map(EarnProduct::from).collect())
+            }
+        }
+    }
+
+    async fn lending_positions(
+        &self,
+        product_type: EarnProductType,
+        asset: Option<String>,
+    ) -> Result<Vec<EarnPosition>> {
+        let mut parameters = BTreeMap::new();
+        if let Some(asset) = asset {
+            parameters.insert("asset".to_string(), asset);
+        }
+        match product_type {
+            EarnProductType::Flexible => {
+                let request = build_signed_request(parameters, self.recv_window)?;
+                let positions: Vec<LendingFlexiblePosition> = self
+                    .client
+                    .get_signed_d(SAPI_V1_LENDING_DAILY_TOKEN_POSITION, &request)
+                    .await?;
+                Ok(positions.into_iter().map(EarnPosition::from).collect())
+            }
+            EarnProductType::Locked => {
+                parameters.insert("type".to_string(), "CUSTOMIZED_FIXED".to_string());
+                let request = build_signed_request(parameters, self.recv_window)?;
+                let positions: Vec<LendingFixedPosition> = self
+                    .client
+                    .get_signed_d(SAPI_V1_LENDING_PROJECT_POSITION_LIST, &request)
+                    .await?;
+                Ok(positions.into_iter().map(EarnPosition::from).collect())
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{EarnProductType, Savings};
+    use crate::client::Client;
+    use crate::test_server::{ok, response, routed_server};
+
+    /// Answers the simple earn endpoints with `simple_earn`, the legacy lending ones with `lending`
+    async fn earn_server(simple_earn: (&'static str, &'static str), lending: &'static str) -> Savings {
+        let host = routed_server(move |request| {
+            if request.contains("/sapi/v1/simple-earn/") {
+                response(simple_earn.0, simple_earn.1)
+            } else {
+                ok(lending)
+            }
+        })
+        .await;
+        Savings {
+            client: Client::new(Some("key".into()), Some("secret".into()), host),
+            recv_window: 5000,
+        }
+    }
+
+    #[tokio::test]
+    async fn simple_earn_products() {
+        let savings = earn_server(
+            (
+                "200 OK",
+                r#"{"rows":[{"projectId":"Axs*90","detail":{"asset":"AXS","rewardAsset":"AXS","duration":90,
+                "renewable":true,"isSoldOut":false,"apr":"1.2069","status":"CREATED",
+                "subscriptionStartTime":"1646182276000"},"quota":{"totalPersonalQuota":"2","minimum":"0.001"}}],
+                "total":1}"#,
+            ),
+            "[]",
+        )
+        .await;
+        let products = savings.earn_products(EarnProductType::Locked).await.unwrap();
+        assert_eq!(products.len(), 1);
+        assert_eq!(products[0].product_id, "Axs*90");
+        assert_eq!(products[0].duration, Some(90));
+        assert_eq!(products[0].min_purchase_amount, 0.001);
+        assert!(products[0].can_purchase);
+    }
+
+    #[tokio::test]
+    async fn falls_back_to_lending() {
+        let savings = earn_server(
+            ("404 Not Found", r#"{"code":-1000,"msg":"This endpoint is not available."}"#),
+            r#"[{"annualInterestRate":"0.02600000","asset":"USDT","avgAnnualInterestRate":"0.02599895",
+            "canRedeem":true,"dailyInterestRate":"0.00007123","freeAmount":"75.46000000","freezeAmount":"0.00000000",
+            "lockedAmount":"0.00000000","productId":"USDT001","productName":"USDT","redeemingAmount":"0.00000000",
+            "todayPurchasedAmount":"0.00000000","totalAmount":"75.46000000","totalInterest":"0.22759183"}]"#,
+        )
+        .await;
+        let positions = savings
+            .earn_positions(EarnProductType::Flexible, Some("USDT".into()))
+            .await
+            .unwrap();
+        assert_eq!(positions.len(), 1);
+        assert_eq!(positions[0].product_id, "USDT001");
+        assert_eq!(positions[0].amount, 75.46);
+        assert_eq!(positions[0].annual_percentage_rate, Some(0.026));
+
+        // other errors are not hidden by the fallback
+        let savings = earn_server(("400 Bad Request", r#"{"code":-1102,"msg":"Mandatory parameter."}"#), "[]").await;
+        assert!(savings.earn_positions(EarnProductType::Flexible, None).await.is_err());
+    }
 }
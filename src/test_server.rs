@@ -0,0 +1,58 @@
+//! Local HTTP servers standing in for Binance in the tests.
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::task::JoinHandle;
+
+/// Answer each request with the next response, sending back the requests received
+pub(crate) async fn scripted_server<R: Into<String>>(responses: Vec<R>) -> (String, JoinHandle<Vec<String>>) {
+    let responses: Vec<String> = responses.into_iter().map(Into::into).collect();
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let host = format!("http://{}", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let mut requests = vec![];
+        for response in responses {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            requests.push(read_request(&mut stream).await);
+            stream.write_all(response.as_bytes()).await.unwrap();
+        }
+        requests
+    });
+    (host, server)
+}
+
+/// Answer every request with the response `respond` makes of it, for as long as the test runs
+pub(crate) async fn routed_server<F>(mut respond: F) -> String
+where
+    F: FnMut(&str) -> String + Send + 'static,
+{
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let host = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let request = read_request(&mut stream).await;
+            stream.write_all(respond(&request).as_bytes()).await.unwrap();
+        }
+    });
+    host
+}
+
+/// A response with `status`, e.g. "200 OK", and `body`
+pub(crate) fn response(status: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    )
+}
+
+/// A successful response with `body`
+pub(crate) fn ok(body: &str) -> String { response("200 OK", body) }
+
+async fn read_request(stream: &mut tokio::net::TcpStream) -> String {
+    let mut buf = vec![0; 4096];
+    let n = stream.read(&mut buf).await.unwrap();
+    String::from_utf8_lossy(&buf[..n]).to_string()
+}
@@ -0,0 +1,209 @@
+//! Optional instrumentation with `tracing`, behind the `tracing` feature. Every helper compiles to nothing
+//! without the feature.
+//!
+//! Secrets never reach the logs: query strings and error messages go through [`redact`] first, which hides
+//! the `signature`, `apiKey` and `listenKey` parameters, headers are never logged and websocket urls are
+//! reduced to their host since user data streams are named after their listen key.
+
+use std::future::Future;
+
+use reqwest::header::HeaderMap;
+use reqwest::{Method, StatusCode};
+use url::Url;
+
+use crate::ws_model::CloseReason;
+
+/// Span of a REST request
+#[cfg(feature = "tracing")]
+pub(crate) type RequestSpan = tracing::Span;
+#[cfg(not(feature = "tracing"))]
+pub(crate) struct RequestSpan;
+
+/// Start the span of a REST request, its parameters are logged at debug level
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+#[inline]
+pub(crate) fn request_span(method: &Method, url: &str) -> RequestSpan {
+    #[cfg(feature = "tracing")]
+    {
+        let (path, query) = url.split_once('?').unwrap_or((url, ""));
+        let path = Url::parse(path).map(|u| u.path().to_string()).unwrap_or_default();
+        let span = tracing::debug_span!(
+            "rest_request",
+            method = %method,
+            path = %path,
+            status = tracing::field::Empty,
+            used_weight = tracing::field::Empty,
+            latency_ms = tracing::field::Empty,
+        );
+        tracing::debug!(parent: &span, parameters = %redact(query), "sending request");
+        span
+    }
+    #[cfg(not(feature = "tracing"))]
+    RequestSpan
+}
+
+/// Run `request` in `span`, its latency is recorded when it completes
+#[cfg(feature = "tracing")]
+pub(crate) async fn in_request_span<F: Future>(span: RequestSpan, request: F) -> F::Output {
+    use tracing::Instrument;
+
+    let started = std::time::Instant::now();
+    let output = request.instrument(span.clone()).await;
+    span.record("latency_ms", started.elapsed().as_millis() as u64);
+    tracing::debug!(parent: &span, "request completed");
+    output
+}
+
+#[cfg(not(feature = "tracing"))]
+#[inline]
+pub(crate) fn in_request_span<F: Future>(_span: RequestSpan, request: F) -> F { request }
+
+/// Record the status and the used weight of a response in the span of its request
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+#[inline]
+pub(crate) fn record_response(status: StatusCode, headers: &HeaderMap) {
+    #[cfg(feature = "tracing")]
+    {
+        let span = tracing::Span::current();
+        span.record("status", status.as_u16());
+        let used_weight = headers
+            .iter()
+            .find(|(name, _)| name.as_str().starts_with("x-mbx-used-weight-"))
+            .and_then(|(_, value)| value.to_str().ok())
+            .and_then(|value| value.parse::<u32>().ok());
+        if let Some(used_weight) = used_weight {
+            span.record("used_weight", used_weight);
+        }
+    }
+}
+
+/// A REST request failed without a response
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+#[inline]
+pub(crate) fn request_failed(error: &crate::errors::Error) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(error = %redact(&error.to_string()), "request failed");
+}
+
+/// An order book depth limit Binance would reject was replaced
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+#[inline]
+pub(crate) fn depth_limit_replaced(requested: u16, limit: u16) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(requested, limit, "unsupported order book depth limit, using the nearest supported one");
+}
+
+/// A websocket message had an event type this crate does not know
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+#[inline]
+pub(crate) fn unknown_event(event_type: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::debug!(event_type, "unknown websocket event type");
+}
+
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+#[inline]
+pub(crate) fn ws_connected(url: &Url) {
+    #[cfg(feature = "tracing")]
+    tracing::info!(host = %ws_host(url), "websocket connected");
+}
+
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+#[inline]
+pub(crate) fn ws_closed(url: Option<&Url>, reason: &CloseReason) {
+    #[cfg(feature = "tracing")]
+    tracing::info!(
+        host = %url.map(ws_host).unwrap_or_default(),
+        code = reason.code,
+        reason = %redact(&reason.reason),
+        "websocket closed by the server"
+    );
+}
+
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+#[inline]
+pub(crate) fn ws_disconnected(url: Option<&Url>, reason: &str) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(host = %url.map(ws_host).unwrap_or_default(), reason = %redact(reason), "websocket disconnected");
+}
+
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+#[inline]
+pub(crate) fn ws_reconnected(url: &Url, attempt: u32) {
+    #[cfg(feature = "tracing")]
+    tracing::info!(host = %ws_host(url), attempt, "websocket reconnected");
+}
+
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+#[inline]
+pub(crate) fn ws_reconnect_failed(url: &Url, attempt: u32, error: &crate::errors::Error) {
+    #[cfg(feature = "tracing")]
+    tracing::warn!(host = %ws_host(url), attempt, error = %redact(&error.to_string()), "websocket reconnection failed");
+}
+
+/// Scheme, host and port of a websocket url, its path may hold a listen key
+#[cfg(feature = "tracing")]
+fn ws_host(url: &Url) -> String {
+    match url.port() {
+        Some(port) => format!("{}://{}:{}", url.scheme(), url.host_str().unwrap_or_default(), port),
+        None => format!("{}://{}", url.scheme(), url.host_str().unwrap_or_default()),
+    }
+}
+
+/// Parameters whose value is a secret
+#[cfg(feature = "tracing")]
+const SECRET_PARAMETERS: [&str; 3] = ["signature", "apiKey", "listenKey"];
+
+/// `text` with the values of the secret parameters replaced, in query strings as well as in the urls that
+/// error messages quote
+#[cfg(feature = "tracing")]
+pub(crate) fn redact(text: &str) -> String {
+    let mut redacted = text.to_string();
+    for parameter in SECRET_PARAMETERS {
+        let pattern = format!("{}=", parameter);
+        let mut from = 0;
+        while let Some(found) = redacted[from..].find(&pattern) {
+            let start = from + found + pattern.len();
+            // only whole parameter names, e.g. not `xsignature=`
+            let boundary = from + found == 0 || matches!(redacted.as_bytes()[from + found - 1], b'?' | b'&' | b' ');
+            let end = redacted[start..]
+                .find(['&', ')', ' ', '"', '\'', '#'])
+                .map_or(redacted.len(), |end| start + end);
+            if boundary {
+                redacted.replace_range(start..end, "<redacted>");
+                from = start + "<redacted>".len();
+            } else {
+                from = end;
+            }
+        }
+    }
+    redacted
+}
+
+#[cfg(all(test, feature = "tracing"))]
+mod test {
+    use super::*;
+
+    #[test]
+    fn redacts_secrets() {
+        assert_eq!(
+            redact("symbol=BTCUSDT&timestamp=1&signature=c8db56825ae71d6d79447849e617115f4a920fa2"),
+            "symbol=BTCUSDT&timestamp=1&signature=<redacted>"
+        );
+        assert_eq!(
+            redact("error sending request for url (http://h/api/v3/order?apiKey=key&signature=abc): timed out"),
+            "error sending request for url (http://h/api/v3/order?apiKey=<redacted>&signature=<redacted>): timed out"
+        );
+        assert_eq!(redact("listenKey=pqia91ma19a5s61cv6a81va65sdf19v8a65a1"), "listenKey=<redacted>");
+        // base64 signatures are url encoded
+        assert_eq!(redact("a=1&signature=hE8V7WJR%2FeC%3D%3D&b=2"), "a=1&signature=<redacted>&b=2");
+        assert_eq!(redact("xsignature=1&signature="), "xsignature=1&signature=<redacted>");
+        assert_eq!(redact("symbol=BTCUSDT"), "symbol=BTCUSDT");
+    }
+
+    #[test]
+    fn websocket_hosts() {
+        let url = Url::parse("wss://stream.binance.com:9443/ws/pqia91ma19a5s61cv6a81va65sdf19v8a65a1").unwrap();
+        assert_eq!(ws_host(&url), "wss://stream.binance.com:9443");
+    }
+}
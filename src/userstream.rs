@@ -1,4 +1,8 @@
-use serde_json::from_str;
+use std::sync::Arc;
+use std::time::Duration;
+
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
 
 use crate::client::*;
 use crate::errors::*;
@@ -6,6 +10,9 @@ use crate::rest_model::*;
 
 static USER_DATA_STREAM: &str = "/api/v3/userDataStream";
 
+/// Listen keys expire after 60mn without a keepalive
+static DEFAULT_KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+
 #[derive(Clone)]
 pub struct UserStream {
     pub client: Client,
@@ -23,8 +30,7 @@ impl UserStream {
     /// assert!(start.unwrap().listen_key.len() > 0)
     /// ```
     pub async fn start(&self) -> Result<UserDataStream> {
-        let data = self.client.post(USER_DATA_STREAM, None).await?;
-        let user_data_stream: UserDataStream = from_str(data.as_str())?;
+        let user_data_stream: UserDataStream = self.client.post_d(USER_DATA_STREAM, None).await?;
 
         Ok(user_data_stream)
     }
@@ -40,9 +46,7 @@ impl UserStream {
     /// assert!(keep_alive.is_ok())
     /// ```
     pub async fn keep_alive(&self, listen_key: &str) -> Result<Success> {
-        let data = self.client.put(USER_DATA_STREAM, listen_key, None).await?;
-
-        let success: Success = from_str(data.as_str())?;
+        let success: Success = self.client.put_d(USER_DATA_STREAM, listen_key, None).await?;
 
         Ok(success)
     }
@@ -58,10 +62,233 @@ impl UserStream {
     /// assert!(close.is_ok())
     /// ```
     pub async fn close(&self, listen_key: &str) -> Result<Success> {
-        let data = self.client.delete(USER_DATA_STREAM, listen_key, None).await?;
-
-        let success: Success = from_str(data.as_str())?;
+        let success: Success = self.client.delete_d(USER_DATA_STREAM, listen_key, None).await?;
 
         Ok(success)
     }
+
+    /// Endpoint of the spot listen keys, to start a [`ManagedListenKey`]
+    pub fn listen_key_endpoint(&self) -> ListenKeyEndpoint {
+        ListenKeyEndpoint::new(self.client.clone(), USER_DATA_STREAM, None)
+    }
+}
+
+/// Listen key endpoint of a user data stream, spot, margin and futures streams only differ by their endpoint
+#[derive(Clone)]
+pub struct ListenKeyEndpoint {
+    client: Client,
+    endpoint: &'static str,
+    /// Isolated margin listen keys are per symbol
+    symbol: Option<String>,
+}
+
+impl ListenKeyEndpoint {
+    pub(crate) fn new(client: Client, endpoint: &'static str, symbol: Option<String>) -> Self {
+        Self {
+            client,
+            endpoint,
+            symbol,
+        }
+    }
+
+    pub async fn start(&self) -> Result<UserDataStream> {
+        self.client.post_d(self.endpoint, self.symbol.as_deref()).await
+    }
+
+    pub async fn keep_alive(&self, listen_key: &str) -> Result<Success> {
+        self.client.put_d(self.endpoint, listen_key, self.symbol.as_deref()).await
+    }
+
+    pub async fn close(&self, listen_key: &str) -> Result<Success> {
+        self.client.delete_d(self.endpoint, listen_key, self.symbol.as_deref()).await
+    }
+}
+
+/// Schedule of the keepalive task of a [`ManagedListenKey`]
+#[derive(Debug, Clone)]
+pub struct KeepAliveOptions {
+    /// Time between two keepalives, default 30mn
+    pub interval: Duration,
+    /// Attempts made after a failed keepalive before reporting the failure, default 3
+    pub retries: u32,
+    /// Time between two attempts, default 10s
+    pub retry_delay: Duration,
+    /// Runtime running the keepalive task and the final close, the current runtime by default
+    pub runtime: Option<Handle>,
+}
+
+impl Default for KeepAliveOptions {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_KEEP_ALIVE_INTERVAL,
+            retries: 3,
+            retry_delay: Duration::from_secs(10),
+            runtime: None,
+        }
+    }
+}
+
+/// A listen key kept alive by a background task, and closed when dropped
+///
+/// Keepalives that still fail after the retries are reported to the failure callback, the task then
+/// carries on with the next interval. A failure usually means the key expired and the stream stopped
+/// sending events : the application should start a new key and reconnect.
+/// # Examples
+/// ```rust,no_run
+/// use binance::{api::*, userstream::*, config::*};
+/// let userstream: UserStream = Binance::new_with_env(&Config::testnet());
+/// let managed = tokio_test::block_on(ManagedListenKey::start(
+///     userstream.listen_key_endpoint(),
+///     KeepAliveOptions::default(),
+///     |e| eprintln!("listen key keepalive failed: {}", e),
+/// ))
+/// .unwrap();
+/// // connect the websocket with `managed.listen_key()`
+/// tokio_test::block_on(managed.close()).unwrap();
+/// ```
+pub struct ManagedListenKey {
+    endpoint: ListenKeyEndpoint,
+    listen_key: String,
+    task: JoinHandle<()>,
+    runtime: Option<Handle>,
+    closed: bool,
+}
+
+impl ManagedListenKey {
+    /// Start a listen key and spawn its keepalive task, on `options.runtime` or else on the current runtime
+    pub async fn start<F>(endpoint: ListenKeyEndpoint, options: KeepAliveOptions, on_failure: F) -> Result<Self>
+    where
+        F: Fn(&Error) + Send + Sync + 'static,
+    {
+        let listen_key = endpoint.start().await?.listen_key;
+        let runtime = options.runtime.clone();
+        let keep_alive = keep_alive_loop(endpoint.clone(), listen_key.clone(), options, Arc::new(on_failure));
+        let task = match &runtime {
+            Some(runtime) => runtime.spawn(keep_alive),
+            None => tokio::spawn(keep_alive),
+        };
+        Ok(Self {
+            endpoint,
+            listen_key,
+            task,
+            runtime,
+            closed: false,
+        })
+    }
+
+    pub fn listen_key(&self) -> &str { &self.listen_key }
+
+    /// Whether the keepalive task still runs, it only stops when the key is closed or its runtime shuts down
+    pub fn is_keeping_alive(&self) -> bool { !self.task.is_finished() }
+
+    /// Stop the keepalive task and invalidate the listen key
+    pub async fn close(mut self) -> Result<()> {
+        self.task.abort();
+        self.closed = true;
+        self.endpoint.close(&self.listen_key).await?;
+        Ok(())
+    }
+}
+
+impl Drop for ManagedListenKey {
+    fn drop(&mut self) {
+        self.task.abort();
+        if self.closed {
+            return;
+        }
+        // Without a runtime the key is left to expire
+        if let Some(handle) = self.runtime.clone().or_else(|| Handle::try_current().ok()) {
+            let endpoint = self.endpoint.clone();
+            let listen_key = std::mem::take(&mut self.listen_key);
+            handle.spawn(async move {
+                let _ = endpoint.close(&listen_key).await;
+            });
+        }
+    }
+}
+
+async fn keep_alive_loop(
+    endpoint: ListenKeyEndpoint,
+    listen_key: String,
+    options: KeepAliveOptions,
+    on_failure: Arc<dyn Fn(&Error) + Send + Sync>,
+) {
+    let start = tokio::time::Instant::now() + options.interval;
+    let mut interval = tokio::time::interval_at(start, options.interval);
+    loop {
+        interval.tick().await;
+        let mut attempts = 0;
+        while let Err(e) = endpoint.keep_alive(&listen_key).await {
+            if attempts >= options.retries {
+                on_failure(&e);
+                break;
+            }
+            attempts += 1;
+            tokio::time::sleep(options.retry_delay).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    use tokio::sync::mpsc;
+
+    use super::{KeepAliveOptions, ListenKeyEndpoint, ManagedListenKey};
+    use crate::client::Client;
+    use crate::test_server::{ok, response, routed_server};
+
+    #[tokio::test]
+    async fn keeps_alive_retries_and_closes() {
+        let (methods, mut received) = mpsc::unbounded_channel();
+        let host = routed_server(move |request| {
+            let method = request.split(' ').next().unwrap().to_string();
+            let answer = match method.as_str() {
+                "POST" => ok(r#"{"listenKey":"key"}"#),
+                "PUT" => response("400 Bad Request", r#"{"code":-1125,"msg":"This listenKey does not exist."}"#),
+                _ => ok("{}"),
+            };
+            methods.send(method).unwrap();
+            answer
+        })
+        .await;
+
+        let endpoint = ListenKeyEndpoint::new(
+            Client::new(Some("api_key".into()), None, host),
+            "/api/v3/userDataStream",
+            None,
+        );
+        let options = KeepAliveOptions {
+            interval: Duration::from_millis(50),
+            retries: 2,
+            retry_delay: Duration::from_millis(10),
+            runtime: Some(tokio::runtime::Handle::current()),
+        };
+        let failures = Arc::new(AtomicUsize::new(0));
+        let counter = failures.clone();
+        let managed = ManagedListenKey::start(endpoint, options, move |_| {
+            counter.fetch_add(1, Ordering::SeqCst);
+        })
+        .await
+        .unwrap();
+        assert_eq!(managed.listen_key(), "key");
+        assert!(managed.is_keeping_alive());
+        assert_eq!(received.recv().await.unwrap(), "POST");
+        // a first keepalive and its two retries
+        for _ in 0..3 {
+            assert_eq!(received.recv().await.unwrap(), "PUT");
+        }
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert_eq!(failures.load(Ordering::SeqCst), 1);
+
+        drop(managed);
+        loop {
+            if received.recv().await.unwrap() == "DELETE" {
+                break;
+            }
+        }
+    }
 }
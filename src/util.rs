@@ -1,21 +1,22 @@
 use std::collections::BTreeMap;
+use std::time::Duration;
 
-use chrono::Utc;
 use serde_json::Value;
 
+use crate::clock;
 use crate::errors::*;
 
+/// Query string of the parameters, their keys and values percent-encoded
 pub fn build_request(parameters: &BTreeMap<String, String>) -> String {
-    let mut request = String::new();
-    for (key, value) in parameters {
-        let param = format!("{}={}&", key, value);
-        request.push_str(param.as_ref());
-    }
-    request.pop(); // remove last &
-
-    request
+    url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(parameters)
+        .finish()
 }
 
+/// Query string of a request, its parameters in the order of the fields of the payload.
+///
+/// `None` fields are left out, strings are percent-encoded and floats are written in plain decimal notation,
+/// which Binance requires: `0.0000001` rather than `1e-7`.
 pub fn build_request_p<S>(payload: S) -> Result<String>
 where
     S: serde::Serialize,
@@ -31,53 +32,119 @@ pub fn build_signed_request(mut parameters: BTreeMap<String, String>, recv_windo
     if let Ok(timestamp) = get_timestamp() {
         parameters.insert("timestamp".into(), timestamp.to_string());
 
-        let mut request = String::new();
-        for (key, value) in &parameters {
-            let param = format!("{}={}&", key, value);
-            request.push_str(param.as_ref());
-        }
-        request.pop(); // remove last &
-
-        Ok(request)
+        Ok(build_request(&parameters))
     } else {
         Err(Error::Msg("Failed to get timestamp".to_string()))
     }
 }
 
+/// Query string of a signed request, see [`build_request_p`], followed by the receive window unless the payload
+/// sets its own, and by the timestamp
 pub fn build_signed_request_p<S>(payload: S, recv_window: u64) -> Result<String>
 where
     S: serde::Serialize,
 {
-    let query_string = qs::to_string(&payload)?;
-    let mut parameters: BTreeMap<String, String> = BTreeMap::new();
-
-    if recv_window > 0 {
-        parameters.insert("recvWindow".into(), recv_window.to_string());
+    let mut request = build_request_p(payload)?;
+    let timestamp = get_timestamp().map_err(|_| Error::Msg("Failed to get timestamp".to_string()))?;
+    if recv_window > 0 && !has_parameter(&request, "recvWindow") {
+        push_parameter(&mut request, "recvWindow", recv_window);
     }
+    push_parameter(&mut request, "timestamp", timestamp);
 
-    if let Ok(timestamp) = get_timestamp() {
-        parameters.insert("timestamp".into(), timestamp.to_string());
+    Ok(request)
+}
 
-        let mut request = query_string;
-        for (key, value) in &parameters {
-            let param = format!("&{}={}", key, value);
-            request.push_str(param.as_ref());
-        }
-        if let Some('&') = request.chars().last() {
-            request.pop(); // remove last &
-        }
+fn has_parameter(query: &str, name: &str) -> bool {
+    query
+        .split('&')
+        .any(|parameter| parameter.split('=').next() == Some(name))
+}
 
-        Ok(request)
-    } else {
-        Err(Error::Msg("Failed to get timestamp".to_string()))
+fn push_parameter(query: &mut String, name: &str, value: u64) {
+    if !query.is_empty() {
+        query.push('&');
     }
+    query.push_str(name);
+    query.push('=');
+    query.push_str(&value.to_string());
+}
+
+/// Serialize requests as the JSON array expected by batch endpoints, where every parameter is sent as a string
+/// and floats in plain decimal notation
+pub fn to_batch_json<S>(items: &[S]) -> Result<String>
+where
+    S: serde::Serialize,
+{
+    let items: Vec<BTreeMap<String, String>> = items
+        .iter()
+        .map(|item| match serde_json::to_value(item)? {
+            Value::Object(fields) => Ok(fields
+                .into_iter()
+                .filter(|(_, v)| !v.is_null())
+                .map(|(k, v)| match v {
+                    Value::String(s) => (k, s),
+                    // serde_json writes small and large floats with an exponent
+                    Value::Number(n) if n.is_f64() => (k, n.as_f64().unwrap_or_default().to_string()),
+                    v => (k, v.to_string()),
+                })
+                .collect()),
+            _ => Ok(BTreeMap::new()),
+        })
+        .collect::<Result<_>>()?;
+    Ok(serde_json::to_string(&items)?)
 }
 
 pub fn to_i64(v: &Value) -> i64 { v.as_i64().unwrap() }
 
 pub fn to_f64(v: &Value) -> f64 { v.as_str().unwrap().parse().unwrap() }
 
-pub fn get_timestamp() -> Result<u64> { Ok(Utc::now().timestamp_millis() as u64) }
+pub fn get_timestamp() -> Result<u64> { Ok(clock::now().duration_since(std::time::UNIX_EPOCH)?.as_millis() as u64) }
+
+/// Exponential backoff before the given attempt (starting at 0), with a random jitter of up to half of it
+pub(crate) fn backoff_delay(base_delay: Duration, max_delay: Duration, attempt: u32) -> Duration {
+    let delay = base_delay
+        .checked_mul(2_u32.saturating_pow(attempt))
+        .unwrap_or(max_delay)
+        .min(max_delay);
+    let nanos = clock::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or_default();
+    delay / 2 + (delay / 2).mul_f64(f64::from(nanos % 1000) / 1000.0)
+}
+
+/// Longest client order id accepted by Binance
+const MAX_CLIENT_ORDER_ID_LENGTH: usize = 36;
+
+/// Check a client order id against the format accepted by Binance, `^[\.A-Z\:/a-z0-9_-]{1,36}$`
+pub fn validate_client_order_id(id: &str) -> Result<()> {
+    let length = id.chars().count();
+    if length == 0 || length > MAX_CLIENT_ORDER_ID_LENGTH {
+        return Err(Error::InvalidOrderError {
+            msg: format!(
+                "client order id {:?} has {} characters, it must have 1 to {}",
+                id, length, MAX_CLIENT_ORDER_ID_LENGTH
+            ),
+        });
+    }
+    match id
+        .chars()
+        .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '.' | ':' | '/' | '_' | '-')))
+    {
+        Some(c) => Err(Error::InvalidOrderError {
+            msg: format!("client order id {:?} contains the forbidden character {:?}", id, c),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Check every client order id set on a request
+pub(crate) fn validate_client_order_ids<'a, I>(ids: I) -> Result<()>
+where
+    I: IntoIterator<Item = Option<&'a str>>,
+{
+    ids.into_iter().flatten().try_for_each(validate_client_order_id)
+}
 
 lazy_static! {
     static ref TRUE: String = "TRUE".to_string();
@@ -95,3 +162,98 @@ pub fn bool_to_string(b: bool) -> String {
 }
 
 pub fn bool_to_string_some(b: bool) -> Option<String> { Some(bool_to_string(b)) }
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn client_order_id_format() {
+        assert!(validate_client_order_id("a").is_ok());
+        assert!(validate_client_order_id(&"x".repeat(36)).is_ok());
+        assert!(validate_client_order_id("web_.:/-AZaz09").is_ok());
+        assert!(validate_client_order_id("0b7c8f0e-1c1b-4a8e-9f5c-9d4a7e3b2c1d").is_ok());
+
+        let too_long = validate_client_order_id(&"x".repeat(37)).unwrap_err();
+        assert!(too_long.to_string().contains("has 37 characters"), "{}", too_long);
+        assert!(validate_client_order_id("").is_err());
+        for id in ["{0b7c8f0e}", "my id", "id#1", "caf\u{e9}", "a+b"] {
+            let error = validate_client_order_id(id).unwrap_err();
+            assert!(error.to_string().contains("forbidden character"), "{}", error);
+        }
+
+        assert!(validate_client_order_ids([None, Some("ok")]).is_ok());
+        assert!(validate_client_order_ids([Some("ok"), Some("not ok")]).is_err());
+    }
+
+    #[derive(Serialize)]
+    #[serde(rename_all = "camelCase")]
+    struct Payload {
+        symbol: String,
+        price: Option<f64>,
+        quantity: f64,
+        new_client_order_id: Option<String>,
+        recv_window: Option<u64>,
+    }
+
+    fn payload(price: f64, quantity: f64) -> Payload {
+        Payload {
+            symbol: "SHIBUSDT".to_string(),
+            price: Some(price),
+            quantity,
+            new_client_order_id: None,
+            recv_window: None,
+        }
+    }
+
+    #[test]
+    fn floats_are_written_in_plain_decimal() {
+        assert_eq!(
+            build_request_p(payload(1e-7, 12_345_678_901_234.5)).unwrap(),
+            "symbol=SHIBUSDT&price=0.0000001&quantity=12345678901234.5"
+        );
+        assert_eq!(
+            build_request_p(payload(0.000012, 1e21)).unwrap(),
+            "symbol=SHIBUSDT&price=0.000012&quantity=1000000000000000000000"
+        );
+        let batch = to_batch_json(&[payload(1e-7, 1e21)]).unwrap();
+        assert!(batch.contains(r#""price":"0.0000001""#), "{}", batch);
+        assert!(batch.contains(r#""quantity":"1000000000000000000000""#), "{}", batch);
+    }
+
+    #[test]
+    fn strings_are_percent_encoded() {
+        let mut order = payload(1.5, 2.0);
+        order.symbol = "BTC USDT&x=1".to_string();
+        order.new_client_order_id = Some("a+b/c".to_string());
+        let query = build_request_p(order).unwrap();
+        let parameters: Vec<(String, String)> = url::form_urlencoded::parse(query.as_bytes()).into_owned().collect();
+        assert_eq!(parameters[0], ("symbol".to_string(), "BTC USDT&x=1".to_string()));
+        assert_eq!(parameters[3], ("newClientOrderId".to_string(), "a+b/c".to_string()));
+        assert_eq!(parameters.len(), 4);
+
+        let mut parameters = BTreeMap::new();
+        parameters.insert("symbol".to_string(), "BTC USDT&x=1".to_string());
+        parameters.insert("newClientOrderId".to_string(), "a+b/c".to_string());
+        let query = build_signed_request(parameters, 5000).unwrap();
+        let parameters: Vec<(String, String)> = url::form_urlencoded::parse(query.as_bytes()).into_owned().collect();
+        assert_eq!(parameters[0], ("newClientOrderId".to_string(), "a+b/c".to_string()));
+        assert_eq!(parameters[2], ("symbol".to_string(), "BTC USDT&x=1".to_string()));
+        assert_eq!(parameters.len(), 4);
+    }
+
+    #[test]
+    fn signed_requests_keep_the_receive_window_of_the_payload() {
+        let query = build_signed_request_p(payload(1.5, 2.0), 5000).unwrap();
+        assert!(query.starts_with("symbol=SHIBUSDT&price=1.5&quantity=2&recvWindow=5000&timestamp="), "{}", query);
+
+        let mut order = payload(1.5, 2.0);
+        order.recv_window = Some(1000);
+        let query = build_signed_request_p(order, 5000).unwrap();
+        assert_eq!(query.matches("recvWindow").count(), 1, "{}", query);
+        assert!(query.contains("recvWindow=1000&timestamp="), "{}", query);
+
+        let query = build_signed_request_p(None::<Payload>, 0).unwrap();
+        assert!(query.starts_with("timestamp="), "{}", query);
+    }
+}
@@ -1,18 +1,33 @@
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::collections::{BTreeSet, HashMap, VecDeque};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
-use futures::StreamExt;
-use serde_json::from_str;
+use futures::{SinkExt, Stream, StreamExt};
+use serde_json::{from_str, Value};
 use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot, Notify};
 use tokio_tungstenite::tungstenite::handshake::client::Response;
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
 use tokio_tungstenite::tungstenite::Message;
 use tokio_tungstenite::WebSocketStream;
-use tokio_tungstenite::{connect_async, MaybeTlsStream};
+use tokio_tungstenite::MaybeTlsStream;
 use url::Url;
 
 use crate::config::Config;
 use crate::errors::*;
+use crate::rest_model::KlineInterval;
+use crate::userstream::ListenKeyEndpoint;
+use crate::trace;
+use crate::util::backoff_delay;
+use crate::ws_model::{CloseReason, ConnectionEvent, ListenKeyExpiredEvent, TimeUnit};
+use crate::ws_transport;
 
 pub static STREAM_ENDPOINT: &str = "stream";
+/// Maximum number of streams of a single connection
+pub const MAX_STREAMS_PER_CONNECTION: usize = 1024;
+/// Longest url accepted for a combined stream connection
+pub const MAX_URL_LENGTH: usize = 8192;
 pub static WS_ENDPOINT: &str = "ws";
 pub static OUTBOUND_ACCOUNT_INFO: &str = "outboundAccountInfo";
 pub static OUTBOUND_ACCOUNT_POSITION: &str = "outboundAccountPosition";
@@ -22,6 +37,7 @@ pub static AGGREGATED_TRADE: &str = "aggTrade";
 pub static DEPTH_ORDERBOOK: &str = "depthUpdate";
 pub static PARTIAL_ORDERBOOK: &str = "lastUpdateId";
 pub static DAYTICKER: &str = "24hrTicker";
+pub static LISTEN_KEY_EXPIRED: &str = "listenKeyExpired";
 
 pub fn all_ticker_stream() -> &'static str {
     "!ticker@arr"
@@ -31,6 +47,41 @@ pub fn ticker_stream(symbol: &str) -> String {
     format!("{}@ticker", symbol)
 }
 
+/// Window of the rolling window ticker streams
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TickerWindow {
+    OneHour,
+    FourHours,
+    OneDay,
+}
+
+impl TickerWindow {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TickerWindow::OneHour => "1h",
+            TickerWindow::FourHours => "4h",
+            TickerWindow::OneDay => "1d",
+        }
+    }
+}
+
+/// Statistics over a rolling window, e.g. `btcusdt@ticker_1h`, received as
+/// [`crate::ws_model::WebsocketEvent::RollingWindowTicker`]
+pub fn rolling_window_ticker_stream(symbol: &str, window: TickerWindow) -> String {
+    format!("{}@ticker_{}", symbol, window.as_str())
+}
+
+/// Rolling window statistics of every symbol that changed, received as `Vec<WebsocketEvent>`
+pub fn all_rolling_window_ticker_stream(window: TickerWindow) -> String {
+    format!("!ticker_{}@arr", window.as_str())
+}
+
+/// Average price of the symbol, pushed with every change, received as
+/// [`crate::ws_model::WebsocketEvent::AveragePrice`]
+pub fn average_price_stream(symbol: &str) -> String {
+    format!("{}@avgPrice", symbol)
+}
+
 pub fn agg_trade_stream(symbol: &str) -> String {
     format!("{}@aggTrade", symbol)
 }
@@ -39,7 +90,8 @@ pub fn trade_stream(symbol: &str) -> String {
     format!("{}@trade", symbol)
 }
 
-pub fn kline_stream(symbol: &str, interval: &str) -> String {
+/// e.g. `btcusdt@kline_1m`
+pub fn kline_stream(symbol: &str, interval: KlineInterval) -> String {
     format!("{}@kline_{}", symbol, interval)
 }
 
@@ -51,39 +103,505 @@ pub fn all_book_ticker_stream() -> &'static str {
     "!bookTicker"
 }
 
+/// Mini tickers of every symbol that changed, once per second.
+///
+/// Each message is an array, received as a whole with `Vec<WebsocketEvent>` as the event type: one
+/// call of the handler per second with a consistent snapshot of the market.
 pub fn all_mini_ticker_stream() -> &'static str {
     "!miniTicker@arr"
 }
 
+/// Received as [`crate::ws_model::WebsocketEvent::DayMiniTicker`]
 pub fn mini_ticker_stream(symbol: &str) -> String {
     format!("{}@miniTicker", symbol)
 }
 
-/// # Arguments
-///
-/// * `symbol`: the market symbol
-/// * `levels`: 5, 10 or 20
-/// * `update_speed`: 1000 or 100
-pub fn partial_book_depth_stream(symbol: &str, levels: u16, update_speed: u16) -> String {
-    format!("{}@depth{}@{}ms", symbol, levels, update_speed)
+/// Number of levels of a partial book depth stream
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DepthLevels {
+    Five = 5,
+    Ten = 10,
+    Twenty = 20,
 }
 
-/// # Arguments
-///
-/// * `symbol`: the market symbol
-/// * `update_speed`: 1000 or 100
-pub fn diff_book_depth_stream(symbol: &str, update_speed: u16) -> String {
-    format!("{}@depth@{}ms", symbol, update_speed)
+/// Update speed of the book depth streams
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateSpeed {
+    Ms100 = 100,
+    Ms1000 = 1000,
+}
+
+/// Top `levels` bids and asks, e.g. `btcusdt@depth10@100ms`, parsed as
+/// [`crate::ws_model::WebsocketEventUntag::PartialDepthOrderBook`]
+pub fn partial_book_depth_stream(symbol: &str, levels: DepthLevels, update_speed: UpdateSpeed) -> String {
+    format!("{}@depth{}@{}ms", symbol, levels as u16, update_speed as u16)
+}
+
+pub fn diff_book_depth_stream(symbol: &str, update_speed: UpdateSpeed) -> String {
+    format!("{}@depth@{}ms", symbol, update_speed as u16)
+}
+
+/// The close code is 1005 when the server sent none
+fn close_reason(frame: Option<CloseFrame<'_>>) -> CloseReason {
+    match frame {
+        Some(frame) => CloseReason {
+            code: frame.code.into(),
+            reason: frame.reason.into_owned(),
+        },
+        None => CloseReason {
+            code: 1005,
+            reason: String::new(),
+        },
+    }
 }
 
 fn combined_stream(streams: Vec<String>) -> String {
     streams.join("/")
 }
 
+/// Resolves after `interval`, never without one
+async fn idle(interval: Option<Duration>) {
+    match interval {
+        Some(interval) => tokio::time::sleep(interval).await,
+        None => futures::future::pending().await,
+    }
+}
+
+/// Resolves at `instant`, never without one
+async fn deadline(instant: Option<tokio::time::Instant>) {
+    match instant {
+        Some(instant) => tokio::time::sleep_until(instant).await,
+        None => futures::future::pending().await,
+    }
+}
+
+/// Url of a combined stream connection, checking the limits Binance enforces on the handshake
+fn combined_stream_url(host: &str, streams: Vec<String>) -> Result<Url> {
+    if streams.is_empty() {
+        return Err(Error::Msg("At least one stream is required".to_string()));
+    }
+    if streams.len() > MAX_STREAMS_PER_CONNECTION {
+        return Err(Error::Msg(format!(
+            "{} streams requested, a connection is limited to {} streams",
+            streams.len(),
+            MAX_STREAMS_PER_CONNECTION
+        )));
+    }
+    let mut url = Url::parse(host)?;
+    url.path_segments_mut()
+        .map_err(|_| Error::UrlParserError(url::ParseError::RelativeUrlWithoutBase))?
+        .push(STREAM_ENDPOINT);
+    url.set_query(Some(&format!("streams={}", combined_stream(streams))));
+    if url.as_str().len() > MAX_URL_LENGTH {
+        return Err(Error::Msg(format!(
+            "The stream url is {} characters long, the limit is {}, split the streams over several connections",
+            url.as_str().len(),
+            MAX_URL_LENGTH
+        )));
+    }
+    Ok(url)
+}
+
+/// Slice in which reconnection delays are cut to notice a stop request
+const RECONNECT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// How long [`WebSockets::close`] waits for the server to acknowledge the Close frame
+const CLOSE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Detects connections which stay open but stop delivering messages, see [`WebSockets::set_watchdog`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Watchdog {
+    /// Longest time without any message, data or control, before the connection is considered stale
+    pub timeout: Duration,
+    /// Drop a stale connection and reconnect, rather than only notifying it
+    pub reconnect: bool,
+}
+
+impl Watchdog {
+    pub fn new(timeout: Duration) -> Self { Self { timeout, reconnect: true } }
+
+    /// For market data streams, which are never quiet for long on a traded symbol
+    pub fn market_data() -> Self { Self::new(Duration::from_secs(30)) }
+
+    /// For user data streams, which only carry the pings of the server while the account is idle. Futures
+    /// servers ping every 3 minutes.
+    pub fn user_data() -> Self { Self::new(Duration::from_secs(300)) }
+
+    pub fn set_reconnect(mut self, reconnect: bool) -> Self {
+        self.reconnect = reconnect;
+        self
+    }
+}
+
+/// Exponential backoff between reconnection attempts, see [`WebSockets::event_loop_with_reconnect`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReconnectPolicy {
+    /// Delay before the first attempt, doubled after every failed attempt
+    pub base_delay: Duration,
+    /// Ceiling of the delay between two attempts
+    pub max_delay: Duration,
+    /// Consecutive failed attempts before giving up, `None` retries forever
+    pub max_retries: Option<u32>,
+}
+
+impl Default for ReconnectPolicy {
+    fn default() -> Self {
+        Self {
+            base_delay: Duration::from_millis(250),
+            max_delay: Duration::from_secs(30),
+            max_retries: None,
+        }
+    }
+}
+
+impl ReconnectPolicy {
+    pub fn set_base_delay(mut self, base_delay: Duration) -> Self {
+        self.base_delay = base_delay;
+        self
+    }
+
+    pub fn set_max_delay(mut self, max_delay: Duration) -> Self {
+        self.max_delay = max_delay;
+        self
+    }
+
+    pub fn set_max_retries(mut self, max_retries: Option<u32>) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Delay before the given attempt (starting at 0), with a random jitter of up to half of it
+    pub fn delay(&self, attempt: u32) -> Duration { backoff_delay(self.base_delay, self.max_delay, attempt) }
+}
+
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum SubscriptionMethod {
+    Subscribe,
+    Unsubscribe,
+    ListSubscriptions,
+}
+
+#[derive(Serialize)]
+struct SubscriptionRequest<'s> {
+    method: SubscriptionMethod,
+    #[serde(skip_serializing_if = "<[String]>::is_empty")]
+    params: &'s [String],
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct SubscriptionResponse {
+    result: Option<Value>,
+    error: Option<BinanceContentError>,
+    id: u64,
+}
+
+struct SubscriptionCommand {
+    method: SubscriptionMethod,
+    streams: Vec<String>,
+    reply: oneshot::Sender<Result<Value>>,
+}
+
+enum Command {
+    Subscription(SubscriptionCommand),
+    /// Subscribe again to streams after a reconnection
+    Restore(Vec<String>),
+    /// Close the connection gracefully and end the event loop
+    Close(oneshot::Sender<Result<()>>),
+}
+
+struct PendingRequest {
+    method: SubscriptionMethod,
+    streams: Vec<String>,
+    /// `None` for the requests restoring the subscriptions after a reconnection
+    reply: Option<oneshot::Sender<Result<Value>>>,
+}
+
+/// Subscription requests awaiting their answer, and the changes made at runtime to the streams of the
+/// connection url
+#[derive(Default)]
+struct Subscriptions {
+    next_id: u64,
+    pending: HashMap<u64, PendingRequest>,
+    /// Streams subscribed at runtime
+    streams: BTreeSet<String>,
+    /// Streams of the connection url unsubscribed at runtime
+    removed: BTreeSet<String>,
+    /// Outcome of the restoration of the subscriptions, for the handler
+    notifications: Vec<ConnectionEvent>,
+}
+
+impl Subscriptions {
+    /// Record the answer to a request, returns the streams to restore one by one after a failed restoration
+    fn complete(&mut self, request: PendingRequest, response: SubscriptionResponse) -> Vec<String> {
+        let reply = match request.reply {
+            Some(reply) => reply,
+            None => return self.complete_restoration(request, response),
+        };
+        let result = match response.error {
+            Some(error) => Err(Error::BinanceError { response: error }),
+            None => {
+                match request.method {
+                    SubscriptionMethod::Subscribe => {
+                        for stream in request.streams {
+                            self.removed.remove(&stream);
+                            self.streams.insert(stream);
+                        }
+                    }
+                    SubscriptionMethod::Unsubscribe => {
+                        for stream in request.streams {
+                            if !self.streams.remove(&stream) {
+                                self.removed.insert(stream);
+                            }
+                        }
+                    }
+                    SubscriptionMethod::ListSubscriptions => {}
+                }
+                Ok(response.result.unwrap_or(Value::Null))
+            }
+        };
+        let _ = reply.send(result);
+        vec![]
+    }
+
+    /// A single stream that cannot be restored is reported and forgotten, a failed batch is retried stream
+    /// by stream to tell which ones failed
+    fn complete_restoration(&mut self, request: PendingRequest, response: SubscriptionResponse) -> Vec<String> {
+        if request.method != SubscriptionMethod::Subscribe {
+            return vec![];
+        }
+        match (response.error, request.streams.len()) {
+            (None, _) => {
+                self.notifications
+                    .push(ConnectionEvent::SubscriptionsRestored(request.streams));
+                vec![]
+            }
+            (Some(error), 1) => {
+                let stream = request.streams.into_iter().next().unwrap_or_default();
+                self.streams.remove(&stream);
+                self.notifications.push(ConnectionEvent::SubscriptionRestoreFailed {
+                    stream,
+                    error: error.to_string(),
+                });
+                vec![]
+            }
+            (Some(_), _) => request.streams,
+        }
+    }
+
+    /// Pending requests will never be answered once the connection is lost
+    fn fail_pending(&mut self) {
+        for (_, request) in self.pending.drain() {
+            if let Some(reply) = request.reply {
+                let _ = reply.send(Err(Error::Msg("The connection was lost before the answer".to_string())));
+            }
+        }
+    }
+}
+
+/// Manages the streams of a live connection, from any task.
+///
+/// Requests are sent and answered by the event loop of the connection, they wait for it to run.
+#[derive(Clone)]
+pub struct SubscriptionHandle {
+    commands: mpsc::UnboundedSender<Command>,
+}
+
+impl SubscriptionHandle {
+    /// Subscribe to streams, which are restored after a reconnection by
+    /// [`WebSockets::event_loop_with_reconnect`]
+    pub async fn subscribe(&self, streams: &[&str]) -> Result<()> {
+        self.request(SubscriptionMethod::Subscribe, streams).await?;
+        Ok(())
+    }
+
+    /// Unsubscribe from streams, including the ones of the connection url, which stay unsubscribed after a
+    /// reconnection
+    pub async fn unsubscribe(&self, streams: &[&str]) -> Result<()> {
+        self.request(SubscriptionMethod::Unsubscribe, streams).await?;
+        Ok(())
+    }
+
+    /// Streams of the connection, as known by the server
+    pub async fn list_subscriptions(&self) -> Result<Vec<String>> {
+        let streams = self.request(SubscriptionMethod::ListSubscriptions, &[]).await?;
+        Ok(serde_json::from_value(streams)?)
+    }
+
+    async fn request(&self, method: SubscriptionMethod, streams: &[&str]) -> Result<Value> {
+        let (reply, answer) = oneshot::channel();
+        self.commands
+            .send(Command::Subscription(SubscriptionCommand {
+                method,
+                streams: streams.iter().map(|s| s.to_string()).collect(),
+                reply,
+            }))
+            .map_err(|_| Error::Msg("The websocket was dropped".to_string()))?;
+        answer
+            .await
+            .map_err(|_| Error::Msg("The websocket was dropped before the answer".to_string()))?
+    }
+
+    /// Close the connection gracefully, see [`WebSockets::close`], the event loop then returns `Ok`
+    pub async fn close(&self) -> Result<()> {
+        let (reply, answer) = oneshot::channel();
+        self.commands
+            .send(Command::Close(reply))
+            .map_err(|_| Error::Msg("The websocket was dropped".to_string()))?;
+        answer
+            .await
+            .map_err(|_| Error::Msg("The websocket was dropped before closing".to_string()))?
+    }
+}
+
+/// What happens to the events received while the channel of [`WebSockets::new_with_channel`] is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drop the oldest event to make room, counted by [`EventReceiver::dropped_events`]
+    DropOldest,
+    /// Stop reading the socket until the receiver makes room. Pings are read from the same socket and
+    /// wait as well: Binance disconnects a receiver that stays behind for about a minute.
+    Backpressure,
+}
+
+/// Events shared between the handler of a websocket and its [`EventReceiver`]
+struct EventQueue<WE> {
+    events: Mutex<VecDeque<WE>>,
+    capacity: usize,
+    overflow: OverflowPolicy,
+    dropped: AtomicU64,
+    /// Notified when an event is pushed or the websocket is dropped
+    available: Notify,
+    /// Notified when an event is taken or the receiver is dropped
+    room: Notify,
+    sender_closed: AtomicBool,
+    receiver_closed: AtomicBool,
+}
+
+impl<WE> EventQueue<WE> {
+    fn push(&self, event: WE) -> Result<()> {
+        if self.receiver_closed.load(Ordering::Relaxed) {
+            return Err(Error::Msg("The event receiver was dropped".to_string()));
+        }
+        let mut events = self.events.lock().unwrap();
+        // Connection events come without reading the socket, backpressure lets them exceed the capacity
+        if self.overflow == OverflowPolicy::DropOldest && events.len() >= self.capacity {
+            events.pop_front();
+            self.dropped.fetch_add(1, Ordering::Relaxed);
+        }
+        events.push_back(event);
+        drop(events);
+        self.available.notify_one();
+        Ok(())
+    }
+
+    async fn wait_for_room(&self) {
+        if self.overflow != OverflowPolicy::Backpressure {
+            return;
+        }
+        loop {
+            let room = self.room.notified();
+            if self.receiver_closed.load(Ordering::Relaxed) || self.events.lock().unwrap().len() < self.capacity {
+                return;
+            }
+            room.await;
+        }
+    }
+}
+
+/// Handler of a websocket feeding an [`EventQueue`], closes the queue when the websocket is dropped
+struct EventSender<WE> {
+    queue: Arc<EventQueue<WE>>,
+}
+
+impl<WE> Drop for EventSender<WE> {
+    fn drop(&mut self) {
+        self.queue.sender_closed.store(true, Ordering::Relaxed);
+        self.queue.available.notify_one();
+    }
+}
+
+/// Receives the events of a websocket created with [`WebSockets::new_with_channel`]
+pub struct EventReceiver<WE> {
+    queue: Arc<EventQueue<WE>>,
+}
+
+impl<WE> EventReceiver<WE> {
+    /// Next event, `None` once the websocket is dropped and all its events were received
+    pub async fn recv(&self) -> Option<WE> {
+        loop {
+            let available = self.queue.available.notified();
+            if let Some(event) = self.try_recv() {
+                return Some(event);
+            }
+            if self.queue.sender_closed.load(Ordering::Relaxed) {
+                return None;
+            }
+            available.await;
+        }
+    }
+
+    /// Next event if one is waiting
+    pub fn try_recv(&self) -> Option<WE> {
+        let event = self.queue.events.lock().unwrap().pop_front();
+        if event.is_some() {
+            self.queue.room.notify_one();
+        }
+        event
+    }
+
+    /// Events waiting to be received
+    pub fn len(&self) -> usize { self.queue.events.lock().unwrap().len() }
+
+    pub fn is_empty(&self) -> bool { self.len() == 0 }
+
+    /// Events dropped so far because the channel was full, always 0 with [`OverflowPolicy::Backpressure`]
+    pub fn dropped_events(&self) -> u64 { self.queue.dropped.load(Ordering::Relaxed) }
+}
+
+impl<WE> Drop for EventReceiver<WE> {
+    fn drop(&mut self) {
+        self.queue.receiver_closed.store(true, Ordering::Relaxed);
+        self.queue.room.notify_one();
+    }
+}
+
+/// State of the stream of [`WebSockets::into_stream`], closes the connection when the stream is dropped
+struct EventStreamState<WE> {
+    events: EventReceiver<WE>,
+    event_loop: tokio::task::JoinHandle<Result<()>>,
+    commands: mpsc::UnboundedSender<Command>,
+    ended: bool,
+}
+
+impl<WE> Drop for EventStreamState<WE> {
+    fn drop(&mut self) {
+        // Nobody waits for the answer, the event loop closes the connection and ends
+        let (reply, _) = oneshot::channel();
+        let _ = self.commands.send(Command::Close(reply));
+    }
+}
+
 pub struct WebSockets<'a, WE> {
     pub socket: Option<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response)>,
     handler: Box<dyn FnMut(WE) -> Result<()> + 'a + Send>,
     conf: Config,
+    /// Url of the last connection, to reconnect to the same streams
+    url: Option<Url>,
+    command_sender: mpsc::UnboundedSender<Command>,
+    commands: mpsc::UnboundedReceiver<Command>,
+    subscriptions: Subscriptions,
+    /// Renews the listen key of a user stream when it expires
+    listen_key_endpoint: Option<ListenKeyEndpoint>,
+    expired_listen_key: Option<String>,
+    /// The connection was closed on purpose, the event loops stop instead of reporting a disconnection
+    closed: bool,
+    /// Channel fed by the handler, when created with [`Self::new_with_channel`]
+    queue: Option<Arc<EventQueue<WE>>>,
+    watchdog: Option<Watchdog>,
+    /// No message arrived within the timeout of the watchdog
+    stale: bool,
+    time_unit: TimeUnit,
 }
 
 impl<'a, WE: serde::de::DeserializeOwned> WebSockets<'a, WE> {
@@ -104,57 +622,164 @@ impl<'a, WE: serde::de::DeserializeOwned> WebSockets<'a, WE> {
     where
         Callback: FnMut(WE) -> Result<()> + 'a + Send,
     {
+        let (command_sender, commands) = mpsc::unbounded_channel();
         WebSockets {
             socket: None,
             handler: Box::new(handler),
             conf,
+            url: None,
+            command_sender,
+            commands,
+            subscriptions: Subscriptions::default(),
+            listen_key_endpoint: None,
+            expired_listen_key: None,
+            closed: false,
+            queue: None,
+            watchdog: None,
+            stale: false,
+            time_unit: TimeUnit::default(),
         }
     }
 
+    /// New websocket holder delivering its events to a channel of `capacity` events rather than to a
+    /// handler, so that the event loop can keep reading while the events are processed by another task.
+    ///
+    /// Run the event loop in its own task, it ends with an error once the receiver is dropped. With
+    /// [`OverflowPolicy::DropOldest`] the loop never waits, and
+    /// [`EventReceiver::dropped_events`] tells how far behind the receiver fell.
+    /// # Examples
+    /// ```rust,no_run
+    /// use std::sync::atomic::AtomicBool;
+    /// use binance::{config::*, websockets::*, ws_model::*};
+    /// tokio_test::block_on(async {
+    ///     let (mut web_socket, events) =
+    ///         WebSockets::<WebsocketEvent>::new_with_channel(1000, OverflowPolicy::DropOldest, Config::default());
+    ///     web_socket.connect("btcusdt@aggTrade").await.unwrap();
+    ///     tokio::spawn(async move {
+    ///         let running = AtomicBool::new(true);
+    ///         web_socket.event_loop_with_reconnect(&running, &ReconnectPolicy::default()).await
+    ///     });
+    ///     while let Some(event) = events.recv().await {
+    ///         println!("{:?}, {} dropped so far", event, events.dropped_events());
+    ///     }
+    /// });
+    /// ```
+    pub fn new_with_channel(
+        capacity: usize,
+        overflow: OverflowPolicy,
+        conf: Config,
+    ) -> (WebSockets<'a, WE>, EventReceiver<WE>)
+    where
+        WE: Send + 'a,
+    {
+        let queue = Arc::new(EventQueue {
+            events: Mutex::new(VecDeque::new()),
+            capacity: capacity.max(1),
+            overflow,
+            dropped: AtomicU64::new(0),
+            available: Notify::new(),
+            room: Notify::new(),
+            sender_closed: AtomicBool::new(false),
+            receiver_closed: AtomicBool::new(false),
+        });
+        let sender = EventSender { queue: queue.clone() };
+        let mut web_socket = Self::new_with_options(move |event: WE| sender.queue.push(event), conf);
+        web_socket.queue = Some(queue.clone());
+        (web_socket, EventReceiver { queue })
+    }
+
     /// Connect to multiple websocket endpoints
     /// N.B: WE has to be CombinedStreamEvent
     pub async fn connect_multiple(&mut self, endpoints: Vec<String>) -> Result<()> {
-        let mut url = Url::parse(&self.conf.ws_endpoint)?;
-        url.path_segments_mut()
-            .map_err(|_| Error::UrlParserError(url::ParseError::RelativeUrlWithoutBase))?
-            .push(STREAM_ENDPOINT);
-        url.set_query(Some(&format!("streams={}", combined_stream(endpoints))));
-
-        match connect_async(url).await {
-            Ok(answer) => {
-                self.socket = Some(answer);
-                Ok(())
-            }
-            Err(e) => Err(Error::Msg(format!("Error during handshake {}", e))),
-        }
+        let host = self.conf.ws_endpoint.clone();
+        self.connect_multiple_to(&host, endpoints).await
+    }
+
+    /// Connect to multiple futures websocket endpoints
+    /// N.B: WE has to be CombinedStreamEvent
+    pub async fn connect_multiple_futures(&mut self, endpoints: Vec<String>) -> Result<()> {
+        let host = self.conf.futures_ws_endpoint.clone();
+        self.connect_multiple_to(&host, endpoints).await
+    }
+
+    /// Connect to multiple COIN-M futures websocket endpoints
+    /// N.B: WE has to be CombinedStreamEvent
+    pub async fn connect_multiple_coin_futures(&mut self, endpoints: Vec<String>) -> Result<()> {
+        let host = self.conf.coin_futures_ws_endpoint.clone();
+        self.connect_multiple_to(&host, endpoints).await
+    }
+
+    /// Connect to multiple options websocket endpoints
+    /// N.B: WE has to be CombinedStreamEvent
+    pub async fn connect_multiple_options(&mut self, endpoints: Vec<String>) -> Result<()> {
+        let host = self.conf.options_ws_endpoint.clone();
+        self.connect_multiple_to(&host, endpoints).await
+    }
+
+    /// Connect to several streams over a single connection, e.g. `["btcusdt@depth", "btcusdt@trade"]`
+    /// N.B: WE has to be CombinedStreamEvent, which tells the stream each event comes from
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{websockets::*, ws_model::*};
+    /// let mut web_socket: WebSockets<'_, CombinedStreamEvent<WebsocketEvent>> =
+    ///     WebSockets::new(|event: CombinedStreamEvent<WebsocketEvent>| {
+    ///         println!("{}: {:?}", event.stream(), event.data);
+    ///         Ok(())
+    ///     });
+    /// tokio_test::block_on(web_socket.connect_multiple_streams(&["btcusdt@aggTrade", "ethusdt@aggTrade"])).unwrap();
+    /// ```
+    pub async fn connect_multiple_streams(&mut self, streams: &[&str]) -> Result<()> {
+        self.connect_multiple(streams.iter().map(|s| s.to_string()).collect()).await
+    }
+
+    async fn connect_multiple_to(&mut self, host: &str, endpoints: Vec<String>) -> Result<()> {
+        let url = combined_stream_url(host, endpoints)?;
+        self.connect_url(url).await
     }
 
     /// Connect to a websocket endpoint
+    ///
+    /// Events are sent without the name of their stream, which a `CombinedStreamEvent` still gets from the url
     pub async fn connect(&mut self, endpoint: &str) -> Result<()> {
-        let wss: String = format!("{}/{}/{}", self.conf.ws_endpoint, WS_ENDPOINT, endpoint);
-        let url = Url::parse(&wss)?;
-
-        match connect_async(url).await {
-            Ok(answer) => {
-                self.socket = Some(answer);
-                Ok(())
-            }
-            Err(e) => Err(Error::Msg(format!("Error during handshake {}", e))),
-        }
+        let host = self.conf.ws_endpoint.clone();
+        self.connect_to(&host, endpoint).await
     }
 
     /// Connect to a futures websocket endpoint
     pub async fn connect_futures(&mut self, endpoint: &str) -> Result<()> {
-        let wss: String = format!("{}/{}/{}", self.conf.futures_ws_endpoint, WS_ENDPOINT, endpoint);
+        let host = self.conf.futures_ws_endpoint.clone();
+        self.connect_to(&host, endpoint).await
+    }
+
+    /// Connect to a COIN-M futures websocket endpoint
+    pub async fn connect_coin_futures(&mut self, endpoint: &str) -> Result<()> {
+        let host = self.conf.coin_futures_ws_endpoint.clone();
+        self.connect_to(&host, endpoint).await
+    }
+
+    /// Connect to an options websocket endpoint
+    pub async fn connect_options(&mut self, endpoint: &str) -> Result<()> {
+        let host = self.conf.options_ws_endpoint.clone();
+        self.connect_to(&host, endpoint).await
+    }
+
+    async fn connect_to(&mut self, host: &str, endpoint: &str) -> Result<()> {
+        let wss: String = format!("{}/{}/{}", host, WS_ENDPOINT, endpoint);
         let url = Url::parse(&wss)?;
 
-        match connect_async(url).await {
-            Ok(answer) => {
-                self.socket = Some(answer);
-                Ok(())
-            }
-            Err(e) => Err(Error::Msg(format!("Error during handshake {}", e))),
+        self.connect_url(url).await
+    }
+
+    async fn connect_url(&mut self, mut url: Url) -> Result<()> {
+        if self.time_unit != TimeUnit::default() {
+            url.query_pairs_mut().append_pair("timeUnit", &self.time_unit.to_string());
         }
+        let answer = ws_transport::connect(&url, &self.conf).await?;
+        trace::ws_connected(&url);
+        self.socket = Some(answer);
+        self.url = Some(url);
+        self.closed = false;
+        Ok(())
     }
 
     /// Disconnect from the endpoint
@@ -167,30 +792,1193 @@ impl<'a, WE: serde::de::DeserializeOwned> WebSockets<'a, WE> {
         }
     }
 
+    /// Close the connection gracefully: send a Close frame and wait for the server to answer with its own,
+    /// for at most 5 seconds. The socket is dropped either way.
+    ///
+    /// Stopping an event loop through its `running` flag leaves the connection open, call this once it
+    /// returned, or [`SubscriptionHandle::close`] while it runs.
+    pub async fn close(&mut self) -> Result<()> {
+        self.closed = true;
+        let (mut socket, _) = match self.socket.take() {
+            Some(socket) => socket,
+            None => return Ok(()),
+        };
+        socket.close(None).await?;
+        // The answer of the server ends the stream
+        let acknowledged = tokio::time::timeout(CLOSE_TIMEOUT, async {
+            while let Some(Ok(_)) = socket.next().await {}
+        })
+        .await;
+        acknowledged.map_err(|_| Error::Msg("The server did not answer the Close frame in time".to_string()))
+    }
+
     pub fn socket(&self) -> &Option<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response)> {
         &self.socket
     }
 
+    /// Start a new listen key from `endpoint` when the one of the user stream expires.
+    ///
+    /// Only [`Self::event_loop_with_reconnect`] renews keys: on a `listenKeyExpired` event, which the handler
+    /// still receives, it starts a new key, reconnects with it, and notifies the handler with
+    /// [`ConnectionEvent::ListenKeyRenewed`].
+    /// # Examples
+    /// ```rust,no_run
+    /// use std::sync::atomic::AtomicBool;
+    /// use binance::{api::*, config::*, userstream::*, websockets::*, ws_model::*};
+    /// let user_stream: UserStream = Binance::new_with_env(&Config::testnet());
+    /// let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new(|event: WebsocketEvent| {
+    ///     if let WebsocketEvent::Connection(ConnectionEvent::ListenKeyRenewed(listen_key)) = event {
+    ///         println!("keep {} alive and resync the open orders", listen_key);
+    ///     }
+    ///     Ok(())
+    /// });
+    /// web_socket.set_listen_key_renewal(Some(user_stream.listen_key_endpoint()));
+    /// tokio_test::block_on(async move {
+    ///     let listen_key = user_stream.start().await?.listen_key;
+    ///     web_socket.connect(&listen_key).await?;
+    ///     let running = AtomicBool::new(true);
+    ///     web_socket.event_loop_with_reconnect(&running, &ReconnectPolicy::default()).await
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn set_listen_key_renewal(&mut self, endpoint: Option<ListenKeyEndpoint>) {
+        self.listen_key_endpoint = endpoint;
+    }
+
+    /// Watch this connection for silence, disabled by default.
+    ///
+    /// When no message arrives within the timeout, [`Self::event_loop_with_reconnect`] notifies the handler
+    /// with [`ConnectionEvent::Stale`], again after every timeout while the silence lasts, and reconnects if
+    /// [`Watchdog::reconnect`] is set. [`Self::event_loop`] ends with [`Error::WebsocketStale`].
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{websockets::*, ws_model::*};
+    /// let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new(|event: WebsocketEvent| {
+    ///     if let WebsocketEvent::Connection(ConnectionEvent::Stale(silence)) = event {
+    ///         println!("nothing received for {:?}, reconnecting", silence);
+    ///     }
+    ///     Ok(())
+    /// });
+    /// web_socket.set_watchdog(Some(Watchdog::market_data()));
+    /// ```
+    pub fn set_watchdog(&mut self, watchdog: Option<Watchdog>) { self.watchdog = watchdog; }
+
+    /// Unit of the event and transaction times of the next connections, milliseconds by default.
+    ///
+    /// Only the spot streams support microseconds, the time fields of the events then hold microseconds,
+    /// see [`TimeUnit::to_millis`] to compare them with the times of the REST API.
+    /// # Examples
+    /// ```rust,no_run
+    /// use binance::{websockets::*, ws_model::*};
+    /// let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new(|event: WebsocketEvent| {
+    ///     if let WebsocketEvent::Trade(trade) = event {
+    ///         println!("traded at {:?}", TimeUnit::Microsecond.to_system_time(trade.trade_order_time));
+    ///     }
+    ///     Ok(())
+    /// });
+    /// web_socket.set_time_unit(TimeUnit::Microsecond);
+    /// ```
+    pub fn set_time_unit(&mut self, time_unit: TimeUnit) { self.time_unit = time_unit; }
+
+    /// Handle to subscribe to and unsubscribe from streams while the event loop runs
+    /// # Examples
+    /// ```rust,no_run
+    /// use std::sync::atomic::AtomicBool;
+    /// use binance::{websockets::*, ws_model::*};
+    /// let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new(|event: WebsocketEvent| {
+    ///     println!("{:?}", event);
+    ///     Ok(())
+    /// });
+    /// let subscriptions = web_socket.subscription_handle();
+    /// tokio_test::block_on(async move {
+    ///     web_socket.connect("btcusdt@aggTrade").await.unwrap();
+    ///     tokio::spawn(async move {
+    ///         subscriptions.subscribe(&["ethusdt@aggTrade"]).await.unwrap();
+    ///         println!("{:?}", subscriptions.list_subscriptions().await);
+    ///     });
+    ///     let running = AtomicBool::new(true);
+    ///     web_socket.event_loop(&running).await
+    /// })
+    /// .unwrap();
+    /// ```
+    pub fn subscription_handle(&self) -> SubscriptionHandle {
+        SubscriptionHandle {
+            commands: self.command_sender.clone(),
+        }
+    }
+
+    /// Read the socket and hand every event to the handler until `running` is false.
+    ///
+    /// Pings are answered as soon as they are read, but nothing is read while the handler runs: Binance
+    /// disconnects peers that do not answer its pings in time, so long work belongs to another task.
     pub async fn event_loop(&mut self, running: &AtomicBool) -> Result<()> {
+        let result = self.run_event_loop(running).await;
+        self.subscriptions.fail_pending();
+        // Listen keys are only renewed by the reconnecting loop
+        self.expired_listen_key = None;
+        result
+    }
+
+    async fn run_event_loop(&mut self, running: &AtomicBool) -> Result<()> {
         while running.load(Ordering::Relaxed) {
-            if let Some((ref mut socket, _)) = self.socket {
-                let message = socket.next().await.unwrap()?;
+            if self.socket.is_some() {
+                self.wait_for_room().await;
+                let message = match self.next_message().await {
+                    Some(message) => message?,
+                    None if self.closed => return Ok(()),
+                    None if self.stale => {
+                        let timeout = self.watchdog.map(|w| w.timeout).unwrap_or_default();
+                        return Err(Error::WebsocketStale(timeout));
+                    }
+                    None => return Err(Error::Msg("Disconnected".to_string())),
+                };
 
                 match message {
                     Message::Text(msg) => {
                         if msg.is_empty() {
                             return Ok(());
                         }
-                        let event: WE = from_str(msg.as_str())?;
-                        (self.handler)(event)?;
+                        self.handle_text(msg)?;
                     }
                     Message::Ping(_) | Message::Pong(_) | Message::Binary(_) => {}
-                    Message::Close(e) => {
-                        return Err(Error::Msg(format!("Disconnected {:?}", e)));
+                    Message::Close(frame) => {
+                        return Err(Error::WebsocketClosed(close_reason(frame)));
                     }
                 }
             }
         }
         Ok(())
     }
+
+    /// Backpressure of the channel of [`Self::new_with_channel`], the future does not borrow `self` which is
+    /// not `Sync`
+    fn wait_for_room(&self) -> impl std::future::Future<Output = ()> {
+        let queue = self.queue.clone();
+        async move {
+            if let Some(queue) = queue {
+                queue.wait_for_room().await;
+            }
+        }
+    }
+
+    /// Next message of the socket, sending the subscription requests received in the meantime.
+    ///
+    /// Pings are answered before being returned, and an unsolicited pong is sent after
+    /// [`Config::ws_keepalive_interval`] without any message from the server. `None` is returned when the
+    /// connection is closed, on purpose or not, or found stale by the watchdog.
+    async fn next_message(&mut self) -> Option<tokio_tungstenite::tungstenite::Result<Message>> {
+        self.stale = false;
+        let stale_at = self.watchdog.map(|w| tokio::time::Instant::now() + w.timeout);
+        loop {
+            let keepalive = self.conf.ws_keepalive_interval;
+            let (socket, _) = self.socket.as_mut()?;
+            let command = tokio::select! {
+                message = socket.next() => {
+                    if let Some(Ok(Message::Ping(_))) = message {
+                        // tungstenite queues the pong, it would only be written by the next read or write
+                        if let Err(e) = socket.flush().await {
+                            return Some(Err(e));
+                        }
+                    }
+                    return message;
+                }
+                Some(command) = self.commands.recv() => command,
+                _ = idle(keepalive) => {
+                    if let Err(e) = socket.send(Message::Pong(vec![])).await {
+                        return Some(Err(e));
+                    }
+                    continue;
+                }
+                _ = deadline(stale_at) => {
+                    self.stale = true;
+                    return None;
+                }
+            };
+            match command {
+                Command::Subscription(command) => {
+                    self.send_request(command.method, command.streams, Some(command.reply))
+                        .await
+                }
+                Command::Restore(streams) => self.send_request(SubscriptionMethod::Subscribe, streams, None).await,
+                Command::Close(reply) => {
+                    let _ = reply.send(self.close().await);
+                    return None;
+                }
+            }
+        }
+    }
+
+    async fn send_request(
+        &mut self,
+        method: SubscriptionMethod,
+        streams: Vec<String>,
+        reply: Option<oneshot::Sender<Result<Value>>>,
+    ) {
+        let id = self.subscriptions.next_id;
+        self.subscriptions.next_id += 1;
+        let request = SubscriptionRequest {
+            method,
+            params: &streams,
+            id,
+        };
+        let sent = match (serde_json::to_string(&request), self.socket.as_mut()) {
+            (Ok(frame), Some((socket, _))) => socket.send(Message::Text(frame)).await.map_err(Error::from),
+            (Err(e), _) => Err(e.into()),
+            (_, None) => Err(Error::Msg("Not connected".to_string())),
+        };
+        match sent {
+            Ok(()) => {
+                self.subscriptions
+                    .pending
+                    .insert(id, PendingRequest { method, streams, reply });
+            }
+            Err(e) => {
+                if let Some(reply) = reply {
+                    let _ = reply.send(Err(e));
+                }
+            }
+        }
+    }
+
+    /// Answers subscription requests, and hands everything else to the handler
+    fn handle_text(&mut self, msg: String) -> Result<()> {
+        if !self.subscriptions.pending.is_empty() {
+            if let Ok(response) = from_str::<SubscriptionResponse>(msg.as_str()) {
+                if let Some(request) = self.subscriptions.pending.remove(&response.id) {
+                    for stream in self.subscriptions.complete(request, response) {
+                        let _ = self.command_sender.send(Command::Restore(vec![stream]));
+                    }
+                    return Ok(());
+                }
+            }
+        }
+        if self.listen_key_endpoint.is_some() && msg.contains(LISTEN_KEY_EXPIRED) {
+            if let Ok(expired) = from_str::<ListenKeyExpiredEvent>(msg.as_str()) {
+                self.expired_listen_key = Some(expired.listen_key);
+            }
+        }
+        let event: WE = match from_str(msg.as_str()) {
+            Ok(event) => event,
+            // A CombinedStreamEvent on a raw stream, whose name is only known from the url
+            Err(e) => match self.raw_stream() {
+                Some(stream) if !msg.starts_with("{\"stream\"") => {
+                    let envelope = format!("{{\"stream\":{},\"data\":{}}}", Value::from(stream), msg);
+                    from_str(envelope.as_str()).map_err(|_| e)?
+                }
+                _ => return Err(e.into()),
+            },
+        };
+        (self.handler)(event)
+    }
+
+    /// Name of the stream of a raw `/ws/<stream>` connection
+    fn raw_stream(&self) -> Option<&str> {
+        let mut segments = self.url.as_ref()?.path_segments()?.rev();
+        let stream = segments.next()?;
+        match segments.next() {
+            Some(endpoint) if endpoint == WS_ENDPOINT && !stream.is_empty() => Some(stream),
+            _ => None,
+        }
+    }
+
+    /// Same as [`Self::event_loop`], but reconnects to the same streams whenever the connection is lost,
+    /// waiting between attempts according to `policy`.
+    ///
+    /// Every disconnection is notified to the handler with [`ConnectionEvent::Disconnected`], and every
+    /// reconnection with [`ConnectionEvent::Connected`]: events sent in between are lost, so this is the
+    /// time to resync state over REST. The changes made with a [`SubscriptionHandle`] are restored after
+    /// reconnecting: the streams of the url unsubscribed at runtime are unsubscribed again, and the streams
+    /// subscribed at runtime are subscribed again, which is notified with
+    /// [`ConnectionEvent::SubscriptionsRestored`], or with [`ConnectionEvent::SubscriptionRestoreFailed`] for
+    /// each stream Binance refused. When the server closes the connection, its close code and reason are
+    /// notified first with [`ConnectionEvent::Closed`].
+    ///
+    /// Errors returned by the handler or while parsing events still end the loop, as does reaching
+    /// `policy.max_retries` consecutive failed attempts. Closing with [`SubscriptionHandle::close`] ends it
+    /// without error.
+    /// # Examples
+    /// see examples/binance_websockets.rs
+    pub async fn event_loop_with_reconnect(&mut self, running: &AtomicBool, policy: &ReconnectPolicy) -> Result<()>
+    where
+        WE: From<ConnectionEvent>,
+    {
+        let mut url = self
+            .url
+            .clone()
+            .ok_or_else(|| Error::Msg("Connect before starting the event loop".to_string()))?;
+        let result = self.run_event_loop_with_reconnect(&mut url, running, policy).await;
+        self.subscriptions.fail_pending();
+        result
+    }
+
+    async fn run_event_loop_with_reconnect(
+        &mut self,
+        url: &mut Url,
+        running: &AtomicBool,
+        policy: &ReconnectPolicy,
+    ) -> Result<()>
+    where
+        WE: From<ConnectionEvent>,
+    {
+        while running.load(Ordering::Relaxed) {
+            if self.socket.is_none() {
+                self.reconnect(url, running, policy).await?;
+                continue;
+            }
+            self.wait_for_room().await;
+            let reason = match self.next_message().await {
+                Some(Ok(Message::Text(msg))) => {
+                    if !msg.is_empty() {
+                        self.handle_text(msg)?;
+                    }
+                    for notification in std::mem::take(&mut self.subscriptions.notifications) {
+                        (self.handler)(notification.into())?;
+                    }
+                    if self.expired_listen_key.is_none() {
+                        continue;
+                    }
+                    "The listen key expired".to_string()
+                }
+                Some(Ok(Message::Close(frame))) => {
+                    let reason = close_reason(frame);
+                    trace::ws_closed(self.url.as_ref(), &reason);
+                    self.socket = None;
+                    (self.handler)(ConnectionEvent::Closed(reason.clone()).into())?;
+                    format!("Disconnected {}", reason)
+                }
+                Some(Ok(_)) => continue,
+                Some(Err(e)) => e.to_string(),
+                None if self.closed => return Ok(()),
+                None if self.stale => {
+                    let watchdog = self.watchdog.unwrap_or_else(Watchdog::market_data);
+                    (self.handler)(ConnectionEvent::Stale(watchdog.timeout).into())?;
+                    if !watchdog.reconnect {
+                        continue;
+                    }
+                    format!("No message received for {:?}", watchdog.timeout)
+                }
+                None => "Connection closed".to_string(),
+            };
+            trace::ws_disconnected(self.url.as_ref(), &reason);
+            self.socket = None;
+            self.subscriptions.fail_pending();
+            (self.handler)(ConnectionEvent::Disconnected(reason).into())?;
+        }
+        Ok(())
+    }
+
+    async fn reconnect(&mut self, url: &mut Url, running: &AtomicBool, policy: &ReconnectPolicy) -> Result<()>
+    where
+        WE: From<ConnectionEvent>,
+    {
+        let mut attempt = 0;
+        loop {
+            let mut delay = policy.delay(attempt);
+            while !delay.is_zero() {
+                if !running.load(Ordering::Relaxed) {
+                    return Ok(());
+                }
+                let step = delay.min(RECONNECT_POLL_INTERVAL);
+                tokio::time::sleep(step).await;
+                delay -= step;
+            }
+            let connected = match self.renew_listen_key(url).await {
+                Ok(renewed) => ws_transport::connect(url, &self.conf)
+                    .await
+                    .map(|answer| (answer, renewed)),
+                Err(e) => Err(e),
+            };
+            match connected {
+                Ok((answer, renewed)) => {
+                    trace::ws_reconnected(url, attempt + 1);
+                    self.socket = Some(answer);
+                    // The connection url brings back the streams it was opened with
+                    if !self.subscriptions.removed.is_empty() {
+                        let streams = self.subscriptions.removed.iter().cloned().collect();
+                        self.send_request(SubscriptionMethod::Unsubscribe, streams, None).await;
+                    }
+                    if !self.subscriptions.streams.is_empty() {
+                        let streams = self.subscriptions.streams.iter().cloned().collect();
+                        self.send_request(SubscriptionMethod::Subscribe, streams, None).await;
+                    }
+                    (self.handler)(ConnectionEvent::Connected.into())?;
+                    return match renewed {
+                        Some(listen_key) => (self.handler)(ConnectionEvent::ListenKeyRenewed(listen_key).into()),
+                        None => Ok(()),
+                    };
+                }
+                Err(e) => {
+                    attempt += 1;
+                    trace::ws_reconnect_failed(url, attempt, &e);
+                    if policy.max_retries.is_some_and(|max_retries| attempt >= max_retries) {
+                        return Err(Error::Msg(format!("Could not reconnect after {} attempts: {}", attempt, e)));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Start a new listen key if the current one expired, and put it in place of the old one in `url`
+    async fn renew_listen_key(&mut self, url: &mut Url) -> Result<Option<String>> {
+        let (endpoint, expired) = match (&self.listen_key_endpoint, &self.expired_listen_key) {
+            (Some(endpoint), Some(expired)) => (endpoint, expired),
+            _ => return Ok(None),
+        };
+        let listen_key = endpoint.start().await?.listen_key;
+        *url = Url::parse(&url.as_str().replace(expired.as_str(), &listen_key))?;
+        self.url = Some(url.clone());
+        self.expired_listen_key = None;
+        Ok(Some(listen_key))
+    }
+}
+
+impl<WE: serde::de::DeserializeOwned + Send + 'static> WebSockets<'static, WE> {
+    /// Run the event loop in a task of its own and receive the events as a stream, rather than from `events`.
+    ///
+    /// `events` has to be the receiver created with this websocket by [`Self::new_with_channel`]. The
+    /// stream ends with the error that ended the event loop, if any. Dropping the stream closes the
+    /// connection gracefully and ends the event loop.
+    /// # Examples
+    /// ```rust,no_run
+    /// use futures::StreamExt;
+    /// use binance::{config::*, websockets::*, ws_model::*};
+    /// tokio_test::block_on(async {
+    ///     let (mut web_socket, events) =
+    ///         WebSockets::<WebsocketEvent>::new_with_channel(1000, OverflowPolicy::Backpressure, Config::default());
+    ///     web_socket.connect("btcusdt@aggTrade").await.unwrap();
+    ///     let mut stream = web_socket.into_stream(events).unwrap();
+    ///     while let Some(event) = stream.next().await {
+    ///         println!("{:?}", event);
+    ///     }
+    /// });
+    /// ```
+    pub fn into_stream(self, events: EventReceiver<WE>) -> Result<impl Stream<Item = Result<WE>> + Send + Unpin> {
+        self.spawn_stream(events, |mut web_socket| async move {
+            let running = AtomicBool::new(true);
+            web_socket.event_loop(&running).await
+        })
+    }
+
+    /// Same as [`Self::into_stream`], with the event loop of [`Self::event_loop_with_reconnect`]
+    pub fn into_reconnecting_stream(
+        self,
+        events: EventReceiver<WE>,
+        policy: ReconnectPolicy,
+    ) -> Result<impl Stream<Item = Result<WE>> + Send + Unpin>
+    where
+        WE: From<ConnectionEvent>,
+    {
+        self.spawn_stream(events, |mut web_socket| async move {
+            let running = AtomicBool::new(true);
+            web_socket.event_loop_with_reconnect(&running, &policy).await
+        })
+    }
+
+    fn spawn_stream<F, Fut>(
+        self,
+        events: EventReceiver<WE>,
+        event_loop: F,
+    ) -> Result<impl Stream<Item = Result<WE>> + Send + Unpin>
+    where
+        F: FnOnce(Self) -> Fut,
+        Fut: std::future::Future<Output = Result<()>> + Send + 'static,
+    {
+        if !self.queue.as_ref().is_some_and(|queue| Arc::ptr_eq(queue, &events.queue)) {
+            return Err(Error::Msg(
+                "The events have to be received from the channel of this websocket".to_string(),
+            ));
+        }
+        let state = EventStreamState {
+            events,
+            commands: self.command_sender.clone(),
+            event_loop: tokio::spawn(event_loop(self)),
+            ended: false,
+        };
+        Ok(Box::pin(futures::stream::unfold(state, |mut state| async move {
+            if state.ended {
+                return None;
+            }
+            if let Some(event) = state.events.recv().await {
+                return Some((Ok(event), state));
+            }
+            // The websocket, and with it the sender of the events, was dropped as the event loop ended
+            state.ended = true;
+            match (&mut state.event_loop).await {
+                Ok(Ok(())) => None,
+                Ok(Err(e)) => Some((Err(e), state)),
+                Err(e) => Some((Err(Error::Msg(format!("The event loop failed: {}", e))), state)),
+            }
+        })))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use futures::{SinkExt, StreamExt};
+    use serde_json::{json, Value};
+    use tokio::net::TcpListener;
+    use tokio::sync::mpsc;
+    use tokio_tungstenite::tungstenite::handshake::server::Request;
+    use tokio_tungstenite::tungstenite::protocol::frame::coding::CloseCode;
+    use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+    use tokio_tungstenite::tungstenite::Message;
+    use tokio_tungstenite::{accept_async, accept_hdr_async};
+
+    use super::{combined_stream_url, OverflowPolicy, ReconnectPolicy, Watchdog, WebSockets,
+                MAX_STREAMS_PER_CONNECTION};
+    use crate::client::Client;
+    use crate::config::Config;
+    use crate::test_server::{ok, scripted_server};
+    use crate::errors::Error;
+    use crate::userstream::ListenKeyEndpoint;
+    use crate::ws_model::{CombinedStreamEvent, ConnectionEvent, TimeUnit, WebsocketEvent, WebsocketEventUntag};
+
+    static BALANCE_UPDATE: &str = r#"{"e":"balanceUpdate","E":1,"a":"BTC","d":"1.0","T":1}"#;
+    static LISTEN_KEY_EXPIRED_EVENT: &str = r#"{"e":"listenKeyExpired","E":1,"listenKey":"expired"}"#;
+
+    #[test]
+    fn reconnect_delay_grows_up_to_the_ceiling() {
+        let policy = ReconnectPolicy::default()
+            .set_base_delay(Duration::from_millis(100))
+            .set_max_delay(Duration::from_secs(1));
+        let first = policy.delay(0);
+        assert!(first >= Duration::from_millis(50) && first <= Duration::from_millis(100));
+        let third = policy.delay(2);
+        assert!(third >= Duration::from_millis(200) && third <= Duration::from_millis(400));
+        assert!(policy.delay(40) <= Duration::from_secs(1));
+    }
+
+    #[test]
+    fn combined_stream_limits() {
+        let url = combined_stream_url("wss://stream.binance.com", vec!["a@trade".into(), "b@trade".into()]).unwrap();
+        assert_eq!(url.as_str(), "wss://stream.binance.com/stream?streams=a@trade/b@trade");
+        assert!(combined_stream_url("wss://stream.binance.com", vec![]).is_err());
+        let too_many = (0..=MAX_STREAMS_PER_CONNECTION).map(|i| format!("s{}@trade", i)).collect();
+        assert!(combined_stream_url("wss://stream.binance.com", too_many).is_err());
+        let too_long = (0..1000).map(|i| format!("symbol{}@depth20@100ms", i)).collect();
+        assert!(combined_stream_url("wss://stream.binance.com", too_long).is_err());
+    }
+
+    #[tokio::test]
+    async fn names_the_stream_of_raw_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            let depth = r#"{"lastUpdateId":1,"bids":[["1.0","2.0"]],"asks":[]}"#;
+            ws.send(Message::Text(depth.to_string())).await.unwrap();
+            ws.close(None).await.unwrap();
+        });
+
+        let events = Arc::new(Mutex::new(vec![]));
+        let received = events.clone();
+        let mut web_socket: WebSockets<'_, CombinedStreamEvent<WebsocketEventUntag>> = WebSockets::new_with_options(
+            |event: CombinedStreamEvent<WebsocketEventUntag>| {
+                received.lock().unwrap().push(event);
+                Ok(())
+            },
+            Config::default().set_ws_endpoint(host),
+        );
+        web_socket.connect("btcusdt@depth5").await.unwrap();
+        let _ = web_socket.event_loop(&AtomicBool::new(true)).await;
+
+        let events = events.lock().unwrap();
+        assert_eq!(events[0].stream(), "btcusdt@depth5");
+        assert!(
+            matches!(&events[0].data, WebsocketEventUntag::PartialDepthOrderBook(depth) if depth.symbol == "BTCUSDT")
+        );
+    }
+
+    #[tokio::test]
+    async fn reconnects_after_server_close() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(stream).await.unwrap();
+                ws.send(Message::Text(BALANCE_UPDATE.to_string())).await.unwrap();
+                ws.close(None).await.unwrap();
+            }
+        });
+
+        let running = AtomicBool::new(true);
+        let events = Arc::new(Mutex::new(vec![]));
+        let received = events.clone();
+        let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new_with_options(
+            |event: WebsocketEvent| {
+                let mut events = received.lock().unwrap();
+                events.push(event);
+                if events.len() == 5 {
+                    running.store(false, Ordering::Relaxed);
+                }
+                Ok(())
+            },
+            Config::default().set_ws_endpoint(host),
+        );
+        web_socket.connect("btcusdt@balance").await.unwrap();
+        let policy = ReconnectPolicy::default().set_base_delay(Duration::from_millis(10));
+        web_socket.event_loop_with_reconnect(&running, &policy).await.unwrap();
+
+        let events = events.lock().unwrap();
+        assert!(matches!(events[0], WebsocketEvent::BalanceUpdate(_)));
+        assert!(
+            matches!(&events[1], WebsocketEvent::Connection(ConnectionEvent::Closed(reason)) if reason.is_normal())
+        );
+        assert!(matches!(events[2], WebsocketEvent::Connection(ConnectionEvent::Disconnected(_))));
+        assert!(matches!(events[3], WebsocketEvent::Connection(ConnectionEvent::Connected)));
+        assert!(matches!(events[4], WebsocketEvent::BalanceUpdate(_)));
+    }
+
+    // The handshake callback returns the error response of tungstenite
+    #[allow(clippy::result_large_err)]
+    #[tokio::test]
+    async fn renews_expired_listen_keys() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        let (paths, mut connected_paths) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().await.unwrap();
+                let paths = paths.clone();
+                let mut ws = accept_hdr_async(stream, move |request: &Request, response| {
+                    paths.send(request.uri().path().to_string()).unwrap();
+                    Ok(response)
+                })
+                .await
+                .unwrap();
+                ws.send(Message::Text(LISTEN_KEY_EXPIRED_EVENT.to_string())).await.unwrap();
+                ws.next().await;
+            }
+        });
+        let (rest_host, rest) = scripted_server(vec![ok(r#"{"listenKey":"renewed"}"#)]).await;
+
+        let running = AtomicBool::new(true);
+        let events = Arc::new(Mutex::new(vec![]));
+        let received = events.clone();
+        let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new_with_options(
+            |event: WebsocketEvent| {
+                let mut events = received.lock().unwrap();
+                events.push(event);
+                if events.len() == 4 {
+                    running.store(false, Ordering::Relaxed);
+                }
+                Ok(())
+            },
+            Config::default().set_ws_endpoint(host),
+        );
+        let client = Client::new(Some("api_key".into()), None, rest_host);
+        web_socket.set_listen_key_renewal(Some(ListenKeyEndpoint::new(client, "/api/v3/userDataStream", None)));
+        web_socket.connect("expired").await.unwrap();
+        let policy = ReconnectPolicy::default().set_base_delay(Duration::from_millis(10));
+        web_socket.event_loop_with_reconnect(&running, &policy).await.unwrap();
+
+        assert_eq!(connected_paths.recv().await.unwrap(), "/ws/expired");
+        assert_eq!(connected_paths.recv().await.unwrap(), "/ws/renewed");
+        let renewal = rest.await.unwrap().remove(0);
+        assert!(renewal.starts_with("POST /api/v3/userDataStream"), "{}", renewal);
+        let events = events.lock().unwrap();
+        assert!(matches!(events[0], WebsocketEvent::ListenKeyExpired(_)));
+        assert!(matches!(events[1], WebsocketEvent::Connection(ConnectionEvent::Disconnected(_))));
+        assert!(matches!(events[2], WebsocketEvent::Connection(ConnectionEvent::Connected)));
+        assert!(
+            matches!(&events[3], WebsocketEvent::Connection(ConnectionEvent::ListenKeyRenewed(key)) if key == "renewed")
+        );
+    }
+
+    // The handshake callback returns the error response of tungstenite
+    #[allow(clippy::result_large_err)]
+    #[tokio::test]
+    async fn connects_to_the_configured_hosts() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        let (paths, mut connected_paths) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().await.unwrap();
+                let paths = paths.clone();
+                let _ = accept_hdr_async(stream, move |request: &Request, response| {
+                    paths.send(request.uri().to_string()).unwrap();
+                    Ok(response)
+                })
+                .await;
+            }
+        });
+
+        let config = Config::default()
+            .set_coin_futures_ws_endpoint(host.clone())
+            .set_options_ws_endpoint(format!("{}/eoptions", host));
+        let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new_with_options(|_| Ok(()), config);
+        web_socket.connect_coin_futures("btcusd_perp@aggTrade").await.unwrap();
+        web_socket.connect_multiple_options(vec!["BTC-240628-60000-C@trade".into()]).await.unwrap();
+
+        assert_eq!(connected_paths.recv().await.unwrap(), "/ws/btcusd_perp@aggTrade");
+        assert_eq!(connected_paths.recv().await.unwrap(), "/eoptions/stream?streams=BTC-240628-60000-C@trade");
+    }
+
+    // The handshake callback returns the error response of tungstenite
+    #[allow(clippy::result_large_err)]
+    #[tokio::test]
+    async fn requests_microsecond_times() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        let (paths, mut connected_paths) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for _ in 0..2 {
+                let (stream, _) = listener.accept().await.unwrap();
+                let paths = paths.clone();
+                let _ = accept_hdr_async(stream, move |request: &Request, response| {
+                    paths.send(request.uri().to_string()).unwrap();
+                    Ok(response)
+                })
+                .await;
+            }
+        });
+
+        let mut web_socket: WebSockets<'_, WebsocketEvent> =
+            WebSockets::new_with_options(|_| Ok(()), Config::default().set_ws_endpoint(host));
+        web_socket.set_time_unit(TimeUnit::Microsecond);
+        web_socket.connect("btcusdt@trade").await.unwrap();
+        web_socket
+            .connect_multiple(vec!["btcusdt@trade".into(), "ethusdt@trade".into()])
+            .await
+            .unwrap();
+
+        assert_eq!(
+            connected_paths.recv().await.unwrap(),
+            "/ws/btcusdt@trade?timeUnit=MICROSECOND"
+        );
+        assert_eq!(
+            connected_paths.recv().await.unwrap(),
+            "/stream?streams=btcusdt@trade/ethusdt@trade&timeUnit=MICROSECOND"
+        );
+        assert_eq!(TimeUnit::Microsecond.to_millis(1_700_000_000_123_456), 1_700_000_000_123);
+    }
+
+    #[tokio::test]
+    async fn closes_gracefully_from_the_handle() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            let mut received_close = false;
+            while let Some(Ok(message)) = ws.next().await {
+                received_close |= message.is_close();
+            }
+            received_close
+        });
+
+        let running = AtomicBool::new(true);
+        let mut web_socket: WebSockets<'_, WebsocketEvent> =
+            WebSockets::new_with_options(|_| Ok(()), Config::default().set_ws_endpoint(host));
+        web_socket.connect("btcusdt@balance").await.unwrap();
+        let handle = web_socket.subscription_handle();
+        let policy = ReconnectPolicy::default();
+        let (result, closed) = tokio::join!(web_socket.event_loop_with_reconnect(&running, &policy), handle.close());
+        result.unwrap();
+        closed.unwrap();
+        assert!(web_socket.socket.is_none());
+        assert!(server.await.unwrap());
+    }
+
+    #[tokio::test]
+    async fn reports_server_close_reasons() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            let frame = CloseFrame {
+                code: CloseCode::Policy,
+                reason: "Pong timeout".into(),
+            };
+            ws.close(Some(frame)).await.unwrap();
+            while ws.next().await.is_some() {}
+        });
+
+        let running = AtomicBool::new(true);
+        let mut events = vec![];
+        let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new_with_options(
+            |event: WebsocketEvent| {
+                if matches!(event, WebsocketEvent::Connection(ConnectionEvent::Disconnected(_))) {
+                    running.store(false, Ordering::Relaxed);
+                }
+                events.push(event);
+                Ok(())
+            },
+            Config::default().set_ws_endpoint(host),
+        );
+        web_socket.connect("btcusdt@balance").await.unwrap();
+        let policy = ReconnectPolicy::default();
+        web_socket.event_loop_with_reconnect(&running, &policy).await.unwrap();
+        drop(web_socket);
+
+        match &events[0] {
+            WebsocketEvent::Connection(ConnectionEvent::Closed(reason)) => {
+                assert_eq!(reason.code, 1008);
+                assert_eq!(reason.reason, "Pong timeout");
+                assert!(!reason.is_normal());
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        assert!(matches!(events[1], WebsocketEvent::Connection(ConnectionEvent::Disconnected(_))));
+    }
+
+    #[tokio::test]
+    async fn channel_drops_the_oldest_events() {
+        let (mut web_socket, events) =
+            WebSockets::<WebsocketEvent>::new_with_channel(2, OverflowPolicy::DropOldest, Config::default());
+        for time in 1..=5 {
+            let event = json!({"e": "balanceUpdate", "E": time, "a": "BTC", "d": "1.0", "T": time});
+            (web_socket.handler)(serde_json::from_value(event).unwrap()).unwrap();
+        }
+        assert_eq!(events.len(), 2);
+        assert_eq!(events.dropped_events(), 3);
+        drop(web_socket);
+        assert!(matches!(events.recv().await, Some(WebsocketEvent::BalanceUpdate(update)) if update.event_time == 4));
+        assert!(matches!(events.recv().await, Some(WebsocketEvent::BalanceUpdate(update)) if update.event_time == 5));
+        assert!(events.recv().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn channel_backpressure_waits_for_the_receiver() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            for _ in 0..3 {
+                ws.send(Message::Text(BALANCE_UPDATE.to_string())).await.unwrap();
+            }
+            while ws.next().await.is_some() {}
+        });
+
+        let (mut web_socket, events) = WebSockets::<WebsocketEvent>::new_with_channel(
+            1,
+            OverflowPolicy::Backpressure,
+            Config::default().set_ws_endpoint(host),
+        );
+        web_socket.connect("btcusdt@balance").await.unwrap();
+        let handle = web_socket.subscription_handle();
+        let running = AtomicBool::new(true);
+        let (result, _) = tokio::join!(web_socket.event_loop(&running), async {
+            for _ in 0..3 {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                assert_eq!(events.len(), 1);
+                assert!(matches!(events.recv().await, Some(WebsocketEvent::BalanceUpdate(_))));
+            }
+            handle.close().await.unwrap();
+        });
+        result.unwrap();
+        assert_eq!(events.dropped_events(), 0);
+    }
+
+    #[tokio::test]
+    async fn streams_events_until_dropped() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        let (closed_sender, mut closed) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            // The first connection is closed by dropping the stream, the second by the server
+            for connection in 0..2 {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(stream).await.unwrap();
+                for _ in 0..2 {
+                    ws.send(Message::Text(BALANCE_UPDATE.to_string())).await.unwrap();
+                }
+                if connection == 0 {
+                    while let Some(Ok(message)) = ws.next().await {
+                        if message.is_close() {
+                            closed_sender.send(()).unwrap();
+                        }
+                    }
+                } else {
+                    ws.close(None).await.unwrap();
+                }
+            }
+        });
+
+        let config = Config::default().set_ws_endpoint(host);
+        let (mut web_socket, events) =
+            WebSockets::<WebsocketEvent>::new_with_channel(10, OverflowPolicy::Backpressure, config.clone());
+        web_socket.connect("btcusdt@balance").await.unwrap();
+        let mut stream = web_socket.into_stream(events).unwrap();
+        for _ in 0..2 {
+            assert!(matches!(stream.next().await, Some(Ok(WebsocketEvent::BalanceUpdate(_)))));
+        }
+        drop(stream);
+        tokio::time::timeout(Duration::from_secs(5), closed.recv())
+            .await
+            .unwrap()
+            .unwrap();
+
+        let (mut web_socket, events) =
+            WebSockets::<WebsocketEvent>::new_with_channel(10, OverflowPolicy::Backpressure, config.clone());
+        web_socket.connect("btcusdt@balance").await.unwrap();
+        let mut stream = web_socket.into_stream(events).unwrap();
+        let mut received = vec![];
+        while let Some(event) = stream.next().await {
+            received.push(event);
+        }
+        assert_eq!(received.len(), 3);
+        assert!(matches!(received[2], Err(Error::WebsocketClosed(_))));
+
+        let (web_socket, _) = WebSockets::<WebsocketEvent>::new_with_channel(1, OverflowPolicy::DropOldest, config);
+        let (_, others) =
+            WebSockets::<WebsocketEvent>::new_with_channel(1, OverflowPolicy::DropOldest, Config::default());
+        assert!(web_socket.into_stream(others).is_err());
+    }
+
+    #[tokio::test]
+    async fn watchdog_reconnects_stale_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut silent = vec![];
+            for connection in 0..3 {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(stream).await.unwrap();
+                if connection == 1 {
+                    ws.send(Message::Text(BALANCE_UPDATE.to_string())).await.unwrap();
+                }
+                // stays open without sending anything
+                silent.push(ws);
+            }
+            futures::future::pending::<()>().await;
+        });
+
+        let running = AtomicBool::new(true);
+        let mut events = vec![];
+        let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new_with_options(
+            |event: WebsocketEvent| {
+                events.push(event);
+                if events.len() == 4 {
+                    running.store(false, Ordering::Relaxed);
+                }
+                Ok(())
+            },
+            Config::default().set_ws_endpoint(host),
+        );
+        web_socket.set_watchdog(Some(Watchdog::new(Duration::from_millis(50))));
+        web_socket.connect("btcusdt@balance").await.unwrap();
+        let policy = ReconnectPolicy::default().set_base_delay(Duration::from_millis(10));
+        web_socket.event_loop_with_reconnect(&running, &policy).await.unwrap();
+
+        // without reconnection, the plain event loop gives up
+        web_socket.connect("btcusdt@balance").await.unwrap();
+        let running = AtomicBool::new(true);
+        let error = web_socket.event_loop(&running).await.unwrap_err();
+        assert!(matches!(error, Error::WebsocketStale(timeout) if timeout == Duration::from_millis(50)));
+        drop(web_socket);
+
+        assert!(matches!(events[0], WebsocketEvent::Connection(ConnectionEvent::Stale(_))));
+        assert!(matches!(events[1], WebsocketEvent::Connection(ConnectionEvent::Disconnected(_))));
+        assert!(matches!(events[2], WebsocketEvent::Connection(ConnectionEvent::Connected)));
+        assert!(matches!(events[3], WebsocketEvent::BalanceUpdate(_)));
+    }
+
+    #[tokio::test]
+    async fn unknown_events_do_not_end_the_loop() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            let unknown = r#"{"e":"eventStreamTerminated","E":1}"#;
+            ws.send(Message::Text(unknown.to_string())).await.unwrap();
+            ws.send(Message::Text(BALANCE_UPDATE.to_string())).await.unwrap();
+            ws.next().await;
+        });
+
+        let running = AtomicBool::new(true);
+        let mut events = vec![];
+        let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new_with_options(
+            |event: WebsocketEvent| {
+                events.push(event);
+                if events.len() == 2 {
+                    running.store(false, Ordering::Relaxed);
+                }
+                Ok(())
+            },
+            Config::default().set_ws_endpoint(host),
+        );
+        web_socket.connect("btcusdt@balance").await.unwrap();
+        web_socket.event_loop(&running).await.unwrap();
+        drop(web_socket);
+
+        assert!(matches!(&events[0], WebsocketEvent::Unknown { raw } if raw.contains("eventStreamTerminated")));
+        assert!(matches!(events[1], WebsocketEvent::BalanceUpdate(_)));
+    }
+
+    #[tokio::test]
+    async fn live_subscriptions() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            for _ in 0..3 {
+                let request: Value = match ws.next().await {
+                    Some(Ok(Message::Text(text))) => serde_json::from_str(&text).unwrap(),
+                    other => panic!("unexpected message {:?}", other),
+                };
+                let answer = match request["method"].as_str().unwrap() {
+                    "SUBSCRIBE" => json!({"result": null, "id": request["id"]}),
+                    "LIST_SUBSCRIPTIONS" => {
+                        json!({"result": ["btcusdt@balance", "ethusdt@trade"], "id": request["id"]})
+                    }
+                    _ => json!({"error": {"code": 2, "msg": "Invalid request"}, "id": request["id"]}),
+                };
+                ws.send(Message::Text(answer.to_string())).await.unwrap();
+            }
+            ws.send(Message::Text(BALANCE_UPDATE.to_string())).await.unwrap();
+            ws.next().await;
+        });
+
+        let running = AtomicBool::new(true);
+        let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new_with_options(
+            |_event: WebsocketEvent| {
+                running.store(false, Ordering::Relaxed);
+                Ok(())
+            },
+            Config::default().set_ws_endpoint(host),
+        );
+        web_socket.connect("btcusdt@balance").await.unwrap();
+        let subscriptions = web_socket.subscription_handle();
+        let (result, _) = tokio::join!(web_socket.event_loop(&running), async move {
+            subscriptions.subscribe(&["ethusdt@trade"]).await.unwrap();
+            assert_eq!(subscriptions.list_subscriptions().await.unwrap(), vec![
+                "btcusdt@balance",
+                "ethusdt@trade"
+            ]);
+            let error = subscriptions.unsubscribe(&["unknown"]).await.unwrap_err();
+            assert!(matches!(error, Error::BinanceError { response } if response.code == 2));
+        });
+        result.unwrap();
+        assert!(web_socket.subscriptions.streams.contains("ethusdt@trade"));
+    }
+
+    #[tokio::test]
+    async fn answers_pings_and_sends_keepalive_pongs() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            ws.send(Message::Ping(b"heartbeat".to_vec())).await.unwrap();
+            let pong = tokio::time::timeout(Duration::from_millis(500), ws.next()).await;
+            assert!(matches!(pong, Ok(Some(Ok(Message::Pong(payload)))) if payload == b"heartbeat"));
+            let keepalive = tokio::time::timeout(Duration::from_millis(500), ws.next()).await;
+            assert!(matches!(keepalive, Ok(Some(Ok(Message::Pong(payload)))) if payload.is_empty()));
+            ws.send(Message::Text(BALANCE_UPDATE.to_string())).await.unwrap();
+            ws.next().await;
+        });
+
+        let running = AtomicBool::new(true);
+        let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new_with_options(
+            |_event: WebsocketEvent| {
+                running.store(false, Ordering::Relaxed);
+                Ok(())
+            },
+            Config::default()
+                .set_ws_endpoint(host)
+                .set_ws_keepalive_interval(Some(Duration::from_millis(50))),
+        );
+        web_socket.connect("btcusdt@balance").await.unwrap();
+        web_socket.event_loop(&running).await.unwrap();
+        web_socket.disconnect().await.unwrap();
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn restores_subscriptions_after_reconnecting() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let host = format!("ws://{}", listener.local_addr().unwrap());
+        let (requests_sender, mut requests) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            for connection in 0..2 {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(stream).await.unwrap();
+                // Changes of the subscriptions, then their restoration stream by stream
+                for _ in 0..if connection == 0 { 2 } else { 4 } {
+                    let request: Value = match ws.next().await {
+                        Some(Ok(Message::Text(text))) => serde_json::from_str(&text).unwrap(),
+                        other => panic!("unexpected message {:?}", other),
+                    };
+                    let streams: Vec<String> = serde_json::from_value(request["params"].clone()).unwrap();
+                    let answer = if connection == 1 && streams.iter().any(|s| s == "delisted@trade") {
+                        json!({"error": {"code": 2, "msg": "Invalid request"}, "id": request["id"]})
+                    } else {
+                        json!({"result": null, "id": request["id"]})
+                    };
+                    requests_sender.send((request["method"].clone(), streams)).unwrap();
+                    ws.send(Message::Text(answer.to_string())).await.unwrap();
+                }
+                if connection == 0 {
+                    ws.close(None).await.unwrap();
+                    while ws.next().await.is_some() {}
+                } else {
+                    ws.send(Message::Text(BALANCE_UPDATE.to_string())).await.unwrap();
+                    ws.next().await;
+                }
+            }
+        });
+
+        let running = AtomicBool::new(true);
+        let mut events = vec![];
+        let mut web_socket: WebSockets<'_, WebsocketEvent> = WebSockets::new_with_options(
+            |event: WebsocketEvent| {
+                if matches!(event, WebsocketEvent::BalanceUpdate(_)) {
+                    running.store(false, Ordering::Relaxed);
+                }
+                events.push(event);
+                Ok(())
+            },
+            Config::default().set_ws_endpoint(host),
+        );
+        web_socket.connect("btcusdt@balance").await.unwrap();
+        let subscriptions = web_socket.subscription_handle();
+        let policy = ReconnectPolicy::default().set_base_delay(Duration::from_millis(10));
+        let (result, _) = tokio::join!(web_socket.event_loop_with_reconnect(&running, &policy), async move {
+            subscriptions.subscribe(&["ethusdt@trade", "delisted@trade"]).await.unwrap();
+            subscriptions.unsubscribe(&["btcusdt@balance"]).await.unwrap();
+        });
+        result.unwrap();
+        assert!(!web_socket.subscriptions.streams.contains("delisted@trade"));
+        drop(web_socket);
+
+        let mut sent = vec![];
+        while let Ok((method, streams)) = requests.try_recv() {
+            sent.push(format!("{} {}", method.as_str().unwrap(), streams.join(",")));
+        }
+        assert_eq!(sent, [
+            "SUBSCRIBE ethusdt@trade,delisted@trade",
+            "UNSUBSCRIBE btcusdt@balance",
+            "UNSUBSCRIBE btcusdt@balance",
+            "SUBSCRIBE delisted@trade,ethusdt@trade",
+            "SUBSCRIBE delisted@trade",
+            "SUBSCRIBE ethusdt@trade"
+        ]);
+        let restoration: Vec<_> = events
+            .iter()
+            .filter_map(|event| match event {
+                WebsocketEvent::Connection(event @ ConnectionEvent::SubscriptionsRestored(_))
+                | WebsocketEvent::Connection(event @ ConnectionEvent::SubscriptionRestoreFailed { .. }) => Some(event),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(restoration, [
+            &ConnectionEvent::SubscriptionRestoreFailed {
+                stream: "delisted@trade".to_string(),
+                error: "code: 2, msg: Invalid request".to_string()
+            },
+            &ConnectionEvent::SubscriptionsRestored(vec!["ethusdt@trade".to_string()])
+        ]);
+    }
 }
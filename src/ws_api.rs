@@ -0,0 +1,810 @@
+//! Client of the spot WebSocket API, which places and queries orders and reads market data over a
+//! persistent connection.
+//!
+//! Requests are JSON frames `{"id", "method", "params"}` signed like REST requests, or not signed at all once
+//! the session is logged on with an Ed25519 key. Responses are matched to their request by id, and carry the
+//! rate limits the request counted against.
+
+use std::collections::{BTreeMap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures::{SinkExt, StreamExt};
+use serde::de::DeserializeOwned;
+use serde_json::value::RawValue;
+use serde_json::{json, Value};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot};
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+use crate::account::{OrderAmendment, OrderCancellation, OrderRequest, OrderStatusRequest};
+use crate::client::{handle_content_error, Client};
+use crate::config::Config;
+use crate::errors::*;
+use crate::market::{kline_summaries, supported_depth_limit};
+use crate::rest_model::{AccountCommission, BookTickers, ExchangeInformation, KlineSummaries, NewOrderResponse, Order,
+                        OrderAmended, OrderBook, OrderCanceled, OrderRateLimitUsage, Prices, SymbolPrice,
+                        TestResponse, Tickers};
+use crate::signing::Ed25519Key;
+use crate::util::{build_request_p, get_timestamp};
+use crate::ws_transport;
+
+/// Requests waiting for their response, by id
+type Pending = Arc<Mutex<HashMap<u64, oneshot::Sender<ResponseFrame>>>>;
+
+/// Result of a WebSocket API request, with the rate limits it counted against
+#[derive(Debug, Clone)]
+pub struct WsApiResponse<T> {
+    pub result: T,
+    pub rate_limits: Vec<OrderRateLimitUsage>,
+}
+
+/// Authentication of the connection
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionStatus {
+    /// API key the session is logged on with, `None` when it is not
+    pub api_key: Option<String>,
+    pub authorized_since: Option<u64>,
+    pub connected_since: u64,
+    pub return_rate_limits: bool,
+    pub server_time: u64,
+    #[serde(default)]
+    pub user_data_stream: bool,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ResponseFrame {
+    /// Null when Binance could not read the request
+    id: Option<Value>,
+    status: u16,
+    result: Option<Box<RawValue>>,
+    error: Option<BinanceContentError>,
+    #[serde(default)]
+    rate_limits: Vec<OrderRateLimitUsage>,
+}
+
+/// Connection to the WebSocket API, reopened on the next request when it drops.
+///
+/// Each request waits at most [`Config::timeout`] for its response. A session logged on with
+/// [`Self::session_logon`] is logged on again after reconnections. Dropping the client closes the
+/// connection.
+/// # Examples
+/// ```rust,no_run
+/// use binance::{account::*, config::*, rest_model::*, ws_api::*};
+/// tokio_test::block_on(async {
+///     let api_key = std::env::var("BINANCE_API_KEY").ok();
+///     let secret_key = std::env::var("BINANCE_API_SECRET_KEY").ok();
+///     let client = WebsocketApiClient::connect(api_key, secret_key, &Config::testnet()).await.unwrap();
+///     let order = OrderRequest {
+///         symbol: "BTCUSDT".to_string(),
+///         side: OrderSide::Buy,
+///         order_type: OrderType::Limit,
+///         time_in_force: Some(TimeInForce::GTC),
+///         quantity: Some(0.001),
+///         price: Some(10000.0),
+///         ..OrderRequest::default()
+///     };
+///     let placed = client.place_order(order).await.unwrap();
+///     println!("rate limits: {:?}", placed.rate_limits);
+///     let cancellation = OrderCancellation {
+///         symbol: "BTCUSDT".to_string(),
+///         order_id: Some(placed.result.order_id()),
+///         ..OrderCancellation::default()
+///     };
+///     client.cancel_order(cancellation).await.unwrap();
+/// });
+/// ```
+pub struct WebsocketApiClient {
+    signer: Client,
+    url: Url,
+    conf: Config,
+    recv_window: u64,
+    next_id: AtomicU64,
+    /// Frames to send on the current connection, closed once the connection dropped
+    connection: tokio::sync::Mutex<Option<mpsc::UnboundedSender<String>>>,
+    pending: Pending,
+    rate_limits: Arc<Mutex<Vec<OrderRateLimitUsage>>>,
+    /// Key of the session, set while logged on
+    session: Mutex<Option<Arc<Ed25519Key>>>,
+}
+
+impl WebsocketApiClient {
+    /// Connect to [`Config::ws_api_endpoint`], credentials are only needed by signed requests
+    pub async fn connect(api_key: Option<String>, secret_key: Option<String>, config: &Config) -> Result<Self> {
+        let client = WebsocketApiClient {
+            signer: Client::try_new(api_key, secret_key, config.rest_api_endpoint.clone(), config)?,
+            url: Url::parse(&config.ws_api_endpoint)?,
+            conf: config.clone(),
+            recv_window: config.recv_window,
+            next_id: AtomicU64::new(1),
+            connection: tokio::sync::Mutex::new(None),
+            pending: Arc::new(Mutex::new(HashMap::new())),
+            rate_limits: Arc::new(Mutex::new(vec![])),
+            session: Mutex::new(None),
+        };
+        client.sender().await?;
+        Ok(client)
+    }
+
+    /// Rate limits reported by the last response
+    pub fn rate_limits(&self) -> Vec<OrderRateLimitUsage> {
+        self.rate_limits
+            .lock()
+            .map(|limits| limits.clone())
+            .unwrap_or_default()
+    }
+
+    /// Place an order, answered with whichever response type it asks for or defaults to
+    pub async fn place_order(&self, order: OrderRequest) -> Result<WsApiResponse<NewOrderResponse>> {
+        order.valid()?;
+        self.signed_request("order.place", to_params(&order)?).await
+    }
+
+    /// Validate an order without sending it to the matching engine, with the commission rates it would pay
+    /// if `compute_commission_rates` is set
+    pub async fn place_test_order(
+        &self,
+        order: OrderRequest,
+        compute_commission_rates: bool,
+    ) -> Result<WsApiResponse<TestResponse>> {
+        order.valid()?;
+        let mut params = to_params(&order)?;
+        if compute_commission_rates {
+            params.insert("computeCommissionRates".to_string(), Value::Bool(true));
+        }
+        self.signed_request("order.test", params).await
+    }
+
+    /// Cancel an order by order id or client order id
+    pub async fn cancel_order(&self, cancellation: OrderCancellation) -> Result<WsApiResponse<OrderCanceled>> {
+        cancellation.valid()?;
+        self.signed_request("order.cancel", to_params(&cancellation)?).await
+    }
+
+    /// Reduce the quantity of an open order without losing its priority
+    pub async fn amend_order(&self, amendment: OrderAmendment) -> Result<WsApiResponse<OrderAmended>> {
+        amendment.valid()?;
+        self.signed_request("order.amend.keepPriority", to_params(&amendment)?).await
+    }
+
+    /// Query an order by order id or client order id
+    pub async fn order_status(&self, request: OrderStatusRequest) -> Result<WsApiResponse<Order>> {
+        self.signed_request("order.status", to_params(&request)?).await
+    }
+
+    /// Commission rates of the account for a symbol
+    pub async fn get_commission<S>(&self, symbol: S) -> Result<WsApiResponse<AccountCommission>>
+    where
+        S: Into<String>,
+    {
+        self.signed_request("account.commission", symbol_params(symbol)).await
+    }
+
+    /// Open orders of a symbol
+    pub async fn get_open_orders<S>(&self, symbol: S) -> Result<WsApiResponse<Vec<Order>>>
+    where
+        S: Into<String>,
+    {
+        self.signed_request("openOrders.status", symbol_params(symbol)).await
+    }
+
+    /// Open orders of all symbols
+    pub async fn get_all_open_orders(&self) -> Result<WsApiResponse<Vec<Order>>> {
+        self.signed_request("openOrders.status", BTreeMap::new()).await
+    }
+
+    /// Trading rules and symbols of the exchange, as in [`crate::general::General::exchange_info`]
+    pub async fn get_exchange_info(&self) -> Result<WsApiResponse<ExchangeInformation>> {
+        self.request("exchangeInfo", BTreeMap::new()).await
+    }
+
+    /// Order book of a symbol, with at most `limit` levels on each side
+    /// Supported limits are: 5, 10, 20, 50, 100, 500, 1000, 5000, others are replaced by the nearest supported one
+    /// as in [`crate::market::Market::get_custom_depth`]
+    pub async fn get_depth<S, L>(&self, symbol: S, limit: L) -> Result<WsApiResponse<OrderBook>>
+    where
+        S: Into<String>,
+        L: Into<Option<u16>>,
+    {
+        let mut params = symbol_params(symbol);
+        if let Some(limit) = limit.into() {
+            params.insert("limit".to_string(), Value::from(supported_depth_limit(limit)));
+        }
+        self.request("depth", params).await
+    }
+
+    /// Returns up to 'limit' klines for given symbol and interval ("1m", "5m", ...)
+    pub async fn get_klines<S1, S2, S3, S4, S5>(
+        &self,
+        symbol: S1,
+        interval: S2,
+        limit: S3,
+        start_time: S4,
+        end_time: S5,
+    ) -> Result<WsApiResponse<KlineSummaries>>
+    where
+        S1: Into<String>,
+        S2: Into<String>,
+        S3: Into<Option<u16>>,
+        S4: Into<Option<u64>>,
+        S5: Into<Option<u64>>,
+    {
+        let mut params = symbol_params(symbol);
+        params.insert("interval".to_string(), Value::String(interval.into()));
+        if let Some(limit) = limit.into() {
+            params.insert("limit".to_string(), Value::from(limit));
+        }
+        if let Some(start_time) = start_time.into() {
+            params.insert("startTime".to_string(), Value::from(start_time));
+        }
+        if let Some(end_time) = end_time.into() {
+            params.insert("endTime".to_string(), Value::from(end_time));
+        }
+        let rows: WsApiResponse<Vec<Vec<Value>>> = self.request("klines", params).await?;
+        Ok(WsApiResponse {
+            result: kline_summaries(&rows.result),
+            rate_limits: rows.rate_limits,
+        })
+    }
+
+    /// Latest price of a symbol
+    pub async fn get_price<S>(&self, symbol: S) -> Result<WsApiResponse<SymbolPrice>>
+    where
+        S: Into<String>,
+    {
+        self.request("ticker.price", symbol_params(symbol)).await
+    }
+
+    /// Latest price of all symbols
+    pub async fn get_all_prices(&self) -> Result<WsApiResponse<Prices>> {
+        self.request("ticker.price", BTreeMap::new()).await
+    }
+
+    /// Best price/qty on the order book of a symbol
+    pub async fn get_book_ticker<S>(&self, symbol: S) -> Result<WsApiResponse<Tickers>>
+    where
+        S: Into<String>,
+    {
+        self.request("ticker.book", symbol_params(symbol)).await
+    }
+
+    /// Best price/qty on the order book of all symbols
+    pub async fn get_all_book_tickers(&self) -> Result<WsApiResponse<BookTickers>> {
+        self.request("ticker.book", BTreeMap::new()).await
+    }
+
+    /// Log the session on with the Ed25519 key of the API key, later requests are then authenticated by the
+    /// session and no longer signed
+    pub async fn session_logon(&self, key: Ed25519Key) -> Result<WsApiResponse<SessionStatus>> {
+        let key = Arc::new(key);
+        let sender = self.sender().await?;
+        let status = self.logon(&sender, &key).await?;
+        *self.lock_session() = Some(key);
+        Ok(status)
+    }
+
+    /// Authentication of the current connection
+    pub async fn session_status(&self) -> Result<WsApiResponse<SessionStatus>> {
+        self.request("session.status", BTreeMap::new()).await
+    }
+
+    /// Log the session out, later requests are signed again
+    pub async fn session_logout(&self) -> Result<WsApiResponse<SessionStatus>> {
+        self.lock_session().take();
+        self.request("session.logout", BTreeMap::new()).await
+    }
+
+    async fn logon(
+        &self,
+        sender: &mpsc::UnboundedSender<String>,
+        key: &Ed25519Key,
+    ) -> Result<WsApiResponse<SessionStatus>> {
+        let mut params = BTreeMap::new();
+        params.insert("apiKey".to_string(), Value::String(self.signer.api_key().to_string()));
+        self.stamp(&mut params)?;
+        params.insert("signature".to_string(), Value::String(key.sign(&signature_payload(&params))));
+        self.send_on(sender, "session.logon", params).await
+    }
+
+    async fn signed_request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        mut params: BTreeMap<String, Value>,
+    ) -> Result<WsApiResponse<T>> {
+        let logged_on = self.lock_session().is_some();
+        if !logged_on {
+            params.insert("apiKey".to_string(), Value::String(self.signer.api_key().to_string()));
+        }
+        self.stamp(&mut params)?;
+        if !logged_on {
+            let signature = self.signer.signature(&signature_payload(&params));
+            params.insert("signature".to_string(), Value::String(signature));
+        }
+        self.request(method, params).await
+    }
+
+    fn stamp(&self, params: &mut BTreeMap<String, Value>) -> Result<()> {
+        if self.recv_window > 0 {
+            params
+                .entry("recvWindow".to_string())
+                .or_insert_with(|| Value::from(self.recv_window));
+        }
+        params.insert("timestamp".to_string(), Value::from(get_timestamp()?));
+        Ok(())
+    }
+
+    async fn request<T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: BTreeMap<String, Value>,
+    ) -> Result<WsApiResponse<T>> {
+        let sender = self.sender().await?;
+        self.send_on(&sender, method, params).await
+    }
+
+    /// Send a request on a given connection and wait for its response
+    async fn send_on<T: DeserializeOwned>(
+        &self,
+        sender: &mpsc::UnboundedSender<String>,
+        method: &str,
+        params: BTreeMap<String, Value>,
+    ) -> Result<WsApiResponse<T>> {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let frame = json!({ "id": id, "method": method, "params": params }).to_string();
+        let (response_sender, response) = oneshot::channel();
+        self.lock_pending().insert(id, response_sender);
+        let sent = sender
+            .send(frame)
+            .map_err(|_| Error::Msg(format!("WebSocket API connection lost before sending {}", method)));
+        if let Err(e) = sent {
+            self.lock_pending().remove(&id);
+            return Err(e);
+        }
+
+        let response = match tokio::time::timeout(self.conf.timeout, response).await {
+            Ok(Ok(response)) => response,
+            Ok(Err(_)) => {
+                return Err(Error::Msg(format!(
+                    "WebSocket API connection lost before the response to {}",
+                    method
+                )))
+            }
+            Err(_) => {
+                self.lock_pending().remove(&id);
+                return Err(Error::Msg(format!(
+                    "no response to {} within {:?}",
+                    method, self.conf.timeout
+                )));
+            }
+        };
+        if let Ok(mut rate_limits) = self.rate_limits.lock() {
+            rate_limits.clone_from(&response.rate_limits);
+        }
+        match (response.status, response.result, response.error) {
+            (200, Some(result), _) => Ok(WsApiResponse {
+                result: serde_json::from_str(result.get())?,
+                rate_limits: response.rate_limits,
+            }),
+            (_, _, Some(error)) => Err(handle_content_error(error)),
+            (status, _, None) => Err(Error::Msg(format!("Received response: {}", status))),
+        }
+    }
+
+    /// The current connection, reopened and logged on again if it dropped
+    async fn sender(&self) -> Result<mpsc::UnboundedSender<String>> {
+        let mut connection = self.connection.lock().await;
+        if let Some(sender) = connection.as_ref().filter(|sender| !sender.is_closed()) {
+            return Ok(sender.clone());
+        }
+        let (socket, _) = ws_transport::connect(&self.url, &self.conf).await?;
+        let (sender, outgoing) = mpsc::unbounded_channel();
+        tokio::spawn(run_connection(socket, outgoing, self.pending.clone()));
+        let session = self.lock_session().clone();
+        if let Some(key) = session {
+            self.logon(&sender, &key).await?;
+        }
+        *connection = Some(sender.clone());
+        Ok(sender)
+    }
+
+    fn lock_pending(&self) -> std::sync::MutexGuard<'_, HashMap<u64, oneshot::Sender<ResponseFrame>>> {
+        self.pending.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn lock_session(&self) -> std::sync::MutexGuard<'_, Option<Arc<Ed25519Key>>> {
+        self.session.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+/// Send the frames of the requests and dispatch the responses until the connection drops.
+///
+/// Pings are answered by tungstenite while reading.
+async fn run_connection(
+    mut socket: WebSocketStream<MaybeTlsStream<TcpStream>>,
+    mut outgoing: mpsc::UnboundedReceiver<String>,
+    pending: Pending,
+) {
+    loop {
+        tokio::select! {
+            frame = outgoing.recv() => match frame {
+                Some(frame) => {
+                    if socket.send(Message::Text(frame)).await.is_err() {
+                        break;
+                    }
+                }
+                // The client was dropped
+                None => {
+                    let _ = socket.close(None).await;
+                    break;
+                }
+            },
+            message = socket.next() => match message {
+                Some(Ok(Message::Text(text))) => dispatch(&text, &pending),
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break,
+                Some(Ok(_)) => {}
+            },
+        }
+    }
+    // New requests reconnect, the ones sent on this connection will not be answered
+    outgoing.close();
+    pending
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .clear();
+}
+
+fn dispatch(text: &str, pending: &Pending) {
+    let response: ResponseFrame = match serde_json::from_str(text) {
+        Ok(response) => response,
+        Err(_) => return,
+    };
+    let id = match response.id.as_ref().and_then(Value::as_u64) {
+        Some(id) => id,
+        None => return,
+    };
+    let waiting = pending
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .remove(&id);
+    if let Some(waiting) = waiting {
+        let _ = waiting.send(response);
+    }
+}
+
+fn symbol_params<S: Into<String>>(symbol: S) -> BTreeMap<String, Value> {
+    let mut params = BTreeMap::new();
+    params.insert("symbol".to_string(), Value::String(symbol.into()));
+    params
+}
+
+/// Parameters of a request, formatted as in REST query strings
+fn to_params<S: serde::Serialize>(payload: &S) -> Result<BTreeMap<String, Value>> {
+    let query = build_request_p(payload)?;
+    Ok(url::form_urlencoded::parse(query.as_bytes())
+        .map(|(key, value)| (key.into_owned(), Value::String(value.into_owned())))
+        .collect())
+}
+
+/// Parameters sorted by name as in a query string, which is what gets signed
+fn signature_payload(params: &BTreeMap<String, Value>) -> String {
+    params
+        .iter()
+        .map(|(key, value)| format!("{}={}", key, param_string(value)))
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Values are signed as they would appear in a query string
+fn param_string(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        value => value.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use ring::signature::{UnparsedPublicKey, ED25519};
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    use super::*;
+    use crate::rest_model::{OrderSide, OrderType};
+
+    /// Serve the requests of a single connection with `answer`
+    async fn ws_api_server<F>(answer: F) -> String
+    where
+        F: Fn(Value) -> Option<Value> + Send + 'static,
+    {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws-api/v3", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            while let Some(Ok(Message::Text(text))) = ws.next().await {
+                if let Some(response) = answer(serde_json::from_str(&text).unwrap()) {
+                    ws.send(Message::Text(response.to_string())).await.unwrap();
+                }
+            }
+        });
+        url
+    }
+
+    fn rate_limits(count: u64) -> Value {
+        json!([{"rateLimitType": "ORDERS", "interval": "SECOND", "intervalNum": 10, "limit": 50, "count": count}])
+    }
+
+    #[tokio::test]
+    async fn places_orders_and_matches_responses() {
+        let url = ws_api_server(|request| {
+            let params = &request["params"];
+            match request["method"].as_str().unwrap() {
+                "order.place" => {
+                    assert_eq!(params["symbol"], "BTCUSDT");
+                    assert_eq!(params["quantity"], "0.001");
+                    assert_eq!(params["apiKey"], "key");
+                    assert_eq!(params["recvWindow"], 5000);
+                    assert_eq!(params["signature"].as_str().unwrap().len(), 64);
+                    Some(json!({
+                        "id": request["id"], "status": 200, "rateLimits": rate_limits(1),
+                        "result": {"symbol": "BTCUSDT", "orderId": 12, "orderListId": -1, "clientOrderId": "abc",
+                                   "transactTime": 1_700_000_000_000_u64}
+                    }))
+                }
+                "order.cancel" => Some(json!({
+                    "id": request["id"], "status": 400, "rateLimits": rate_limits(2),
+                    "error": {"code": -2011, "msg": "Unknown order sent."}
+                })),
+                _ => None,
+            }
+        })
+        .await;
+
+        let config = Config::default().set_ws_api_endpoint(url);
+        let client = WebsocketApiClient::connect(Some("key".into()), Some("secret".into()), &config)
+            .await
+            .unwrap();
+        let order = OrderRequest {
+            symbol: "BTCUSDT".to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Market,
+            quantity: Some(0.001),
+            new_order_resp_type: Some(crate::rest_model::OrderResponse::Ack),
+            ..OrderRequest::default()
+        };
+        let placed = client.place_order(order).await.unwrap();
+        assert_eq!(placed.result.order_id(), 12);
+        assert_eq!(placed.rate_limits[0].count, 1);
+
+        let cancellation = OrderCancellation {
+            symbol: "BTCUSDT".to_string(),
+            order_id: Some(12),
+            ..OrderCancellation::default()
+        };
+        match client.cancel_order(cancellation).await {
+            Err(Error::BinanceError { response }) => assert_eq!(response.code, -2011),
+            other => panic!("unexpected {:?}", other),
+        }
+        assert_eq!(client.rate_limits()[0].count, 2);
+    }
+
+    #[tokio::test]
+    async fn amends_orders_and_reads_commission_rates() {
+        let url = ws_api_server(|request| {
+            let params = &request["params"];
+            let result = match request["method"].as_str().unwrap() {
+                "order.amend.keepPriority" => {
+                    assert_eq!(params["orderId"], "9");
+                    assert_eq!(params["newQty"], "5");
+                    assert!(params.get("signature").is_some());
+                    json!({"transactTime": 1741923284382_u64, "executionId": 16, "amendedOrder": {
+                        "symbol": "BTCUSDT", "orderId": 9, "orderListId": -1, "origClientOrderId": "a",
+                        "clientOrderId": "b", "price": "20000.00", "qty": "5.00", "executedQty": "0.00",
+                        "preventedQty": "0.00", "quoteOrderQty": "0.00", "cumulativeQuoteQty": "0.00",
+                        "status": "NEW", "timeInForce": "GTC", "type": "LIMIT", "side": "SELL"}})
+                }
+                "account.commission" => {
+                    assert_eq!(params["symbol"], "BTCUSDT");
+                    let rates = json!({"maker": "0.001", "taker": "0.002", "buyer": "0", "seller": "0"});
+                    json!({"symbol": "BTCUSDT", "standardCommission": rates, "taxCommission": rates,
+                           "discount": {"enabledForAccount": true, "enabledForSymbol": true,
+                                        "discountAsset": "BNB", "discount": "0.75"}})
+                }
+                _ => return None,
+            };
+            Some(json!({"id": request["id"], "status": 200, "rateLimits": rate_limits(1), "result": result}))
+        })
+        .await;
+
+        let config = Config::default().set_ws_api_endpoint(url);
+        let client = WebsocketApiClient::connect(Some("key".into()), Some("secret".into()), &config)
+            .await
+            .unwrap();
+        let amendment = OrderAmendment {
+            symbol: "BTCUSDT".to_string(),
+            order_id: Some(9),
+            new_qty: 5.0,
+            ..OrderAmendment::default()
+        };
+        let amended = client.amend_order(amendment).await.unwrap().result;
+        assert_eq!(amended.execution_id, 16);
+        assert_eq!(amended.amended_order.qty, 5.0);
+        assert!(client.amend_order(OrderAmendment::default()).await.is_err());
+
+        let commission = client.get_commission("BTCUSDT").await.unwrap().result;
+        assert_eq!(commission.standard_commission.taker, 0.002);
+        assert_eq!(commission.discount.discount, 0.75);
+    }
+
+    #[tokio::test]
+    async fn reports_lost_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws-api/v3", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            // Drop the connection on the first request
+            ws.next().await;
+        });
+
+        let config = Config::default().set_ws_api_endpoint(url);
+        let client = WebsocketApiClient::connect(Some("key".into()), Some("secret".into()), &config)
+            .await
+            .unwrap();
+        let error = client.get_all_open_orders().await.unwrap_err();
+        assert!(error.to_string().contains("connection lost"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn matches_out_of_order_market_data_responses() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws-api/v3", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let mut ws = accept_async(stream).await.unwrap();
+            // Hold the requests until all three arrived and answer them in reverse, the klines time out
+            let mut requests = vec![];
+            while let Some(Ok(Message::Text(text))) = ws.next().await {
+                requests.push(serde_json::from_str::<Value>(&text).unwrap());
+                if requests.len() < 3 {
+                    continue;
+                }
+                for request in requests.drain(..).rev() {
+                    assert!(request["params"].get("signature").is_none());
+                    let result = match request["method"].as_str().unwrap() {
+                        "depth" => {
+                            assert_eq!(request["params"]["limit"], 5);
+                            json!({"lastUpdateId": 7, "bids": [["20000.0", "1.0"]], "asks": [["20001.0", "2.0"]]})
+                        }
+                        "ticker.price" => json!({"symbol": "BTCUSDT", "price": "20000.5"}),
+                        _ => continue,
+                    };
+                    let response = json!({"id": request["id"], "status": 200, "rateLimits": rate_limits(1),
+                                          "result": result});
+                    ws.send(Message::Text(response.to_string())).await.unwrap();
+                }
+            }
+        });
+
+        let config = Config::default()
+            .set_ws_api_endpoint(url)
+            .set_timeout(std::time::Duration::from_millis(500));
+        let client = WebsocketApiClient::connect(None, None, &config).await.unwrap();
+        let (depth, price, klines) = tokio::join!(
+            client.get_depth("BTCUSDT", 5),
+            client.get_price("BTCUSDT"),
+            client.get_klines("BTCUSDT", "1m", None, None, None)
+        );
+        let depth = depth.unwrap().result;
+        assert_eq!(depth.last_update_id, 7);
+        assert_eq!(depth.asks[0].qty, 2.0);
+        assert_eq!(price.unwrap().result.price, 20000.5);
+        let error = klines.unwrap_err();
+        assert!(error.to_string().contains("no response to klines"), "{}", error);
+    }
+
+    #[tokio::test]
+    async fn gets_the_exchange_info() {
+        let mut fixture = std::path::PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+        fixture.push("test_data/exchangeInfo.json");
+        let info: Value = serde_json::from_str(&std::fs::read_to_string(fixture).unwrap()).unwrap();
+        let url = ws_api_server(move |request| {
+            assert_eq!(request["method"], "exchangeInfo");
+            assert!(request.get("params").is_none_or(|params| params.get("signature").is_none()));
+            Some(json!({"id": request["id"], "status": 200, "rateLimits": rate_limits(0), "result": info}))
+        })
+        .await;
+
+        let config = Config::default().set_ws_api_endpoint(url);
+        let client = WebsocketApiClient::connect(None, None, &config).await.unwrap();
+        let info = client.get_exchange_info().await.unwrap().result;
+        assert_eq!(info.symbols.len(), 841);
+        assert_eq!(info.symbol("ETHBTC").unwrap().base_asset, "ETH");
+    }
+
+    #[tokio::test]
+    async fn logs_on_again_after_reconnections() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("ws://{}/ws-api/v3", listener.local_addr().unwrap());
+        let (methods_sender, mut methods) = mpsc::unbounded_channel();
+        tokio::spawn(async move {
+            let key = Ed25519Key::from_seed(&[7; 32]).unwrap();
+            let public_key = UnparsedPublicKey::new(&ED25519, key.public_key().to_vec());
+            let mut logged_on = false;
+            for connection in 0..2 {
+                let (stream, _) = listener.accept().await.unwrap();
+                let mut ws = accept_async(stream).await.unwrap();
+                while let Some(Ok(Message::Text(text))) = ws.next().await {
+                    let request: Value = serde_json::from_str(&text).unwrap();
+                    let method = request["method"].as_str().unwrap().to_string();
+                    let mut params: BTreeMap<String, Value> =
+                        serde_json::from_value(request["params"].clone()).unwrap();
+                    let result = match method.as_str() {
+                        "session.logon" => {
+                            let signature = params.remove("signature").unwrap();
+                            let signature = base64::decode(signature.as_str().unwrap()).unwrap();
+                            public_key
+                                .verify(signature_payload(&params).as_bytes(), &signature)
+                                .unwrap();
+                            logged_on = true;
+                            json!({"apiKey": "key", "authorizedSince": 1, "connectedSince": 1,
+                                   "returnRateLimits": true, "serverTime": 2})
+                        }
+                        "session.logout" => {
+                            logged_on = false;
+                            json!({"apiKey": null, "authorizedSince": null, "connectedSince": 1,
+                                   "returnRateLimits": true, "serverTime": 3})
+                        }
+                        _ => {
+                            assert_eq!(params.contains_key("signature"), !logged_on, "{}", text);
+                            assert_eq!(params.contains_key("apiKey"), !logged_on, "{}", text);
+                            json!([])
+                        }
+                    };
+                    methods_sender.send(method.clone()).unwrap();
+                    let response = json!({"id": request["id"], "status": 200, "result": result});
+                    ws.send(Message::Text(response.to_string())).await.unwrap();
+                    if connection == 0 && method == "openOrders.status" {
+                        break;
+                    }
+                }
+            }
+        });
+
+        let config = Config::default().set_ws_api_endpoint(url);
+        let client = WebsocketApiClient::connect(Some("key".into()), Some("secret".into()), &config)
+            .await
+            .unwrap();
+        let status = client
+            .session_logon(Ed25519Key::from_seed(&[7; 32]).unwrap())
+            .await
+            .unwrap();
+        assert_eq!(status.result.api_key.as_deref(), Some("key"));
+        client.get_all_open_orders().await.unwrap();
+        // Let the client notice that the server closed the first connection
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+        client.get_all_open_orders().await.unwrap();
+        assert!(client.session_logout().await.unwrap().result.api_key.is_none());
+        client.get_all_open_orders().await.unwrap();
+
+        let mut seen = vec![];
+        while let Ok(method) = methods.try_recv() {
+            seen.push(method);
+        }
+        assert_eq!(
+            seen,
+            [
+                "session.logon",
+                "openOrders.status",
+                "session.logon",
+                "openOrders.status",
+                "session.logout",
+                "openOrders.status"
+            ]
+        );
+    }
+}
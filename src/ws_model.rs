@@ -1,7 +1,17 @@
-use crate::rest_model::{string_or_float, Asks, Bids, OrderBook, OrderSide, OrderStatus, OrderType, TimeInForce};
+use serde::de::{self, DeserializeOwned, Deserializer};
+use serde::Deserialize;
+use serde_json::value::RawValue;
+use serde_json::Value;
 
+use crate::rest_model::{string_or_float, string_or_float_opt, string_or_u64, Asks, Bids, KlineInterval,
+                        OrderSide, OrderStatus, OrderType, TimeInForce};
+
+/// Events of the spot streams.
+///
+/// Messages with an event type this crate does not know are delivered as [`WebsocketEvent::Unknown`] rather than
+/// ending the event loop, Binance adds new events every now and then. Known events failing to parse are errors.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-#[serde(tag = "e")]
+#[serde(remote = "Self", tag = "e")]
 pub enum WebsocketEvent {
     #[serde(alias = "aggTrade")]
     AggTrade(Box<TradesEvent>),
@@ -13,6 +23,11 @@ pub enum WebsocketEvent {
     DayTicker(Box<DayTickerEvent>),
     #[serde(alias = "24hrMiniTicker")]
     DayMiniTicker(Box<MiniDayTickerEvent>),
+    /// The window is only known from the stream name
+    #[serde(alias = "1hTicker", alias = "4hTicker", alias = "1dTicker")]
+    RollingWindowTicker(Box<RollingWindowTickerEvent>),
+    #[serde(alias = "avgPrice")]
+    AveragePrice(Box<AveragePriceEvent>),
     #[serde(alias = "depthUpdate")]
     DepthOrderBook(Box<DepthOrderBookEvent>),
     #[serde(alias = "outboundAccountPosition")]
@@ -23,6 +38,169 @@ pub enum WebsocketEvent {
     OrderUpdate(Box<OrderUpdate>),
     #[serde(alias = "listStatus")]
     ListOrderUpdate(Box<OrderListUpdate>),
+    /// The user stream stops sending events, see [`crate::websockets::WebSockets::set_listen_key_renewal`]
+    #[serde(alias = "listenKeyExpired")]
+    ListenKeyExpired(Box<ListenKeyExpiredEvent>),
+    /// Synthetic event of the reconnecting event loop, never sent by Binance
+    #[serde(skip)]
+    Connection(ConnectionEvent),
+    /// A message with an event type this crate does not know, as received
+    #[serde(skip)]
+    Unknown { raw: String },
+}
+
+impl serde::Serialize for WebsocketEvent {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        WebsocketEvent::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for WebsocketEvent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = Box::<RawValue>::deserialize(deserializer)?;
+        parse_event(
+            &raw,
+            |json| WebsocketEvent::deserialize(json),
+            |raw| WebsocketEvent::Unknown { raw },
+        )
+    }
+}
+
+/// Event type of a message, read without parsing the rest of it
+#[derive(Deserialize)]
+struct EventType<'a> {
+    #[serde(rename = "e", borrow)]
+    event_type: Option<std::borrow::Cow<'a, str>>,
+}
+
+/// Parse an event, keeping it raw if serde does not know its event type as a variant. Known events failing to
+/// parse and messages without an event type are errors, untagged parsers such as [`WebsocketEventUntag`] try
+/// their other shapes then.
+fn parse_event<T, E: de::Error>(
+    raw: &RawValue,
+    parse: impl FnOnce(&mut serde_json::Deserializer<serde_json::de::StrRead<'_>>) -> serde_json::Result<T>,
+    unknown: impl FnOnce(String) -> T,
+) -> Result<T, E> {
+    let json = raw.get();
+    let error = match parse(&mut serde_json::Deserializer::from_str(json)) {
+        Ok(event) => return Ok(event),
+        Err(error) => error,
+    };
+    let event_type = serde_json::from_str::<EventType>(json).ok().and_then(|e| e.event_type);
+    match event_type {
+        Some(event_type) if error.to_string().starts_with(&format!("unknown variant `{}`", event_type)) => {
+            crate::trace::unknown_event(&event_type);
+            Ok(unknown(json.to_string()))
+        }
+        _ => Err(E::custom(error)),
+    }
+}
+
+/// State changes of a websocket connection, emitted by [`crate::websockets::WebSockets::event_loop_with_reconnect`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConnectionEvent {
+    /// The connection was re-established after a disconnection, events sent in between are lost
+    Connected,
+    /// The connection was lost, with the reason
+    Disconnected(String),
+    /// The server closed the connection, sent right before [`Self::Disconnected`]
+    Closed(CloseReason),
+    /// No message arrived for this long, see [`crate::websockets::WebSockets::set_watchdog`]. Sent before
+    /// [`Self::Disconnected`] when the watchdog reconnects.
+    Stale(std::time::Duration),
+    /// The listen key expired and the user stream was reconnected with this new key, sent after
+    /// [`Self::Connected`]. The new key has to be kept alive, and orders resynced over REST as the events
+    /// sent since the expiration are lost.
+    ListenKeyRenewed(String),
+    /// The streams subscribed at runtime were subscribed again after [`Self::Connected`]
+    SubscriptionsRestored(Vec<String>),
+    /// A stream subscribed at runtime could not be subscribed again after a reconnection, e.g. because its
+    /// symbol was delisted, and is no longer part of the subscriptions
+    SubscriptionRestoreFailed { stream: String, error: String },
+}
+
+/// Close frame of a websocket connection
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloseReason {
+    /// Close code, 1005 when the frame had none
+    pub code: u16,
+    pub reason: String,
+}
+
+impl CloseReason {
+    /// Normal closure, the peer going away (e.g. the rotation of connections after 24h) or a close without
+    /// code. Other codes report policy violations or errors, such as a client too slow to read its messages.
+    pub fn is_normal(&self) -> bool { matches!(self.code, 1000 | 1001 | 1005) }
+}
+
+impl std::fmt::Display for CloseReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result { write!(f, "{} {}", self.code, self.reason) }
+}
+
+/// Unit of the event and transaction times of the spot streams
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum TimeUnit {
+    #[default]
+    Millisecond,
+    Microsecond,
+}
+
+impl TimeUnit {
+    /// A time of this unit in microseconds
+    pub fn to_micros(self, time: u64) -> u64 {
+        match self {
+            TimeUnit::Millisecond => time * 1000,
+            TimeUnit::Microsecond => time,
+        }
+    }
+
+    /// A time of this unit in milliseconds, truncated
+    pub fn to_millis(self, time: u64) -> u64 {
+        match self {
+            TimeUnit::Millisecond => time,
+            TimeUnit::Microsecond => time / 1000,
+        }
+    }
+
+    /// A time of this unit since the epoch
+    pub fn to_system_time(self, time: u64) -> std::time::SystemTime {
+        std::time::UNIX_EPOCH + std::time::Duration::from_micros(self.to_micros(time))
+    }
+}
+
+impl std::fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TimeUnit::Millisecond => write!(f, "MILLISECOND"),
+            TimeUnit::Microsecond => write!(f, "MICROSECOND"),
+        }
+    }
+}
+
+impl From<ConnectionEvent> for WebsocketEvent {
+    fn from(event: ConnectionEvent) -> Self { WebsocketEvent::Connection(event) }
+}
+
+impl From<ConnectionEvent> for FuturesWebsocketEvent {
+    fn from(event: ConnectionEvent) -> Self { FuturesWebsocketEvent::Connection(event) }
+}
+
+impl From<ConnectionEvent> for WebsocketEventUntag {
+    fn from(event: ConnectionEvent) -> Self { WebsocketEventUntag::WebsocketEvent(event.into()) }
+}
+
+impl<T: From<ConnectionEvent>> From<ConnectionEvent> for CombinedStreamEvent<T> {
+    fn from(event: ConnectionEvent) -> Self {
+        CombinedStreamEvent {
+            stream: String::new(),
+            data: event.into(),
+        }
+    }
+}
+
+impl<T: From<ConnectionEvent>> From<ConnectionEvent> for Vec<T> {
+    fn from(event: ConnectionEvent) -> Self { vec![event.into()] }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -42,13 +220,45 @@ pub struct FuturesOrderInfo {
     pub order_status: OrderStatus,
 }
 
+/// Events of the futures streams, unknown events are kept raw as for [`WebsocketEvent`]
 #[derive(Debug, Serialize, Deserialize, Clone)]
-#[serde(tag = "e")]
+#[serde(remote = "Self", tag = "e")]
 pub enum FuturesWebsocketEvent {
     #[serde(alias = "ACCOUNT_UPDATE")]
     AccountUpdate,
     #[serde(alias = "ORDER_TRADE_UPDATE")]
     OrderUpdate(FuturesOrderUpdate),
+    #[serde(alias = "depthUpdate")]
+    DepthOrderBook(Box<DepthOrderBookEvent>),
+    #[serde(alias = "bookTicker")]
+    BookTicker(Box<BookTickerEvent>),
+    #[serde(alias = "24hrMiniTicker")]
+    DayMiniTicker(Box<MiniDayTickerEvent>),
+    #[serde(alias = "listenKeyExpired")]
+    ListenKeyExpired(Box<ListenKeyExpiredEvent>),
+    /// Synthetic event of the reconnecting event loop, never sent by Binance
+    #[serde(skip)]
+    Connection(ConnectionEvent),
+    /// A message with an event type this crate does not know, as received
+    #[serde(skip)]
+    Unknown { raw: String },
+}
+
+impl serde::Serialize for FuturesWebsocketEvent {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        FuturesWebsocketEvent::serialize(self, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FuturesWebsocketEvent {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = Box::<RawValue>::deserialize(deserializer)?;
+        parse_event(
+            &raw,
+            |json| FuturesWebsocketEvent::deserialize(json),
+            |raw| FuturesWebsocketEvent::Unknown { raw },
+        )
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -64,6 +274,7 @@ pub struct QueryResult {
     pub id: i64,
 }
 
+/// Trades aggregated by taker order and price, sent by `<symbol>@aggTrade`
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TradesEvent {
@@ -98,6 +309,7 @@ pub struct TradesEvent {
     pub m_ignore: bool,
 }
 
+/// A single trade, sent by `<symbol>@trade`
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct TradeEvent {
@@ -116,11 +328,13 @@ pub struct TradeEvent {
     #[serde(rename = "q")]
     pub qty: String,
 
-    #[serde(rename = "b")]
-    pub buyer_order_id: u64,
+    /// No longer sent by spot streams
+    #[serde(rename = "b", default)]
+    pub buyer_order_id: Option<u64>,
 
-    #[serde(rename = "a")]
-    pub seller_order_id: u64,
+    /// No longer sent by spot streams
+    #[serde(rename = "a", default)]
+    pub seller_order_id: Option<u64>,
 
     #[serde(rename = "T")]
     pub trade_order_time: u64,
@@ -181,6 +395,64 @@ pub struct DayTickerEvent {
     pub num_trades: u64,
 }
 
+/// Statistics over a rolling window of 1h, 4h or 1d, sent by `<symbol>@ticker_<window>` and
+/// `!ticker_<window>@arr`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct RollingWindowTickerEvent {
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "p")]
+    pub price_change: String,
+    #[serde(rename = "P")]
+    pub price_change_percent: String,
+    #[serde(rename = "o")]
+    pub open: String,
+    #[serde(rename = "h")]
+    pub high: String,
+    #[serde(rename = "l")]
+    pub low: String,
+    #[serde(rename = "c")]
+    pub last_price: String,
+    #[serde(rename = "w")]
+    pub weighted_avg_price: String,
+    #[serde(rename = "v")]
+    pub volume: String,
+    #[serde(rename = "q")]
+    pub quote_volume: String,
+    #[serde(rename = "O")]
+    pub open_time: u64,
+    #[serde(rename = "C")]
+    pub close_time: u64,
+    #[serde(rename = "F")]
+    pub first_trade_id: i64,
+    #[serde(rename = "L")]
+    pub last_trade_id: i64,
+    #[serde(rename = "n")]
+    pub num_trades: u64,
+}
+
+/// Average price over the interval, the value of `GET /api/v3/avgPrice`, sent by `<symbol>@avgPrice`
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct AveragePriceEvent {
+    #[serde(rename = "E")]
+    pub event_time: u64,
+    #[serde(rename = "s")]
+    pub symbol: String,
+    #[serde(rename = "i")]
+    pub interval: KlineInterval,
+    #[serde(rename = "w", with = "string_or_float")]
+    pub price: f64,
+    /// Time of the last trade
+    #[serde(rename = "T")]
+    pub last_trade_time: u64,
+}
+
+/// Rolling 24h statistics without the best bid and ask, sent by `<symbol>@miniTicker` and
+/// `!miniTicker@arr`
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MiniDayTickerEvent {
@@ -223,7 +495,7 @@ pub struct Kline {
     #[serde(rename = "s")]
     pub symbol: String,
     #[serde(rename = "i")]
-    pub interval: String,
+    pub interval: KlineInterval,
     #[serde(rename = "f")]
     pub first_trade_id: i64,
     #[serde(rename = "L")]
@@ -245,6 +517,7 @@ pub struct Kline {
     pub volume: f64,
     #[serde(rename = "n")]
     pub number_of_trades: i64,
+    /// The candle is closed, it will not be updated anymore
     #[serde(rename = "x")]
     pub is_final_bar: bool,
     #[serde(with = "string_or_float")]
@@ -260,26 +533,46 @@ pub struct Kline {
     pub ignore_me: String,
 }
 
+/// Diff of a book, sent by the `<symbol>@depth` and `<symbol>@depth@100ms` streams.
+///
+/// Levels with a zero quantity were removed from the book.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct DepthOrderBookEvent {
     #[serde(rename = "E")]
     pub event_time: u64,
+    /// Futures only
+    #[serde(rename = "T", default, skip_serializing_if = "Option::is_none")]
+    pub transaction_time: Option<u64>,
     #[serde(rename = "s")]
     pub symbol: String,
     #[serde(rename = "U")]
     pub first_update_id: u64,
     #[serde(rename = "u")]
     pub final_update_id: u64,
+    /// Final update id of the previous event, futures only: a gap with it means events were missed
+    #[serde(rename = "pu", default, skip_serializing_if = "Option::is_none")]
+    pub previous_final_update_id: Option<u64>,
     #[serde(rename = "b")]
     pub bids: Vec<Bids>,
     #[serde(rename = "a")]
     pub asks: Vec<Asks>,
 }
 
+/// Best bid and ask of a symbol, sent by the `<symbol>@bookTicker` streams on every change.
+///
+/// Spot payloads have no event type, they are recognized by their fields as
+/// [`WebsocketEventUntag::BookTicker`].
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BookTickerEvent {
+    /// Futures only
+    #[serde(rename = "E", default, skip_serializing_if = "Option::is_none")]
+    pub event_time: Option<u64>,
+    /// Futures only
+    #[serde(rename = "T", default, skip_serializing_if = "Option::is_none")]
+    pub transaction_time: Option<u64>,
+
     #[serde(rename = "u")]
     pub update_id: u64,
 
@@ -299,22 +592,131 @@ pub struct BookTickerEvent {
     pub best_ask_qty: f64,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+impl BookTickerEvent {
+    pub fn spread(&self) -> f64 { self.best_ask - self.best_bid }
+
+    pub fn mid_price(&self) -> f64 { (self.best_ask + self.best_bid) / 2.0 }
+}
+
+/// An event along with the JSON text it was parsed from, to catch fields the typed models drop.
+///
+/// Opt-in by using it as the event type, e.g. `WebSockets<RawEvent<WebsocketEvent>>`. Within a
+/// [`CombinedStreamEvent`] the text is the `data` payload, partial depth snapshots with the symbol added from
+/// the stream name: use `RawEvent<CombinedStreamEvent<_>>` to get the frame exactly as sent.
+#[derive(Debug, Clone, Serialize)]
+pub struct RawEvent<T> {
+    pub event: T,
+    pub text: String,
+}
+
+impl<'de, T: DeserializeOwned> Deserialize<'de> for RawEvent<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let raw: Box<RawValue> = Deserialize::deserialize(deserializer)?;
+        let event = serde_json::from_str(raw.get()).map_err(de::Error::custom)?;
+        Ok(RawEvent {
+            event,
+            text: raw.get().to_string(),
+        })
+    }
+}
+
+/// Connection events were not received, their text is empty
+impl<T: From<ConnectionEvent>> From<ConnectionEvent> for RawEvent<T> {
+    fn from(event: ConnectionEvent) -> Self {
+        RawEvent {
+            event: event.into(),
+            text: String::new(),
+        }
+    }
+}
+
+/// Snapshot of the top levels of a book, sent by the `<symbol>@depth<levels>` streams
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct PartialDepthOrderBookEvent {
+    /// Absent from the payload, only known from the stream name of combined streams
+    #[serde(rename = "s", default)]
+    pub symbol: String,
+    pub last_update_id: u64,
+    pub bids: Vec<Bids>,
+    pub asks: Vec<Asks>,
+}
+
+/// An event of a combined stream, `{"stream": "btcusdt@depth", "data": {...}}`, along with the name of its
+/// stream, which is the only way to know the market of payloads that do not repeat their symbol.
+///
+/// Also works on a raw single stream connection, the name of the stream is then taken from the url.
+#[derive(Debug, Clone, Serialize)]
 pub struct CombinedStreamEvent<T> {
     stream: String,
     pub data: T,
 }
 
-///
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// Partial depth snapshots do not name their symbol, it is taken from the stream name
+impl<'de, T: DeserializeOwned> Deserialize<'de> for CombinedStreamEvent<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        #[derive(Deserialize)]
+        struct RawCombinedStreamEvent {
+            stream: String,
+            data: Box<RawValue>,
+        }
+
+        let RawCombinedStreamEvent { stream, data } = RawCombinedStreamEvent::deserialize(deserializer)?;
+        let json = data.get();
+        // only partial depth snapshots go through a `Value`, every other payload is parsed from its text
+        let data = if json.contains("\"lastUpdateId\"") {
+            let mut value: Value = serde_json::from_str(json).map_err(de::Error::custom)?;
+            if let Value::Object(fields) = &mut value {
+                if fields.contains_key("lastUpdateId") && !fields.contains_key("s") {
+                    let symbol = stream.split('@').next().unwrap_or_default().to_uppercase();
+                    fields.insert("s".to_string(), Value::String(symbol));
+                }
+            }
+            T::deserialize(value)
+        } else {
+            serde_json::from_str(json)
+        }
+        .map_err(de::Error::custom)?;
+        Ok(CombinedStreamEvent { stream, data })
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 #[serde(untagged)]
 pub enum WebsocketEventUntag {
     WebsocketEvent(WebsocketEvent),
-    Orderbook(Box<OrderBook>),
+    PartialDepthOrderBook(Box<PartialDepthOrderBookEvent>),
     BookTicker(Box<BookTickerEvent>),
 }
 
+/// Each shape is tried in turn on the raw message, events are read from raw JSON rather than from the buffered
+/// content of a derived untagged enum
+impl<'de> Deserialize<'de> for WebsocketEventUntag {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let raw = Box::<RawValue>::deserialize(deserializer)?;
+        let json = raw.get();
+        if let Ok(event) = serde_json::from_str(json) {
+            return Ok(WebsocketEventUntag::WebsocketEvent(event));
+        }
+        if let Ok(depth) = serde_json::from_str(json) {
+            return Ok(WebsocketEventUntag::PartialDepthOrderBook(depth));
+        }
+        serde_json::from_str(json)
+            .map(WebsocketEventUntag::BookTicker)
+            .map_err(|_| de::Error::custom("data did not match any variant of untagged enum WebsocketEventUntag"))
+    }
+}
+
 impl<T> CombinedStreamEvent<T> {
+    /// Name of the stream the event was received on, e.g. `btcusdt@depth`
+    pub fn stream(&self) -> &str { &self.stream }
+
     /// Returns (stream_name, channel)
     pub fn parse_stream(&self) -> (String, String) {
         let mut parsed = self.stream.clone();
@@ -326,7 +728,16 @@ impl<T> CombinedStreamEvent<T> {
     }
 }
 
-/// User Stream related events
+// User Stream related events
+
+/// The listen key of a user stream expired, no more events are sent on the connection
+#[derive(Debug, Serialize, Deserialize, Clone)]
+#[serde(rename_all = "camelCase")]
+pub struct ListenKeyExpiredEvent {
+    #[serde(rename = "E", with = "string_or_u64")]
+    pub event_time: u64,
+    pub listen_key: String,
+}
 
 /// Account position update
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -338,10 +749,15 @@ pub struct AccountPositionUpdate {
     #[serde(alias = "u")]
     pub last_update_time: u64,
 
+    /// Only the assets that changed
     #[serde(alias = "B")]
     pub balances: Vec<EventBalance>,
 }
 
+impl AccountPositionUpdate {
+    pub fn balance(&self, asset: &str) -> Option<&EventBalance> { self.balances.iter().find(|b| b.asset == asset) }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct AccountUpdate {
@@ -385,6 +801,10 @@ pub struct EventBalance {
     pub locked: f64,
 }
 
+impl EventBalance {
+    pub fn total(&self) -> f64 { self.free + self.locked }
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct BalanceUpdate {
@@ -394,6 +814,7 @@ pub struct BalanceUpdate {
     #[serde(rename = "a")]
     pub asset: String,
 
+    /// Positive for deposits and transfers in, negative for withdrawals and transfers out
     #[serde(rename = "d")]
     #[serde(with = "string_or_float")]
     pub delta: f64,
@@ -402,6 +823,24 @@ pub struct BalanceUpdate {
     pub clear_time: u64,
 }
 
+/// What happened to the order in an execution report, its resulting status is a separate field
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ExecutionType {
+    New,
+    Canceled,
+    /// The order was amended
+    Replaced,
+    Rejected,
+    Trade,
+    Expired,
+    /// The order expired because of self trade prevention
+    TradePrevention,
+    #[serde(other)]
+    Other,
+}
+
+/// Execution report of the user stream, sent for every change of an order
 #[derive(Debug, Serialize, Deserialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct OrderUpdate {
@@ -434,7 +873,7 @@ pub struct OrderUpdate {
     #[serde(rename = "C")]
     pub origin_client_id: Option<String>,
     #[serde(rename = "x")]
-    pub execution_type: OrderStatus,
+    pub execution_type: ExecutionType,
     #[serde(rename = "X")]
     pub current_order_status: OrderStatus,
     #[serde(rename = "r")]
@@ -453,13 +892,15 @@ pub struct OrderUpdate {
     #[serde(rename = "n")]
     #[serde(with = "string_or_float")]
     pub commission: f64,
+    /// `None` until the order trades
     #[serde(rename = "N")]
     pub commission_asset: Option<String>,
     #[serde(rename = "T")]
     pub trade_order_time: u64,
+    /// -1 unless the execution is a trade
     #[serde(rename = "t")]
     pub trade_id: i64,
-    #[serde(skip, rename = "I")]
+    #[serde(rename = "I", default)]
     pub i_ignore: u64,
     #[serde(rename = "w")]
     pub is_order_on_the_book: bool,
@@ -479,6 +920,28 @@ pub struct OrderUpdate {
     #[serde(rename = "Q")]
     #[serde(with = "string_or_float")]
     pub quote_order_qty: f64,
+    /// When the order went on the book
+    #[serde(rename = "W", default, skip_serializing_if = "Option::is_none")]
+    pub working_time: Option<u64>,
+    #[serde(rename = "V", default, skip_serializing_if = "Option::is_none")]
+    pub self_trade_prevention_mode: Option<String>,
+    /// Trailing stop orders only
+    #[serde(rename = "d", default, skip_serializing_if = "Option::is_none")]
+    pub trailing_delta: Option<u64>,
+    /// When the trailing stop was activated
+    #[serde(rename = "D", default, skip_serializing_if = "Option::is_none")]
+    pub trailing_time: Option<u64>,
+    #[serde(rename = "j", default, skip_serializing_if = "Option::is_none")]
+    pub strategy_id: Option<u64>,
+    #[serde(rename = "J", default, skip_serializing_if = "Option::is_none")]
+    pub strategy_type: Option<i64>,
+    /// Set when the order expired because of self trade prevention
+    #[serde(rename = "v", default, skip_serializing_if = "Option::is_none")]
+    pub prevented_match_id: Option<u64>,
+    #[serde(rename = "A", default, with = "string_or_float_opt", skip_serializing_if = "Option::is_none")]
+    pub prevented_qty: Option<f64>,
+    #[serde(rename = "B", default, with = "string_or_float_opt", skip_serializing_if = "Option::is_none")]
+    pub last_prevented_qty: Option<f64>,
 }
 
 /// For OCO Events
@@ -517,3 +980,211 @@ pub struct OrderListTransaction {
     #[serde(rename = "c")]
     pub client_order_id: i64,
 }
+
+#[cfg(test)]
+mod test {
+    use super::{CombinedStreamEvent, ExecutionType, FuturesWebsocketEvent, RawEvent, WebsocketEvent,
+                WebsocketEventUntag};
+    use crate::rest_model::{KlineInterval, OrderStatus};
+
+    #[test]
+    fn partial_depth_symbol_from_stream_name() {
+        let msg = r#"{"stream":"btcusdt@depth5@100ms","data":{"lastUpdateId":160,"bids":[["0.0024","10"]],"asks":[["0.0026","100"]]}}"#;
+        let event: CombinedStreamEvent<WebsocketEventUntag> = serde_json::from_str(msg).unwrap();
+        match event.data {
+            WebsocketEventUntag::PartialDepthOrderBook(book) => {
+                assert_eq!(book.symbol, "BTCUSDT");
+                assert_eq!(book.last_update_id, 160);
+                assert_eq!(book.bids[0].qty, 10.0);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn raw_event_keeps_the_text() {
+        let msg = r#"{"e":"balanceUpdate","E":1,"a":"BTC","d":"1.0","T":1,"new":true}"#;
+        let raw: RawEvent<WebsocketEvent> = serde_json::from_str(msg).unwrap();
+        assert!(matches!(raw.event, WebsocketEvent::BalanceUpdate(_)));
+        assert_eq!(raw.text, msg);
+    }
+
+    #[test]
+    fn futures_depth_update() {
+        let msg = r#"{"e":"depthUpdate","E":123456789,"T":123456788,"s":"BTCUSDT","U":157,"u":160,"pu":149,"b":[["0.0024","10"]],"a":[["0.0026","0"]]}"#;
+        match serde_json::from_str(msg).unwrap() {
+            FuturesWebsocketEvent::DepthOrderBook(depth) => {
+                assert_eq!(depth.first_update_id, 157);
+                assert_eq!(depth.final_update_id, 160);
+                assert_eq!(depth.previous_final_update_id, Some(149));
+                assert_eq!(depth.asks[0].qty, 0.0);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn closed_kline() {
+        let msg = r#"{"e":"kline","E":1672515782136,"s":"BNBBTC","k":{"t":1672515780000,"T":1672515780999,"s":"BNBBTC","i":"1s","f":100,"L":200,"o":"0.0010","c":"0.0020","h":"0.0025","l":"0.0015","v":"1000","n":100,"x":true,"q":"1.0000","V":"500","Q":"0.500","B":"123456"}}"#;
+        match serde_json::from_str(msg).unwrap() {
+            WebsocketEvent::Kline(event) => {
+                assert_eq!(event.kline.interval, KlineInterval::OneSecond);
+                assert!(event.kline.is_final_bar);
+                assert_eq!(event.kline.number_of_trades, 100);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn book_ticker_without_event_type() {
+        let msg = r#"{"stream":"bnbusdt@bookTicker","data":{"u":400900217,"s":"BNBUSDT","b":"25.35190000","B":"31.21000000","a":"25.36520000","A":"40.66000000"}}"#;
+        let event: CombinedStreamEvent<WebsocketEventUntag> = serde_json::from_str(msg).unwrap();
+        match event.data {
+            WebsocketEventUntag::BookTicker(ticker) => {
+                assert_eq!(ticker.symbol, "BNBUSDT");
+                assert!((ticker.spread() - 0.0133).abs() < 1e-9);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn all_mini_tickers_array() {
+        let msg = r#"[{"e":"24hrMiniTicker","E":123456789,"s":"BNBBTC","c":"0.0025","o":"0.0010","h":"0.0025","l":"0.0010","v":"10000","q":"18"},{"e":"24hrMiniTicker","E":123456789,"s":"ETHBTC","c":"0.05","o":"0.04","h":"0.06","l":"0.04","v":"100","q":"5"}]"#;
+        let events: Vec<WebsocketEvent> = serde_json::from_str(msg).unwrap();
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[1], WebsocketEvent::DayMiniTicker(ticker) if ticker.symbol == "ETHBTC"));
+    }
+
+    #[test]
+    fn rolling_window_ticker() {
+        let msg = r#"{"e":"1hTicker","E":123456789,"s":"BNBBTC","p":"0.0015","P":"250.00","o":"0.0010","h":"0.0025","l":"0.0010","c":"0.0025","w":"0.0018","v":"10000","q":"18","O":0,"C":1675216573749,"F":0,"L":18150,"n":18151}"#;
+        match serde_json::from_str(msg).unwrap() {
+            WebsocketEvent::RollingWindowTicker(ticker) => assert_eq!(ticker.price_change_percent, "250.00"),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn average_price() {
+        let msg = r#"{"e":"avgPrice","E":1693907033000,"s":"BTCUSDT","i":"5m","w":"25776.86000000","T":1693907032213}"#;
+        match serde_json::from_str(msg).unwrap() {
+            WebsocketEvent::AveragePrice(event) => {
+                assert_eq!(event.interval, KlineInterval::FiveMinutes);
+                assert_eq!(event.price, 25776.86);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn listen_key_expired() {
+        let msg = r#"{"e":"listenKeyExpired","E":"1736996475556","listenKey":"WsCMN0a4KHUPTQuX6IUnqEZfB1inxmv1qR4kbf1Lu"}"#;
+        match serde_json::from_str(msg).unwrap() {
+            FuturesWebsocketEvent::ListenKeyExpired(event) => assert_eq!(event.event_time, 1736996475556),
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unknown_event_types() {
+        let msg = r#"{"e":"eventStreamTerminated","E":1728973001334}"#;
+        match serde_json::from_str(msg).unwrap() {
+            WebsocketEvent::Unknown { raw } => assert!(raw.contains("eventStreamTerminated")),
+            other => panic!("unexpected event {:?}", other),
+        }
+        match serde_json::from_str(msg).unwrap() {
+            FuturesWebsocketEvent::Unknown { .. } => {}
+            other => panic!("unexpected event {:?}", other),
+        }
+        // messages without an event type are left to the other shapes
+        assert!(serde_json::from_str::<WebsocketEvent>(r#"{"u":1,"s":"BNBUSDT"}"#).is_err());
+        // known events failing to parse are not swallowed
+        let malformed = r#"{"e":"executionReport","E":1499405658658,"s":"ETHBTC","X":12}"#;
+        assert!(serde_json::from_str::<WebsocketEvent>(malformed).is_err());
+        assert!(serde_json::from_str::<WebsocketEventUntag>(malformed).is_err());
+        // nor are the unknown variants of their fields
+        let unknown_status = r#"{"e":"executionReport","E":1499405658658,"s":"ETHBTC","X":"SIDEWAYS"}"#;
+        let error = serde_json::from_str::<WebsocketEvent>(unknown_status).unwrap_err();
+        assert!(error.to_string().starts_with("unknown variant `SIDEWAYS`"), "{}", error);
+    }
+
+    #[test]
+    fn execution_reports() {
+        let fill = r#"{"e":"executionReport","E":1499405658658,"s":"ETHBTC","c":"mUvoqJxFIILMdfAW5iGSOW","S":"BUY","o":"LIMIT","f":"GTC","q":"1.00000000","p":"0.10264410","P":"0.00000000","F":"0.00000000","g":-1,"C":"","x":"TRADE","X":"PARTIALLY_FILLED","r":"NONE","i":4293153,"l":"0.40000000","z":"0.40000000","L":"0.10264410","n":"0.00004000","N":"BNB","T":1499405658657,"t":1250,"v":3,"I":8641984,"w":true,"m":false,"M":true,"O":1499405658657,"Z":"0.04105764","Y":"0.04105764","Q":"0.00000000","W":1499405658657,"V":"EXPIRE_MAKER"}"#;
+        match serde_json::from_str(fill).unwrap() {
+            WebsocketEvent::OrderUpdate(update) => {
+                assert_eq!(update.execution_type, ExecutionType::Trade);
+                assert_eq!(update.current_order_status, OrderStatus::PartiallyFilled);
+                assert_eq!(update.commission_asset.as_deref(), Some("BNB"));
+                assert_eq!(update.last_quote_asset_transacted_qty, 0.04105764);
+                assert_eq!(update.working_time, Some(1499405658657));
+                assert_eq!(update.self_trade_prevention_mode.as_deref(), Some("EXPIRE_MAKER"));
+                assert_eq!(update.prevented_match_id, Some(3));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        let cancel = r#"{"e":"executionReport","E":1499405658700,"s":"ETHBTC","c":"cancelRequestId","S":"SELL","o":"STOP_LOSS_LIMIT","f":"GTC","q":"1.00000000","p":"0.10000000","P":"0.10100000","d":100,"j":1000000,"J":1000000,"F":"0.00000000","g":-1,"C":"mUvoqJxFIILMdfAW5iGSOW","x":"CANCELED","X":"CANCELED","r":"NONE","i":4293154,"l":"0.00000000","z":"0.00000000","L":"0.00000000","n":"0","N":null,"T":1499405658699,"t":-1,"I":8641990,"w":false,"m":false,"M":false,"O":1499405658600,"Z":"0.00000000","Y":"0.00000000","Q":"0.00000000","V":"NONE"}"#;
+        match serde_json::from_str(cancel).unwrap() {
+            WebsocketEvent::OrderUpdate(update) => {
+                assert_eq!(update.execution_type, ExecutionType::Canceled);
+                assert_eq!(update.commission_asset, None);
+                assert_eq!(update.trailing_delta, Some(100));
+                assert_eq!(update.strategy_type, Some(1000000));
+                assert_eq!(update.working_time, None);
+                assert_eq!(update.trade_id, -1);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn balance_events() {
+        let deposit = r#"{"e":"balanceUpdate","E":1573200697110,"a":"BTC","d":"100.00000000","T":1573200697068}"#;
+        match serde_json::from_str(deposit).unwrap() {
+            WebsocketEvent::BalanceUpdate(update) => assert_eq!(update.delta, 100.0),
+            other => panic!("unexpected event {:?}", other),
+        }
+        let withdrawal = r#"{"e":"balanceUpdate","E":1573200697110,"a":"USDT","d":"-25.50000000","T":1573200697068}"#;
+        match serde_json::from_str(withdrawal).unwrap() {
+            WebsocketEvent::BalanceUpdate(update) => {
+                assert_eq!(update.asset, "USDT");
+                assert_eq!(update.delta, -25.5);
+                assert_eq!(update.clear_time, 1573200697068);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        let trade = r#"{"e":"outboundAccountPosition","E":1564034571105,"u":1564034571073,"B":[{"a":"ETH","f":"10000.000000","l":"0.000000"},{"a":"USDT","f":"4000.000000","l":"1000.000000"}]}"#;
+        match serde_json::from_str(trade).unwrap() {
+            WebsocketEvent::AccountPositionUpdate(update) => {
+                assert_eq!(update.last_update_time, 1564034571073);
+                assert_eq!(update.balances.len(), 2);
+                assert_eq!(update.balance("USDT").unwrap().total(), 5000.0);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+
+    #[test]
+    fn trade_and_agg_trade_payloads() {
+        let trade = r#"{"e":"trade","E":1672515782136,"s":"BNBBTC","t":12345,"p":"0.001","q":"100","T":1672515782136,"m":true,"M":true}"#;
+        match serde_json::from_str(trade).unwrap() {
+            WebsocketEvent::Trade(trade) => {
+                assert_eq!(trade.trade_id, 12345);
+                assert!(trade.is_buyer_maker);
+                assert_eq!(trade.buyer_order_id, None);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+        let agg_trade = r#"{"e":"aggTrade","E":1672515782136,"s":"BNBBTC","a":12345,"p":"0.001","q":"100","f":100,"l":105,"T":1672515782136,"m":false,"M":true}"#;
+        match serde_json::from_str(agg_trade).unwrap() {
+            WebsocketEvent::AggTrade(agg_trade) => {
+                assert_eq!(agg_trade.aggregated_trade_id, 12345);
+                assert_eq!((agg_trade.first_break_trade_id, agg_trade.last_break_trade_id), (100, 105));
+                assert!(!agg_trade.is_buyer_maker);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
+}
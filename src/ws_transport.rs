@@ -0,0 +1,474 @@
+//! Opening of websocket connections, through a proxy and with the TLS settings of the config.
+//!
+//! Failures of the proxy tunnel and of the TLS handshake are reported as [`Error::WebsocketProxy`] and
+//! [`Error::WebsocketTls`] so that a misconfigured network can be told apart from an unreachable host.
+
+use std::time::Duration;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::tungstenite::handshake::client::Response;
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+use tokio_tungstenite::{tungstenite::Error as WsError, Connector};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
+use url::Url;
+
+use crate::config::Config;
+use crate::errors::*;
+
+/// Upper bound of the response of a proxy to a CONNECT request
+static MAX_PROXY_RESPONSE_BYTES: usize = 8192;
+
+type Socket = (WebSocketStream<MaybeTlsStream<TcpStream>>, Response);
+
+pub(crate) async fn connect(url: &Url, config: &Config) -> Result<Socket> {
+    let host = url
+        .host_str()
+        .ok_or_else(|| Error::Msg(format!("no host in websocket url {}", url)))?;
+    let port = url
+        .port_or_known_default()
+        .ok_or_else(|| Error::Msg(format!("no port in websocket url {}", url)))?;
+    let stream = match proxy_for(url, config)? {
+        Some(proxy) => tunnel(&proxy, host, port, config.connect_timeout).await?,
+        None => TcpStream::connect((host, port)).await?,
+    };
+    handshake(url, stream, config).await
+}
+
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+async fn handshake(url: &Url, stream: TcpStream, config: &Config) -> Result<Socket> {
+    let connector = tls_connector(config)?;
+    tokio_tungstenite::client_async_tls_with_config(url.as_str(), stream, None, connector)
+        .await
+        .map_err(handshake_error)
+}
+
+#[cfg(not(any(feature = "native-tls", feature = "rustls-tls")))]
+async fn handshake(url: &Url, stream: TcpStream, config: &Config) -> Result<Socket> {
+    if url.scheme() == "wss" || !config.ws_root_certificates.is_empty() || config.ws_disable_built_in_roots {
+        return Err(Error::WebsocketTls(
+            "TLS needs the native-tls or rustls-tls feature".into(),
+        ));
+    }
+    tokio_tungstenite::client_async(url.as_str(), MaybeTlsStream::Plain(stream))
+        .await
+        .map_err(Error::from)
+}
+
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+fn handshake_error(e: WsError) -> Error {
+    match e {
+        WsError::Tls(e) => Error::WebsocketTls(e.to_string()),
+        // rustls reports handshake failures as IO errors
+        #[cfg(feature = "rustls-tls")]
+        WsError::Io(e) if e.get_ref().is_some_and(|inner| inner.is::<rustls::Error>()) => {
+            Error::WebsocketTls(e.to_string())
+        }
+        e => Error::from(e),
+    }
+}
+
+/// The configured proxy, or the one of the environment
+fn proxy_for(url: &Url, config: &Config) -> Result<Option<Url>> {
+    let proxy = match &config.ws_proxy {
+        Some(proxy) => proxy.clone(),
+        None => match env_proxy(url) {
+            Some(proxy) => proxy,
+            None => return Ok(None),
+        },
+    };
+    Url::parse(&proxy)
+        .map(Some)
+        .map_err(|e| Error::WebsocketProxy(format!("invalid proxy url {}: {}", proxy, e)))
+}
+
+fn env_proxy(url: &Url) -> Option<String> {
+    let host = url.host_str()?;
+    if is_loopback(host) || no_proxy(host) {
+        return None;
+    }
+    let vars: &[&str] = if url.scheme() == "wss" {
+        &["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"]
+    } else {
+        &["HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"]
+    };
+    vars.iter()
+        .find_map(|var| std::env::var(var).ok().filter(|proxy| !proxy.is_empty()))
+}
+
+fn is_loopback(host: &str) -> bool {
+    host == "localhost"
+        || host
+            .trim_matches(|c| c == '[' || c == ']')
+            .parse::<std::net::IpAddr>()
+            .is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Whether `NO_PROXY` lists the host or one of its parent domains
+fn no_proxy(host: &str) -> bool {
+    let no_proxy = match std::env::var("NO_PROXY").or_else(|_| std::env::var("no_proxy")) {
+        Ok(no_proxy) => no_proxy,
+        Err(_) => return false,
+    };
+    no_proxy
+        .split(',')
+        .map(|entry| entry.trim().trim_start_matches('.'))
+        .filter(|entry| !entry.is_empty())
+        .any(|entry| entry == "*" || host == entry || host.ends_with(&format!(".{}", entry)))
+}
+
+/// Open a tunnel to `host:port` through the proxy
+async fn tunnel(proxy: &Url, host: &str, port: u16, connect_timeout: Duration) -> Result<TcpStream> {
+    let proxy_host = proxy
+        .host_str()
+        .ok_or_else(|| Error::WebsocketProxy(format!("no host in proxy url {}", proxy)))?;
+    let credentials = match proxy.password() {
+        Some(password) if !proxy.username().is_empty() => Some((proxy.username(), password)),
+        None if !proxy.username().is_empty() => Some((proxy.username(), "")),
+        _ => None,
+    };
+    let (default_port, socks) = match proxy.scheme() {
+        "http" => (80, false),
+        "socks5" | "socks5h" => (1080, true),
+        scheme => {
+            return Err(Error::WebsocketProxy(format!(
+                "unsupported proxy scheme {}, use http or socks5",
+                scheme
+            )))
+        }
+    };
+    let proxy_port = proxy.port().unwrap_or(default_port);
+    let mut stream = tokio::time::timeout(connect_timeout, TcpStream::connect((proxy_host, proxy_port)))
+        .await
+        .map_err(|_| Error::WebsocketProxy(format!("cannot reach {}:{}: timed out", proxy_host, proxy_port)))?
+        .map_err(|e| Error::WebsocketProxy(format!("cannot reach {}:{}: {}", proxy_host, proxy_port, e)))?;
+    let opened = if socks {
+        socks5_connect(&mut stream, host, port, credentials).await
+    } else {
+        http_connect(&mut stream, host, port, credentials).await
+    };
+    opened.map_err(|e| match e {
+        Error::WebsocketProxy(msg) => Error::WebsocketProxy(msg),
+        e => Error::WebsocketProxy(e.to_string()),
+    })?;
+    Ok(stream)
+}
+
+async fn http_connect(stream: &mut TcpStream, host: &str, port: u16, credentials: Option<(&str, &str)>) -> Result<()> {
+    let mut request = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n", host = host, port = port);
+    if let Some((user, password)) = credentials {
+        let token = base64::encode(format!("{}:{}", decode(user), decode(password)));
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", token));
+    }
+    request.push_str("\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read byte by byte, anything after the headers belongs to the tunnel
+    let mut response = Vec::new();
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() >= MAX_PROXY_RESPONSE_BYTES {
+            return Err(Error::WebsocketProxy("response to CONNECT is too large".into()));
+        }
+        let byte = stream.read_u8().await?;
+        response.push(byte);
+    }
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') => Ok(()),
+        _ => Err(Error::WebsocketProxy(format!("CONNECT refused: {}", status_line))),
+    }
+}
+
+async fn socks5_connect(
+    stream: &mut TcpStream,
+    host: &str,
+    port: u16,
+    credentials: Option<(&str, &str)>,
+) -> Result<()> {
+    // Fields are prefixed with a one byte length, check them all before talking to the proxy
+    let auth = match credentials {
+        Some((user, password)) => {
+            let mut auth = vec![0x01];
+            push_socks5_field(&mut auth, "user name", decode(user).as_bytes())?;
+            push_socks5_field(&mut auth, "password", decode(password).as_bytes())?;
+            Some(auth)
+        }
+        None => None,
+    };
+    let mut request = vec![0x05, 0x01, 0x00, 0x03];
+    push_socks5_field(&mut request, "host", host.as_bytes())?;
+    request.extend_from_slice(&port.to_be_bytes());
+
+    let methods: &[u8] = if credentials.is_some() { &[0x00, 0x02] } else { &[0x00] };
+    let mut greeting = vec![0x05, methods.len() as u8];
+    greeting.extend_from_slice(methods);
+    stream.write_all(&greeting).await?;
+    let mut choice = [0u8; 2];
+    stream.read_exact(&mut choice).await?;
+    match (choice[1], auth) {
+        (0x00, _) => {}
+        (0x02, Some(auth)) => {
+            stream.write_all(&auth).await?;
+            let mut status = [0u8; 2];
+            stream.read_exact(&mut status).await?;
+            if status[1] != 0x00 {
+                return Err(Error::WebsocketProxy("SOCKS5 authentication failed".into()));
+            }
+        }
+        _ => return Err(Error::WebsocketProxy("no acceptable SOCKS5 authentication method".into())),
+    }
+
+    stream.write_all(&request).await?;
+    let mut reply = [0u8; 4];
+    stream.read_exact(&mut reply).await?;
+    if reply[1] != 0x00 {
+        return Err(Error::WebsocketProxy(format!(
+            "SOCKS5 CONNECT refused: {}",
+            socks5_reply(reply[1])
+        )));
+    }
+    // Skip the bound address and port
+    let address_len = match reply[3] {
+        0x01 => 4,
+        0x04 => 16,
+        0x03 => stream.read_u8().await? as usize,
+        atyp => return Err(Error::WebsocketProxy(format!("unknown SOCKS5 address type {}", atyp))),
+    };
+    let mut bound = vec![0u8; address_len + 2];
+    stream.read_exact(&mut bound).await?;
+    Ok(())
+}
+
+fn push_socks5_field(buf: &mut Vec<u8>, name: &str, value: &[u8]) -> Result<()> {
+    let len = u8::try_from(value.len())
+        .map_err(|_| Error::WebsocketProxy(format!("SOCKS5 {} is longer than 255 bytes", name)))?;
+    buf.push(len);
+    buf.extend_from_slice(value);
+    Ok(())
+}
+
+fn socks5_reply(code: u8) -> String {
+    match code {
+        0x01 => "general failure".into(),
+        0x02 => "connection not allowed by ruleset".into(),
+        0x03 => "network unreachable".into(),
+        0x04 => "host unreachable".into(),
+        0x05 => "connection refused".into(),
+        0x06 => "TTL expired".into(),
+        0x07 => "command not supported".into(),
+        0x08 => "address type not supported".into(),
+        code => format!("code {}", code),
+    }
+}
+
+/// Credentials of urls are percent encoded
+fn decode(s: &str) -> String {
+    url::form_urlencoded::parse(format!("v={}", s.replace('+', "%2B")).as_bytes())
+        .next()
+        .map(|(_, v)| v.into_owned())
+        .unwrap_or_default()
+}
+
+/// The default connector of tokio-tungstenite is used unless the config customizes the roots
+#[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+fn tls_connector(config: &Config) -> Result<Option<Connector>> {
+    if config.ws_root_certificates.is_empty() && !config.ws_disable_built_in_roots {
+        return Ok(None);
+    }
+    build_connector(config).map(Some)
+}
+
+#[cfg(feature = "native-tls")]
+fn build_connector(config: &Config) -> Result<Connector> {
+    let mut builder = native_tls::TlsConnector::builder();
+    for pem in &config.ws_root_certificates {
+        let certificate = native_tls::Certificate::from_pem(pem)
+            .map_err(|e| Error::WebsocketTls(format!("invalid root certificate: {}", e)))?;
+        builder.add_root_certificate(certificate);
+    }
+    builder.disable_built_in_roots(config.ws_disable_built_in_roots);
+    builder
+        .build()
+        .map(Connector::NativeTls)
+        .map_err(|e| Error::WebsocketTls(e.to_string()))
+}
+
+#[cfg(all(feature = "rustls-tls", not(feature = "native-tls")))]
+fn build_connector(config: &Config) -> Result<Connector> {
+    let mut roots = rustls::RootCertStore::empty();
+    if !config.ws_disable_built_in_roots {
+        roots.add_server_trust_anchors(webpki_roots::TLS_SERVER_ROOTS.0.iter().map(|anchor| {
+            rustls::OwnedTrustAnchor::from_subject_spki_name_constraints(
+                anchor.subject,
+                anchor.spki,
+                anchor.name_constraints,
+            )
+        }));
+    }
+    for pem in &config.ws_root_certificates {
+        let certificates = rustls_pemfile::certs(&mut pem.as_slice())
+            .map_err(|e| Error::WebsocketTls(format!("invalid root certificate: {}", e)))?;
+        if certificates.is_empty() {
+            return Err(Error::WebsocketTls("no certificate in PEM root".into()));
+        }
+        for certificate in certificates {
+            roots
+                .add(&rustls::Certificate(certificate))
+                .map_err(|e| Error::WebsocketTls(format!("invalid root certificate: {}", e)))?;
+        }
+    }
+    let tls_config = rustls::ClientConfig::builder()
+        .with_safe_defaults()
+        .with_root_certificates(roots)
+        .with_no_client_auth();
+    Ok(Connector::Rustls(std::sync::Arc::new(tls_config)))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use tokio::net::TcpListener;
+    use tokio_tungstenite::accept_async;
+
+    /// A websocket server that greets every client
+    async fn ws_server() -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                tokio::spawn(async move {
+                    use futures::SinkExt;
+                    let mut ws = accept_async(stream).await.unwrap();
+                    ws.send("hello".into()).await.unwrap();
+                    futures::future::pending::<()>().await;
+                });
+            }
+        });
+        addr
+    }
+
+    async fn read_head(stream: &mut TcpStream) -> String {
+        let mut head = Vec::new();
+        while !head.ends_with(b"\r\n\r\n") {
+            head.push(stream.read_u8().await.unwrap());
+        }
+        String::from_utf8(head).unwrap()
+    }
+
+    #[tokio::test]
+    async fn connects_through_an_http_proxy() {
+        let target = ws_server().await;
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = format!("http://user:p%40ss@{}", listener.local_addr().unwrap());
+        let expected = target.clone();
+        tokio::spawn(async move {
+            let (mut client, _) = listener.accept().await.unwrap();
+            let head = read_head(&mut client).await;
+            assert!(head.starts_with(&format!("CONNECT {} HTTP/1.1\r\n", expected)));
+            assert!(head.contains(&format!("Proxy-Authorization: Basic {}\r\n", base64::encode("user:p@ss"))));
+            let mut upstream = TcpStream::connect(&expected).await.unwrap();
+            client.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n").await.unwrap();
+            let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+        });
+
+        let url = Url::parse(&format!("ws://{}/ws/btcusdt@trade", target)).unwrap();
+        let config = Config::default().set_ws_proxy(Some(proxy));
+        let (mut ws, _) = connect(&url, &config).await.unwrap();
+        use futures::StreamExt;
+        assert_eq!(ws.next().await.unwrap().unwrap().into_text().unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn connects_through_a_socks5_proxy() {
+        let target = ws_server().await;
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = format!("socks5://{}", listener.local_addr().unwrap());
+        let expected = target.clone();
+        tokio::spawn(async move {
+            let (mut client, _) = listener.accept().await.unwrap();
+            let mut greeting = [0u8; 3];
+            client.read_exact(&mut greeting).await.unwrap();
+            assert_eq!(greeting, [0x05, 0x01, 0x00]);
+            client.write_all(&[0x05, 0x00]).await.unwrap();
+            let mut request = [0u8; 5];
+            client.read_exact(&mut request).await.unwrap();
+            let mut destination = vec![0u8; request[4] as usize + 2];
+            client.read_exact(&mut destination).await.unwrap();
+            assert_eq!(&destination[..request[4] as usize], b"127.0.0.1");
+            let mut upstream = TcpStream::connect(&expected).await.unwrap();
+            client.write_all(&[0x05, 0x00, 0x00, 0x01, 0, 0, 0, 0, 0, 0]).await.unwrap();
+            let _ = tokio::io::copy_bidirectional(&mut client, &mut upstream).await;
+        });
+
+        let url = Url::parse(&format!("ws://{}/ws/btcusdt@trade", target)).unwrap();
+        let config = Config::default().set_ws_proxy(Some(proxy));
+        let (mut ws, _) = connect(&url, &config).await.unwrap();
+        use futures::StreamExt;
+        assert_eq!(ws.next().await.unwrap().unwrap().into_text().unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn reports_proxy_failures() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut client, _) = listener.accept().await.unwrap();
+            read_head(&mut client).await;
+            client
+                .write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n")
+                .await
+                .unwrap();
+        });
+
+        let url = Url::parse("wss://stream.binance.com/ws/btcusdt@trade").unwrap();
+        let config = Config::default().set_ws_proxy(Some(proxy));
+        let error = connect(&url, &config).await.unwrap_err();
+        assert!(matches!(error, Error::WebsocketProxy(_)));
+        assert_eq!(
+            error.to_string(),
+            "websocket proxy handshake failed: CONNECT refused: HTTP/1.1 407 Proxy Authentication Required"
+        );
+    }
+
+    #[tokio::test]
+    async fn rejects_socks5_fields_longer_than_255_bytes() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let proxy = format!("socks5://{}:pass@{}", "u".repeat(256), listener.local_addr().unwrap());
+        let url = Url::parse("wss://stream.binance.com/ws/btcusdt@trade").unwrap();
+        let config = Config::default().set_ws_proxy(Some(proxy));
+        let error = connect(&url, &config).await.unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "websocket proxy handshake failed: SOCKS5 user name is longer than 255 bytes"
+        );
+
+        let host = vec!["a".repeat(60); 5].join(".");
+        let url = Url::parse(&format!("wss://{}/ws/btcusdt@trade", host)).unwrap();
+        let config = Config::default().set_ws_proxy(Some(format!("socks5://{}", listener.local_addr().unwrap())));
+        let error = connect(&url, &config).await.unwrap_err();
+        assert_eq!(error.to_string(), "websocket proxy handshake failed: SOCKS5 host is longer than 255 bytes");
+    }
+
+    #[cfg(any(feature = "native-tls", feature = "rustls-tls"))]
+    #[tokio::test]
+    async fn reports_tls_failures() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("wss://{}/ws/btcusdt@trade", listener.local_addr().unwrap())).unwrap();
+        tokio::spawn(async move {
+            let (mut client, _) = listener.accept().await.unwrap();
+            client.write_all(b"HTTP/1.1 400 Bad Request\r\n\r\n").await.unwrap();
+        });
+
+        let error = connect(&url, &Config::default()).await.unwrap_err();
+        assert!(matches!(error, Error::WebsocketTls(_)), "{}", error);
+    }
+
+    #[test]
+    fn no_proxy_for_loopback_hosts() {
+        assert!(is_loopback("localhost"));
+        assert!(is_loopback("127.0.0.1"));
+        assert!(is_loopback("[::1]"));
+        assert!(!is_loopback("stream.binance.com"));
+    }
+}
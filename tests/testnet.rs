@@ -0,0 +1,155 @@
+//! End to end tests against the spot testnet, off by default.
+//!
+//! Run them with testnet credentials from <https://testnet.binance.vision>:
+//! `BINANCE_TESTNET_API_KEY=... BINANCE_TESTNET_API_SECRET_KEY=... cargo test --features live-test --test testnet`.
+//! The tests are skipped when the credentials are not set, so that `--all-features` can run without them.
+
+#![allow(clippy::result_large_err)]
+#![cfg(feature = "live-test")]
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use binance::account::{Account, OrderCancellation, OrderRequest, OrderStatusRequest};
+use binance::api::Binance;
+use binance::config::Config;
+use binance::errors::Result;
+use binance::exchange_info_cache::ExchangeInfoCache;
+use binance::general::General;
+use binance::market::Market;
+use binance::rest_model::{Filters, OrderSide, OrderStatus, OrderType, TimeInForce};
+use binance::websockets::{partial_book_depth_stream, DepthLevels, UpdateSpeed, WebSockets};
+use binance::ws_model::{CombinedStreamEvent, WebsocketEventUntag};
+
+const SYMBOL: &str = "BTCUSDT";
+
+fn account() -> Option<Account> {
+    match (
+        std::env::var("BINANCE_TESTNET_API_KEY"),
+        std::env::var("BINANCE_TESTNET_API_SECRET_KEY"),
+    ) {
+        (Ok(api_key), Ok(secret_key)) => Some(Binance::new_with_config(
+            Some(api_key),
+            Some(secret_key),
+            &Config::testnet(),
+        )),
+        _ => {
+            eprintln!("BINANCE_TESTNET_API_KEY and BINANCE_TESTNET_API_SECRET_KEY are not set, skipping");
+            None
+        }
+    }
+}
+
+/// Round down to a multiple of `step`, keeping the decimals Binance accepts
+fn round_to(value: f64, step: f64) -> f64 {
+    let rounded = (value / step).floor() * step;
+    format!("{:.8}", rounded).parse().unwrap()
+}
+
+#[tokio::test]
+async fn account_information() {
+    let account = match account() {
+        Some(account) => account,
+        None => return,
+    };
+    let information = account.get_account().await.unwrap();
+    assert!(information.can_trade);
+    assert!(!information.balances.is_empty());
+}
+
+#[tokio::test]
+async fn order_lifecycle() {
+    let account = match account() {
+        Some(account) => account,
+        None => return,
+    };
+    let result = place_query_and_cancel(&account).await;
+    // Whatever happened, leave no order behind
+    let cleanup = account.cancel_all_open_orders(SYMBOL).await;
+    result.unwrap();
+    if let Err(e) = cleanup {
+        // -2011, there was nothing left to cancel
+        assert!(e.to_string().contains("-2011"), "{:?}", e);
+    }
+}
+
+async fn place_query_and_cancel(account: &Account) -> Result<()> {
+    let general: General = Binance::new_with_config(None, None, &Config::testnet());
+    let market: Market = Binance::new_with_config(None, None, &Config::testnet());
+    let symbol = ExchangeInfoCache::new(general, Duration::from_secs(60)).symbol(SYMBOL).await?;
+    let (tick_size, step_size) = symbol.filters.iter().fold((0.01, 0.00001), |sizes, filter| match filter {
+        Filters::PriceFilter { tick_size, .. } => (*tick_size, sizes.1),
+        Filters::LotSize { step_size, .. } => (sizes.0, *step_size),
+        _ => sizes,
+    });
+    // Far enough below the market not to fill, within the price filters
+    let price = round_to(market.get_price(SYMBOL).await?.price * 0.8, tick_size);
+    let quantity = round_to(20.0 / price, step_size) + step_size;
+
+    let placed = account
+        .place_order(OrderRequest {
+            symbol: SYMBOL.to_string(),
+            side: OrderSide::Buy,
+            order_type: OrderType::Limit,
+            time_in_force: Some(TimeInForce::GTC),
+            quantity: Some(round_to(quantity, step_size)),
+            price: Some(price),
+            ..OrderRequest::default()
+        })
+        .await?;
+    assert!(matches!(placed.status, OrderStatus::New), "{:?}", placed);
+
+    let order = account
+        .order_status(OrderStatusRequest {
+            symbol: SYMBOL.to_string(),
+            order_id: Some(placed.order_id),
+            ..OrderStatusRequest::default()
+        })
+        .await?;
+    assert_eq!(order.client_order_id, placed.client_order_id);
+    assert_eq!(order.price, price);
+
+    let canceled = account
+        .cancel_order(OrderCancellation {
+            symbol: SYMBOL.to_string(),
+            order_id: Some(placed.order_id),
+            ..OrderCancellation::default()
+        })
+        .await?;
+    assert!(matches!(canceled.status, OrderStatus::Canceled), "{:?}", canceled);
+    assert!(!canceled.was_partially_filled());
+    Ok(())
+}
+
+#[tokio::test]
+async fn websocket_stream() {
+    // Public, but skipped along with the rest of the suite
+    if account().is_none() {
+        return;
+    }
+    let running = AtomicBool::new(true);
+    let mut events = vec![];
+    let mut web_socket = WebSockets::new_with_options(
+        |event: CombinedStreamEvent<WebsocketEventUntag>| {
+            events.push(event);
+            running.store(false, Ordering::Relaxed);
+            Ok(())
+        },
+        Config::testnet(),
+    );
+    let stream = partial_book_depth_stream("btcusdt", DepthLevels::Five, UpdateSpeed::Ms100);
+    web_socket.connect(&stream).await.unwrap();
+    tokio::time::timeout(Duration::from_secs(30), web_socket.event_loop(&running))
+        .await
+        .expect("no depth snapshot within 30s")
+        .unwrap();
+    web_socket.close().await.unwrap();
+    drop(web_socket);
+
+    assert_eq!(events[0].stream(), stream);
+    assert!(
+        matches!(&events[0].data, WebsocketEventUntag::PartialDepthOrderBook(depth) if depth.symbol == SYMBOL),
+        "{:?}",
+        events[0].data
+    );
+}